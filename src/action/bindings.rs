@@ -0,0 +1,187 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use super::state::Action;
+
+/// A single physical input that can be bound to an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputSource {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    GamepadButton(GamepadButtonType),
+}
+
+/// Maps devices to actions. Replace or edit this resource to remap the controls.
+#[derive(Resource, Debug, Clone)]
+pub struct InputBindings {
+    /// The sources that press each button action.
+    pub buttons: HashMap<Action, Vec<InputSource>>,
+    /// Sources that turn the ship anticlockwise.
+    pub rotate_left: Vec<InputSource>,
+    /// Sources that turn the ship clockwise.
+    pub rotate_right: Vec<InputSource>,
+    /// Gamepad stick that turns the ship.
+    pub rotate_axis: Option<GamepadAxisType>,
+    /// Whether moving the mouse points the ship at the cursor.
+    pub mouse_aim: bool,
+    /// The only gamepad these bindings listen to, by id, or `None` for every gamepad.
+    pub gamepad: Option<usize>,
+}
+
+impl Default for InputBindings {
+    fn default() -> InputBindings {
+        use InputSource::*;
+
+        InputBindings {
+            buttons: HashMap::from_iter([
+                (
+                    Action::Thrust,
+                    vec![
+                        Key(KeyCode::W),
+                        Key(KeyCode::Up),
+                        Mouse(MouseButton::Right),
+                        GamepadButton(GamepadButtonType::RightTrigger2),
+                    ],
+                ),
+                (
+                    Action::Fire,
+                    vec![
+                        Key(KeyCode::Space),
+                        Mouse(MouseButton::Left),
+                        GamepadButton(GamepadButtonType::South),
+                    ],
+                ),
+                (
+                    Action::Pause,
+                    vec![
+                        Key(KeyCode::Escape),
+                        Key(KeyCode::P),
+                        GamepadButton(GamepadButtonType::Start),
+                    ],
+                ),
+                (
+                    Action::Confirm,
+                    vec![
+                        Key(KeyCode::Return),
+                        Key(KeyCode::NumpadEnter),
+                        GamepadButton(GamepadButtonType::South),
+                    ],
+                ),
+            ]),
+            rotate_left: vec![
+                Key(KeyCode::A),
+                Key(KeyCode::Left),
+                GamepadButton(GamepadButtonType::DPadLeft),
+            ],
+            rotate_right: vec![
+                Key(KeyCode::D),
+                Key(KeyCode::Right),
+                GamepadButton(GamepadButtonType::DPadRight),
+            ],
+            rotate_axis: Some(GamepadAxisType::LeftStickX),
+            mouse_aim: true,
+            gamepad: None,
+        }
+    }
+}
+
+/// The controls of each player when several share the screen, in player order.
+///
+/// They split the keyboard between them and get a gamepad each. Only the first player has the
+/// mouse. A game with a single player uses [`InputBindings`] instead.
+#[derive(Resource, Debug, Clone)]
+pub struct LocalBindings {
+    pub players: Vec<InputBindings>,
+}
+
+impl Default for LocalBindings {
+    fn default() -> LocalBindings {
+        use InputSource::*;
+
+        let player_one = InputBindings {
+            buttons: HashMap::from_iter([
+                (
+                    Action::Thrust,
+                    vec![
+                        Key(KeyCode::W),
+                        Mouse(MouseButton::Right),
+                        GamepadButton(GamepadButtonType::RightTrigger2),
+                    ],
+                ),
+                (
+                    Action::Fire,
+                    vec![
+                        Key(KeyCode::Space),
+                        Mouse(MouseButton::Left),
+                        GamepadButton(GamepadButtonType::South),
+                    ],
+                ),
+                (
+                    Action::Pause,
+                    vec![
+                        Key(KeyCode::Escape),
+                        GamepadButton(GamepadButtonType::Start),
+                    ],
+                ),
+                (
+                    Action::Confirm,
+                    vec![
+                        Key(KeyCode::Return),
+                        GamepadButton(GamepadButtonType::South),
+                    ],
+                ),
+            ]),
+            rotate_left: vec![Key(KeyCode::A), GamepadButton(GamepadButtonType::DPadLeft)],
+            rotate_right: vec![Key(KeyCode::D), GamepadButton(GamepadButtonType::DPadRight)],
+            rotate_axis: Some(GamepadAxisType::LeftStickX),
+            mouse_aim: true,
+            gamepad: Some(0),
+        };
+
+        let player_two = InputBindings {
+            buttons: HashMap::from_iter([
+                (
+                    Action::Thrust,
+                    vec![
+                        Key(KeyCode::Up),
+                        GamepadButton(GamepadButtonType::RightTrigger2),
+                    ],
+                ),
+                (
+                    Action::Fire,
+                    vec![
+                        Key(KeyCode::RControl),
+                        Key(KeyCode::Numpad0),
+                        GamepadButton(GamepadButtonType::South),
+                    ],
+                ),
+                (
+                    Action::Pause,
+                    vec![Key(KeyCode::P), GamepadButton(GamepadButtonType::Start)],
+                ),
+                (
+                    Action::Confirm,
+                    vec![
+                        Key(KeyCode::NumpadEnter),
+                        GamepadButton(GamepadButtonType::South),
+                    ],
+                ),
+            ]),
+            rotate_left: vec![
+                Key(KeyCode::Left),
+                GamepadButton(GamepadButtonType::DPadLeft),
+            ],
+            rotate_right: vec![
+                Key(KeyCode::Right),
+                GamepadButton(GamepadButtonType::DPadRight),
+            ],
+            rotate_axis: Some(GamepadAxisType::LeftStickX),
+            mouse_aim: false,
+            gamepad: Some(1),
+        };
+
+        LocalBindings {
+            players: vec![player_one, player_two],
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod bindings;
+pub mod plugin;
+pub mod state;
+
+mod system;
@@ -0,0 +1,34 @@
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+
+use super::bindings::{InputBindings, LocalBindings};
+use super::state::{ActionState, TickInput};
+use super::system::*;
+
+/// Translates mouse, keyboard and gamepad input into [`Action`](super::state::Action)s.
+pub struct ActionPlugin;
+
+/// Runs at the start of every fixed tick, before any gameplay system reads [`TickInput`].
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct SampleActions;
+
+impl Plugin for ActionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputBindings>()
+            .init_resource::<LocalBindings>()
+            .init_resource::<ActionState>()
+            .init_resource::<TickInput>()
+            // Without input devices (e.g. in tests) the action state is driven by hand
+            .add_system(
+                collect_actions
+                    .run_if(resource_exists::<Input<KeyCode>>())
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(InputSystem),
+            )
+            .add_system(
+                sample_tick_actions
+                    .in_set(SampleActions)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            );
+    }
+}
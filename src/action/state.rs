@@ -0,0 +1,235 @@
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+/// Something the player can do, independent of the device used to do it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Turns the ship. An axis rather than a button: see [`TickActions::rotation`].
+    Rotate,
+    Thrust,
+    Fire,
+    Pause,
+    Confirm,
+}
+
+/// The live state of every action, rebuilt from the input devices each frame.
+///
+/// There is one set of actions per player, and the first player's also stands in for "the
+/// player" wherever only one is playing. Menus read this directly, and take a press from any
+/// player. The simulation reads [`TickInput`] instead, so that a press is seen by exactly one
+/// tick however the frame rate and tick rate line up. Tests can drive the game without real
+/// devices by calling [`ActionState::press`] and [`ActionState::release`].
+#[derive(Resource, Debug)]
+pub struct ActionState {
+    players: Vec<PlayerActions>,
+}
+
+impl Default for ActionState {
+    fn default() -> ActionState {
+        ActionState {
+            players: vec![PlayerActions::default()],
+        }
+    }
+}
+
+impl ActionState {
+    pub fn players(&self) -> usize {
+        self.players.len()
+    }
+
+    /// Starts tracking `players` players, each with their own bindings, from nothing pressed.
+    pub fn set_players(&mut self, players: usize) {
+        self.players.clear();
+        self.players
+            .resize_with(players.max(1), PlayerActions::default);
+    }
+
+    pub fn player_mut(&mut self, index: usize) -> &mut PlayerActions {
+        &mut self.players[index]
+    }
+
+    /// Whether any player pressed the action this frame.
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.players
+            .iter()
+            .any(|player| player.just_pressed.contains(&action))
+    }
+
+    /// Presses the action for the first player.
+    #[cfg(test)]
+    pub fn press(&mut self, action: Action) {
+        self.player_mut(0).press(action);
+    }
+
+    /// Releases the action for the first player.
+    #[cfg(test)]
+    pub fn release(&mut self, action: Action) {
+        self.player_mut(0).release(action);
+    }
+
+    pub(super) fn start_frame(&mut self) {
+        for player in &mut self.players {
+            player.just_pressed.clear();
+        }
+    }
+
+    /// Hands everything that happened since the last tick over to the simulation.
+    pub(super) fn consume(&mut self) -> TickInput {
+        TickInput {
+            players: self
+                .players
+                .iter_mut()
+                .map(PlayerActions::consume)
+                .collect(),
+        }
+    }
+}
+
+/// One player's live actions, inside [`ActionState`].
+#[derive(Default, Debug)]
+pub struct PlayerActions {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    unconsumed: HashSet<Action>,
+    rotation: f32,
+    aim: Option<Vec2>,
+}
+
+impl PlayerActions {
+    pub fn press(&mut self, action: Action) {
+        if self.pressed.insert(action) {
+            self.just_pressed.insert(action);
+            self.unconsumed.insert(action);
+        }
+    }
+
+    pub fn release(&mut self, action: Action) {
+        self.pressed.remove(&action);
+    }
+
+    pub fn set_rotation(&mut self, rotation: f32) {
+        self.rotation = rotation.clamp(-1.0, 1.0);
+    }
+
+    /// Points the ship at a world position on the next tick.
+    pub fn aim_at(&mut self, target: Vec2) {
+        self.aim = Some(target);
+    }
+
+    fn consume(&mut self) -> TickActions {
+        TickActions {
+            pressed: self.pressed.clone(),
+            just_pressed: self.unconsumed.drain().collect(),
+            rotation: self.rotation,
+            aim: self.aim.take(),
+        }
+        .quantized()
+    }
+}
+
+/// Every player's actions for a single simulation tick, in player order.
+#[derive(Resource, Default, Debug, Clone, PartialEq)]
+pub struct TickInput {
+    pub players: Vec<TickActions>,
+}
+
+impl TickInput {
+    /// The actions of the player at `index`, if there is anyone playing there.
+    pub fn player(&self, index: usize) -> Option<&TickActions> {
+        self.players.get(index)
+    }
+}
+
+/// One player's actions for a single simulation tick.
+#[derive(Resource, Default, Debug, Clone, PartialEq)]
+pub struct TickActions {
+    pub pressed: HashSet<Action>,
+    /// Pressed since the previous tick, even if already released again.
+    pub just_pressed: HashSet<Action>,
+    /// Turn rate from -1.0 (clockwise) to 1.0 (anticlockwise).
+    pub rotation: f32,
+    /// Where the mouse asked the ship to point, if it moved since the previous tick.
+    pub aim: Option<Vec2>,
+}
+
+impl TickActions {
+    pub fn pressed(&self, action: Action) -> bool {
+        match action {
+            Action::Rotate => self.rotation != 0.0,
+            _ => self.pressed.contains(&action),
+        }
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    /// Rounds the analog values to the precision stored in replays, so a live game and its
+    /// replay see bit-identical input.
+    pub fn quantized(mut self) -> TickActions {
+        self.rotation = dequantize_rotation(quantize_rotation(self.rotation));
+        self.aim = self.aim.map(|aim| aim.round());
+        self
+    }
+}
+
+const ROTATION_STEPS: f32 = i8::MAX as f32;
+
+/// Maps a rotation in `-1.0..=1.0` onto the `i8` steps used by [`TickActions::quantized`].
+pub fn quantize_rotation(rotation: f32) -> i8 {
+    (rotation.clamp(-1.0, 1.0) * ROTATION_STEPS).round() as i8
+}
+
+/// Inverse of [`quantize_rotation`].
+pub fn dequantize_rotation(steps: i8) -> f32 {
+    steps as f32 / ROTATION_STEPS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_tap_between_ticks_is_seen_by_one_tick() {
+        let mut state = ActionState::default();
+
+        state.press(Action::Fire);
+        state.release(Action::Fire);
+
+        let tick = state.consume();
+        assert!(tick.players[0].just_pressed(Action::Fire));
+        assert!(!tick.players[0].pressed(Action::Fire));
+
+        let tick = state.consume();
+        assert!(!tick.players[0].just_pressed(Action::Fire));
+    }
+
+    #[test]
+    fn holding_a_button_only_presses_it_once() {
+        let mut state = ActionState::default();
+
+        state.press(Action::Thrust);
+        assert!(state.consume().players[0].just_pressed(Action::Thrust));
+
+        state.start_frame();
+        state.press(Action::Thrust);
+        assert!(!state.just_pressed(Action::Thrust));
+
+        let tick = state.consume();
+        assert!(tick.players[0].pressed(Action::Thrust));
+        assert!(!tick.players[0].just_pressed(Action::Thrust));
+    }
+
+    #[test]
+    fn each_player_has_their_own_actions() {
+        let mut state = ActionState::default();
+        state.set_players(2);
+
+        state.player_mut(1).press(Action::Fire);
+        assert!(state.just_pressed(Action::Fire));
+
+        let tick = state.consume();
+        assert!(!tick.players[0].pressed(Action::Fire));
+        assert!(tick.players[1].pressed(Action::Fire));
+    }
+}
@@ -0,0 +1,122 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::camera::plugin::MainCamera;
+
+use super::bindings::{InputBindings, InputSource, LocalBindings};
+use super::state::{Action, ActionState, PlayerActions, TickInput};
+
+const BUTTON_ACTIONS: [Action; 4] = [Action::Thrust, Action::Fire, Action::Pause, Action::Confirm];
+
+#[derive(SystemParam)]
+pub struct Devices<'w> {
+    keyboard_input: Res<'w, Input<KeyCode>>,
+    mouse_buttons: Res<'w, Input<MouseButton>>,
+    gamepads: Res<'w, Gamepads>,
+    gamepad_buttons: Res<'w, Input<GamepadButton>>,
+    gamepad_axes: Res<'w, Axis<GamepadAxis>>,
+}
+
+impl Devices<'_> {
+    /// The connected gamepads that `bindings` listen to.
+    fn gamepads<'a>(&'a self, bindings: &'a InputBindings) -> impl Iterator<Item = Gamepad> + 'a {
+        self.gamepads
+            .iter()
+            .filter(|gamepad| bindings.gamepad.is_none_or(|id| gamepad.id == id))
+    }
+
+    fn pressed(&self, bindings: &InputBindings, source: &InputSource) -> bool {
+        match *source {
+            InputSource::Key(key) => self.keyboard_input.pressed(key),
+            InputSource::Mouse(button) => self.mouse_buttons.pressed(button),
+            InputSource::GamepadButton(button_type) => self.gamepads(bindings).any(|gamepad| {
+                self.gamepad_buttons
+                    .pressed(GamepadButton::new(gamepad, button_type))
+            }),
+        }
+    }
+}
+
+pub fn collect_actions(
+    bindings: Res<InputBindings>,
+    local_bindings: Res<LocalBindings>,
+    mut action_state: ResMut<ActionState>,
+    devices: Devices,
+    mut cursor_moved_reader: EventReader<CursorMoved>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+) {
+    action_state.start_frame();
+
+    // The camera scales the playfield to the window, so it has to map the cursor back
+    let aim = cursor_moved_reader
+        .iter()
+        .last()
+        .zip(camera_query.get_single().ok())
+        .and_then(|(cursor_moved, (camera, camera_transform))| {
+            camera.viewport_to_world_2d(camera_transform, cursor_moved.position)
+        });
+
+    // A single player gets every device, several share them out
+    let players = action_state.players();
+    for index in 0..players {
+        let bindings = match players {
+            1 => &*bindings,
+            _ => match local_bindings.players.get(index) {
+                Some(bindings) => bindings,
+                None => continue,
+            },
+        };
+        collect_player_actions(action_state.player_mut(index), bindings, &devices, aim);
+    }
+}
+
+fn collect_player_actions(
+    actions: &mut PlayerActions,
+    bindings: &InputBindings,
+    devices: &Devices,
+    aim: Option<Vec2>,
+) {
+    let is_pressed = |source: &InputSource| devices.pressed(bindings, source);
+
+    for action in BUTTON_ACTIONS {
+        let pressed = bindings
+            .buttons
+            .get(&action)
+            .is_some_and(|sources| sources.iter().any(is_pressed));
+
+        if pressed {
+            actions.press(action);
+        } else {
+            actions.release(action);
+        }
+    }
+
+    let mut rotation = 0.0;
+    if bindings.rotate_left.iter().any(is_pressed) {
+        rotation += 1.0;
+    }
+    if bindings.rotate_right.iter().any(is_pressed) {
+        rotation -= 1.0;
+    }
+    if let Some(axis_type) = bindings.rotate_axis {
+        for gamepad in devices.gamepads(bindings) {
+            // Pushing the stick right turns the ship clockwise
+            rotation -= devices
+                .gamepad_axes
+                .get(GamepadAxis::new(gamepad, axis_type))
+                .unwrap_or(0.0);
+        }
+    }
+    actions.set_rotation(rotation);
+
+    if let (true, Some(aim)) = (bindings.mouse_aim, aim) {
+        actions.aim_at(aim);
+    }
+}
+
+pub fn sample_tick_actions(
+    mut action_state: ResMut<ActionState>,
+    mut tick_input: ResMut<TickInput>,
+) {
+    *tick_input = action_state.consume();
+}
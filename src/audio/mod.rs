@@ -0,0 +1,7 @@
+pub mod plugin;
+pub mod settings;
+pub mod sound;
+
+mod system;
+#[cfg(test)]
+mod tests;
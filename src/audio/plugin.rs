@@ -0,0 +1,51 @@
+use std::marker::PhantomData;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::game::plugin::GameSet;
+
+use super::settings::AudioSettings;
+use super::sound::{SoundCue, SoundPlayer};
+use super::system::*;
+
+/// Plays sound effects for what happens in the game and menus, and music for each screen.
+///
+/// `P` is the [`SoundPlayer`] that does the playing, which is the audio device unless a test
+/// swaps in its own.
+pub struct SoundPlugin<P = DevicePlayer<'static>> {
+    player: PhantomData<fn() -> P>,
+}
+
+impl Default for SoundPlugin {
+    fn default() -> SoundPlugin {
+        SoundPlugin::with_player()
+    }
+}
+
+impl<P> SoundPlugin<P> {
+    /// Plays sounds through `P` instead of the audio device.
+    pub fn with_player() -> SoundPlugin<P> {
+        SoundPlugin {
+            player: PhantomData,
+        }
+    }
+}
+
+impl<P> Plugin for SoundPlugin<P>
+where
+    P: SystemParam + 'static,
+    for<'w, 's> P::Item<'w, 's>: SoundPlayer,
+{
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AudioSettings>()
+            .init_resource::<MusicSink>()
+            .add_event::<SoundCue>()
+            .add_system(
+                game_event_sounds
+                    .after(GameSet::Updates)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_systems((button_click_sounds, play_sounds::<P>, play_music::<P>).chain());
+    }
+}
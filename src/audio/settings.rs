@@ -0,0 +1,31 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How loud the game is. Each volume runs from 0 (silent) to 1 (full).
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master: f32,
+    pub sfx: f32,
+    pub music: f32,
+}
+impl Default for AudioSettings {
+    fn default() -> AudioSettings {
+        AudioSettings {
+            master: 1.0,
+            sfx: 0.8,
+            music: 0.5,
+        }
+    }
+}
+impl AudioSettings {
+    /// The volume sound effects play at, with the master volume applied.
+    pub fn sfx_volume(&self) -> f32 {
+        (self.master * self.sfx).clamp(0.0, 1.0)
+    }
+
+    /// The volume music plays at, with the master volume applied.
+    pub fn music_volume(&self) -> f32 {
+        (self.master * self.music).clamp(0.0, 1.0)
+    }
+}
@@ -0,0 +1,64 @@
+use crate::global::state::AppState;
+
+/// A one-off sound effect, sent as an event to have it played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundCue {
+    Fire,
+    AsteroidDestroyed,
+    PlayerHit,
+    GameOver,
+    Click,
+}
+impl SoundCue {
+    pub fn path(&self) -> &'static str {
+        match self {
+            SoundCue::Fire => "sounds/laser.wav",
+            SoundCue::AsteroidDestroyed => "sounds/explosion.wav",
+            SoundCue::PlayerHit => "sounds/player_hit.wav",
+            SoundCue::GameOver => "sounds/game_over.wav",
+            SoundCue::Click => "sounds/click.wav",
+        }
+    }
+}
+
+/// A piece of background music, looped for as long as it is wanted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MusicTrack {
+    Menu,
+    Game,
+}
+impl MusicTrack {
+    pub fn path(&self) -> &'static str {
+        match self {
+            MusicTrack::Menu => "sounds/music_menu.wav",
+            MusicTrack::Game => "sounds/music_game.wav",
+        }
+    }
+
+    /// The music that goes with a screen, or `None` to carry on with whatever was playing. The
+    /// game's music carries on through the pause menu.
+    pub fn for_state(state: AppState) -> Option<MusicTrack> {
+        match state {
+            AppState::MainMenu | AppState::GameOver | AppState::HighScores => {
+                Some(MusicTrack::Menu)
+            }
+            AppState::InGame | AppState::Paused | AppState::Online => Some(MusicTrack::Game),
+            // Rounds of a match follow on from each other without a change of music
+            AppState::Settings | AppState::RoundResults => None,
+        }
+    }
+}
+
+/// Whatever turns requested sounds into noise.
+///
+/// The game only decides which sounds it wants and how loud. Implementations are system
+/// parameters, so the real one can reach the audio device and tests can simply write the
+/// requests down.
+pub trait SoundPlayer {
+    fn play(&mut self, cue: SoundCue, volume: f32);
+
+    /// Starts `track` looping in place of whatever music was playing, or stops the music.
+    fn play_music(&mut self, track: Option<MusicTrack>, volume: f32);
+
+    fn set_music_volume(&mut self, volume: f32);
+}
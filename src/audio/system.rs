@@ -0,0 +1,125 @@
+use bevy::ecs::system::{StaticSystemParam, SystemParam};
+use bevy::prelude::*;
+
+use crate::game::event::{AsteroidDestroyedEvent, FireEvent, PlayerHitEvent};
+use crate::global::event::GameOverEvent;
+use crate::global::state::AppState;
+
+use super::settings::AudioSettings;
+use super::sound::{MusicTrack, SoundCue, SoundPlayer};
+
+type InteractionQualifiers = (Changed<Interaction>, With<Button>);
+
+pub fn game_event_sounds(
+    mut fire_reader: EventReader<FireEvent>,
+    mut destroyed_reader: EventReader<AsteroidDestroyedEvent>,
+    mut player_hit_reader: EventReader<PlayerHitEvent>,
+    mut game_over_reader: EventReader<GameOverEvent>,
+    mut cue_writer: EventWriter<SoundCue>,
+) {
+    for _ in fire_reader.iter() {
+        cue_writer.send(SoundCue::Fire);
+    }
+    for _ in destroyed_reader.iter() {
+        cue_writer.send(SoundCue::AsteroidDestroyed);
+    }
+    for _ in player_hit_reader.iter() {
+        cue_writer.send(SoundCue::PlayerHit);
+    }
+    for _ in game_over_reader.iter() {
+        cue_writer.send(SoundCue::GameOver);
+    }
+}
+
+pub fn button_click_sounds(
+    interaction_query: Query<&Interaction, InteractionQualifiers>,
+    mut cue_writer: EventWriter<SoundCue>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            cue_writer.send(SoundCue::Click);
+        }
+    }
+}
+
+pub fn play_sounds<P: SystemParam>(
+    settings: Res<AudioSettings>,
+    mut cue_reader: EventReader<SoundCue>,
+    mut player: StaticSystemParam<P>,
+) where
+    for<'w, 's> P::Item<'w, 's>: SoundPlayer,
+{
+    for cue in cue_reader.iter() {
+        player.play(*cue, settings.sfx_volume());
+    }
+}
+
+/// Switches the music to the one for the current screen, and keeps its volume up to date.
+pub fn play_music<P: SystemParam>(
+    state: Res<State<AppState>>,
+    settings: Res<AudioSettings>,
+    mut current: Local<Option<MusicTrack>>,
+    mut player: StaticSystemParam<P>,
+) where
+    for<'w, 's> P::Item<'w, 's>: SoundPlayer,
+{
+    let wanted = MusicTrack::for_state(state.0).or(*current);
+    if wanted != *current {
+        player.play_music(wanted, settings.music_volume());
+        *current = wanted;
+    } else if settings.is_changed() {
+        player.set_music_volume(settings.music_volume());
+    }
+}
+
+/// The music playing through the audio device, if any.
+#[derive(Resource, Default)]
+pub struct MusicSink(Option<Handle<AudioSink>>);
+
+/// Plays sounds through Bevy's audio output.
+#[derive(SystemParam)]
+pub struct DevicePlayer<'w> {
+    audio: Res<'w, Audio>,
+    asset_server: Res<'w, AssetServer>,
+    sinks: Res<'w, Assets<AudioSink>>,
+    music: ResMut<'w, MusicSink>,
+}
+
+impl SoundPlayer for DevicePlayer<'_> {
+    fn play(&mut self, cue: SoundCue, volume: f32) {
+        self.audio.play_with_settings(
+            self.asset_server.load(cue.path()),
+            PlaybackSettings::ONCE.with_volume(volume),
+        );
+    }
+
+    fn play_music(&mut self, track: Option<MusicTrack>, volume: f32) {
+        if let Some(sink) = self
+            .music
+            .0
+            .take()
+            .and_then(|handle| self.sinks.get(&handle))
+        {
+            sink.stop();
+        }
+        if let Some(track) = track {
+            let sink = self.audio.play_with_settings(
+                self.asset_server.load(track.path()),
+                PlaybackSettings::LOOP.with_volume(volume),
+            );
+            // The sink only exists once the audio output has started it, so hold on to it
+            self.music.0 = Some(self.sinks.get_handle(sink));
+        }
+    }
+
+    fn set_music_volume(&mut self, volume: f32) {
+        if let Some(sink) = self
+            .music
+            .0
+            .as_ref()
+            .and_then(|handle| self.sinks.get(handle))
+        {
+            sink.set_volume(volume);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+
+use crate::action::state::Action;
+use crate::game::components::AsteroidSize;
+use crate::global::state::AppState;
+use crate::testing::Harness;
+
+use super::plugin::SoundPlugin;
+use super::settings::AudioSettings;
+use super::sound::{MusicTrack, SoundCue, SoundPlayer};
+
+const SEED: u64 = 7;
+
+/// Every sound requested, in order, instead of playing them.
+#[derive(Resource, Default)]
+struct SoundLog {
+    sounds: Vec<SoundCue>,
+    music: Option<MusicTrack>,
+    music_volume: f32,
+}
+
+impl SoundPlayer for ResMut<'_, SoundLog> {
+    fn play(&mut self, cue: SoundCue, _volume: f32) {
+        self.sounds.push(cue);
+    }
+
+    fn play_music(&mut self, track: Option<MusicTrack>, volume: f32) {
+        self.music = track;
+        self.music_volume = volume;
+    }
+
+    fn set_music_volume(&mut self, volume: f32) {
+        self.music_volume = volume;
+    }
+}
+
+fn harness() -> Harness {
+    Harness::build(SEED, |app| {
+        app.init_resource::<SoundLog>()
+            .add_plugin(SoundPlugin::<ResMut<'static, SoundLog>>::with_player());
+    })
+}
+
+fn log(harness: &mut Harness) -> Mut<'_, SoundLog> {
+    harness.app.world.resource_mut::<SoundLog>()
+}
+
+#[test]
+fn shooting_an_asteroid_plays_the_laser_then_the_explosion() {
+    let mut harness = harness();
+    harness.start_game();
+
+    harness.spawn_asteroid(AsteroidSize::Small, Vec2::new(0.0, 150.0));
+    harness.actions().press(Action::Fire);
+    harness.step();
+    harness.actions().release(Action::Fire);
+    harness.run_ticks(30);
+
+    assert_eq!(
+        log(&mut harness).sounds,
+        vec![SoundCue::Fire, SoundCue::AsteroidDestroyed]
+    );
+}
+
+#[test]
+fn losing_the_last_life_plays_the_hit_and_game_over_sounds() {
+    let mut harness = harness();
+    harness.start_game();
+    harness.player().lives = 1;
+
+    harness.spawn_asteroid(AsteroidSize::Large, Vec2::ZERO);
+    harness.step();
+
+    assert_eq!(
+        log(&mut harness).sounds,
+        vec![SoundCue::PlayerHit, SoundCue::GameOver]
+    );
+}
+
+#[test]
+fn music_follows_the_screen_and_the_volume() {
+    let mut harness = harness();
+    assert_eq!(log(&mut harness).music, Some(MusicTrack::Menu));
+
+    harness.start_game();
+    assert_eq!(log(&mut harness).music, Some(MusicTrack::Game));
+    harness.enter(AppState::Paused);
+    assert_eq!(log(&mut harness).music, Some(MusicTrack::Game));
+
+    harness.app.world.resource_mut::<AudioSettings>().music = 0.25;
+    harness.step();
+    assert_eq!(log(&mut harness).music_volume, 0.25);
+}
@@ -0,0 +1,50 @@
+//! The dedicated server: runs online games with no window, renderer or assets, and logs what
+//! happens in them to standard output, one `key=value` line per event.
+//!
+//! `cargo run --bin server -- --port 7450 --tick-rate 60 --seed 7 --max-players 2`
+
+use std::time::Duration;
+
+use asteroids::game::components::SeedConfig;
+use asteroids::global::cli::ServerOptions;
+use asteroids::net::server::{MatchEvent, NetServer, ServerPlugin};
+use asteroids::AsteroidsPlugins;
+
+use bevy::app::ScheduleRunnerSettings;
+use bevy::prelude::*;
+
+fn main() {
+    let options = ServerOptions::from_env();
+    let server = match NetServer::bind(("0.0.0.0", options.port), options.mode()) {
+        Ok(server) => server,
+        Err(error) => {
+            eprintln!("Can't listen on port {}: {}", options.port, error);
+            std::process::exit(1);
+        }
+    };
+    println!(
+        "event=listening port={} tick_rate={} max_players={}",
+        options.port, options.tick_rate, options.max_players
+    );
+
+    App::new()
+        .add_plugins(MinimalPlugins)
+        // Nothing is drawn, so frames only need to come often enough for every tick
+        .insert_resource(ScheduleRunnerSettings::run_loop(Duration::from_secs_f32(
+            0.5 / options.tick_rate,
+        )))
+        .insert_resource(SeedConfig {
+            fixed_seed: options.seed,
+        })
+        .add_plugins(AsteroidsPlugins::headless().with_tick_rate(options.tick_rate))
+        .add_plugin(ServerPlugin)
+        .insert_resource(server)
+        .add_system(log_match_events)
+        .run();
+}
+
+fn log_match_events(time: Res<Time>, mut event_reader: EventReader<MatchEvent>) {
+    for event in event_reader.iter() {
+        println!("time={:.3} {}", time.elapsed_seconds(), event);
+    }
+}
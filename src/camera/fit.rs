@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How the playfield is fitted to a window of a different shape, chosen in the settings.
+///
+/// The camera always keeps the whole playfield in view without stretching it, so resizing the
+/// window or going fullscreen only changes how big things are drawn.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScreenFit {
+    /// Shows only the playfield, with bars over the rest of the window.
+    #[default]
+    Letterbox,
+    /// Fills the window, showing things drifting in and out past the playfield edges.
+    Expand,
+}
+
+impl ScreenFit {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScreenFit::Letterbox => "Letterbox",
+            ScreenFit::Expand => "Expand",
+        }
+    }
+}
+
+/// One of the bars covering the world outside the playfield.
+#[derive(Component)]
+pub struct LetterboxBar;
+
+/// How much the UI should be scaled for a window of `window` size to match the playfield.
+pub fn ui_scale(window: Vec2, playfield: Vec2) -> f64 {
+    (window / playfield).min_element().max(0.1) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ui_scales_with_the_shorter_side() {
+        let playfield = Vec2::new(800.0, 800.0);
+
+        assert_eq!(ui_scale(Vec2::new(800.0, 800.0), playfield), 1.0);
+        assert_eq!(ui_scale(Vec2::new(1920.0, 1200.0), playfield), 1.5);
+        assert_eq!(ui_scale(Vec2::new(400.0, 900.0), playfield), 0.5);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod fit;
+pub mod plugin;
+pub mod shake;
+
+mod system;
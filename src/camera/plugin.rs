@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+
+use crate::game::plugin::GameSet;
+
+use super::fit::ScreenFit;
+use super::shake::ScreenShake;
+use super::system::*;
+
+/// Sets up the camera that looks at the playfield, fits it and the UI to the window, and
+/// shakes it when things get rough.
+pub struct CameraPlugin;
+
+/// The camera the playfield is drawn with.
+#[derive(Component)]
+pub struct MainCamera;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScreenShake>()
+            .init_resource::<ScreenFit>()
+            .add_startup_systems((setup_camera, setup_letterbox))
+            .add_system(
+                shake_on_impacts
+                    .after(GameSet::Updates)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_systems((
+                apply_screen_shake,
+                fit_ui_to_window,
+                show_letterbox.run_if(resource_changed::<ScreenFit>()),
+            ));
+    }
+}
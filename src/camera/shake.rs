@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+
+/// Furthest the camera is thrown off centre by the hardest shake, in pixels.
+const MAX_OFFSET: f32 = 12.0;
+/// How much trauma wears off per second.
+const DECAY: f32 = 1.5;
+
+/// Shakes the camera after hits and explosions.
+///
+/// Impacts add trauma, which wears off over time. The shake grows with the square of the
+/// trauma, so small knocks barely register while big ones stack up into a jolt.
+#[derive(Resource, Debug)]
+pub struct ScreenShake {
+    pub enabled: bool,
+    trauma: f32,
+}
+
+impl Default for ScreenShake {
+    fn default() -> ScreenShake {
+        ScreenShake {
+            enabled: true,
+            trauma: 0.0,
+        }
+    }
+}
+
+impl ScreenShake {
+    /// Adds to the shake, up to a full jolt of 1. Does nothing while shaking is turned off.
+    pub fn add_trauma(&mut self, amount: f32) {
+        if self.enabled {
+            self.trauma = (self.trauma + amount).min(1.0);
+        }
+    }
+
+    pub fn decay(&mut self, delta: f32) {
+        self.trauma = (self.trauma - DECAY * delta).max(0.0);
+    }
+
+    /// Where the camera sits at `time` seconds, relative to the centre of the playfield.
+    pub fn offset(&self, time: f32) -> Vec2 {
+        // Two sine waves at unrelated frequencies wander about without visibly repeating
+        let wobble = Vec2::new((time * 41.0).sin(), (time * 53.0).cos());
+        wobble * self.trauma * self.trauma * MAX_OFFSET
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trauma_builds_up_and_wears_off() {
+        let mut shake = ScreenShake::default();
+        shake.add_trauma(0.6);
+        shake.add_trauma(0.6);
+        assert_eq!(shake.trauma, 1.0);
+        assert!(shake.offset(0.3).length() > 0.0);
+
+        shake.decay(1.0 / DECAY);
+        assert_eq!(shake.trauma, 0.0);
+        assert_eq!(shake.offset(0.3), Vec2::ZERO);
+    }
+
+    #[test]
+    fn nothing_shakes_while_turned_off() {
+        let mut shake = ScreenShake {
+            enabled: false,
+            ..default()
+        };
+        shake.add_trauma(1.0);
+
+        assert_eq!(shake.trauma, 0.0);
+    }
+}
@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+use bevy::render::camera::ScalingMode;
+use bevy::ui::UiScale;
+use bevy::window::PrimaryWindow;
+
+use crate::game::components::Playfield;
+use crate::game::event::{AsteroidDestroyedEvent, PlayerHitEvent};
+
+use super::fit::{ui_scale, LetterboxBar, ScreenFit};
+use super::plugin::MainCamera;
+use super::shake::ScreenShake;
+
+/// In front of everything in the world, but within the camera's view.
+const LETTERBOX_Z: f32 = 900.0;
+const LETTERBOX_COLOR: Color = Color::BLACK;
+
+pub fn setup_camera(mut commands: Commands, playfield: Res<Playfield>) {
+    let mut camera = Camera2dBundle::default();
+    // Always show at least the whole playfield, widening the view to the window's shape
+    camera.projection.scaling_mode = ScalingMode::AutoMin {
+        min_width: playfield.width,
+        min_height: playfield.height,
+    };
+    commands.spawn((camera, MainCamera));
+}
+
+/// Covers the world around the playfield with four bars, each far longer than any window is
+/// wide.
+pub fn setup_letterbox(mut commands: Commands, playfield: Res<Playfield>) {
+    let half_size = playfield.half_size();
+    let depth = playfield.width.max(playfield.height) * 4.0;
+    let length = depth * 3.0;
+
+    let bars = [
+        (
+            Vec2::new(-(half_size.x + depth / 2.0), 0.0),
+            Vec2::new(depth, length),
+        ),
+        (
+            Vec2::new(half_size.x + depth / 2.0, 0.0),
+            Vec2::new(depth, length),
+        ),
+        (
+            Vec2::new(0.0, -(half_size.y + depth / 2.0)),
+            Vec2::new(length, depth),
+        ),
+        (
+            Vec2::new(0.0, half_size.y + depth / 2.0),
+            Vec2::new(length, depth),
+        ),
+    ];
+    for (center, size) in bars {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: LETTERBOX_COLOR,
+                    custom_size: Some(size),
+                    ..default()
+                },
+                transform: Transform::from_translation(center.extend(LETTERBOX_Z)),
+                ..default()
+            },
+            LetterboxBar,
+        ));
+    }
+}
+
+pub fn show_letterbox(
+    fit: Res<ScreenFit>,
+    mut bar_query: Query<&mut Visibility, With<LetterboxBar>>,
+) {
+    for mut visibility in bar_query.iter_mut() {
+        *visibility = match *fit {
+            ScreenFit::Letterbox => Visibility::Inherited,
+            ScreenFit::Expand => Visibility::Hidden,
+        };
+    }
+}
+
+/// Scales the UI along with the playfield, so menus keep their size relative to the game.
+pub fn fit_ui_to_window(
+    playfield: Res<Playfield>,
+    mut ui_scale_res: ResMut<UiScale>,
+    window_query: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+) {
+    for window in window_query.iter() {
+        let window_size = Vec2::new(window.width(), window.height());
+        let scale = ui_scale(window_size, Vec2::new(playfield.width, playfield.height));
+        if ui_scale_res.scale != scale {
+            ui_scale_res.scale = scale;
+        }
+    }
+}
+
+pub fn shake_on_impacts(
+    mut shake: ResMut<ScreenShake>,
+    mut player_hit_reader: EventReader<PlayerHitEvent>,
+    mut destroyed_reader: EventReader<AsteroidDestroyedEvent>,
+) {
+    for _ in player_hit_reader.iter() {
+        shake.add_trauma(0.6);
+    }
+    for _ in destroyed_reader.iter() {
+        shake.add_trauma(0.12);
+    }
+}
+
+pub fn apply_screen_shake(
+    time: Res<Time>,
+    mut shake: ResMut<ScreenShake>,
+    mut camera_query: Query<&mut Transform, With<MainCamera>>,
+) {
+    shake.decay(time.delta_seconds());
+    let offset = shake.offset(time.elapsed_seconds());
+
+    for mut transform in camera_query.iter_mut() {
+        transform.translation.x = offset.x;
+        transform.translation.y = offset.y;
+    }
+}
@@ -0,0 +1,497 @@
+use std::fmt;
+
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use serde::Deserialize;
+
+use crate::game::collider::Collider;
+use crate::game::components::{AsteroidSize, PowerUpKind};
+use crate::game::wave::{WaveSpec, WAVES};
+
+/// Where the config is loaded from, relative to the assets folder.
+pub const CONFIG_PATH: &str = "game.config.ron";
+
+/// Every gameplay number that can be tuned without recompiling.
+///
+/// Loaded from [`CONFIG_PATH`]. Anything the file leaves out keeps its default.
+#[derive(Resource, TypeUuid, Debug, Clone, PartialEq, Default, Deserialize)]
+#[uuid = "9f9db435-8efb-4914-a550-1c5d2fc9c148"]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    pub player: PlayerConfig,
+    pub laser: LaserConfig,
+    pub asteroid: AsteroidConfig,
+    pub power_up: PowerUpConfig,
+    pub saucer: SaucerConfig,
+    pub waves: WaveConfig,
+    pub versus: VersusConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlayerConfig {
+    pub lives: u8,
+    /// Extra lives can't raise the count above this.
+    pub max_lives: u8,
+    /// Seconds of invincibility after losing a life.
+    pub respawn_duration: f32,
+    /// Acceleration applied along the ship's facing direction, in pixels per second squared.
+    pub thrust: f32,
+    /// Fraction of the ship's velocity lost per second while coasting.
+    pub drag: f32,
+    pub max_speed: f32,
+    /// Turn rate in radians per second when steering with keys or a gamepad.
+    pub rotation_speed: f32,
+    /// Width and height of the ship's collider in pixels. Ships spawned after a change pick
+    /// it up.
+    pub hit_box: (f32, f32),
+}
+impl Default for PlayerConfig {
+    fn default() -> PlayerConfig {
+        PlayerConfig {
+            lives: 3,
+            max_lives: 5,
+            respawn_duration: 5.0,
+            thrust: 400.0,
+            drag: 0.6,
+            max_speed: 350.0,
+            rotation_speed: 4.0,
+            hit_box: (32.0, 48.0),
+        }
+    }
+}
+impl PlayerConfig {
+    /// A triangle fitted to `hit_box`, pointing the way the ship faces.
+    pub fn collider(&self) -> Collider {
+        let (width, height) = self.hit_box;
+        Collider::polygon(vec![
+            Vec2::new(0.0, height / 2.0),
+            Vec2::new(-width / 2.0, -height / 2.0),
+            Vec2::new(width / 2.0, -height / 2.0),
+        ])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LaserConfig {
+    /// Pixels per second.
+    pub speed: f32,
+}
+impl Default for LaserConfig {
+    fn default() -> LaserConfig {
+        LaserConfig { speed: 600.0 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AsteroidConfig {
+    /// Seconds between asteroids within a wave.
+    pub spawn_interval: f32,
+    /// Diameters of each size's collider in pixels.
+    pub large_size: f32,
+    pub medium_size: f32,
+    pub small_size: f32,
+    /// Fastest an asteroid spins either way, in radians per second.
+    pub max_rotation: f32,
+    pub fragment_count_min: usize,
+    pub fragment_count_max: usize,
+    /// Angle in radians between the headings of neighbouring fragments.
+    pub fragment_spread: f32,
+    /// Largest angle in radians a fragment's heading is off by.
+    pub fragment_jitter: f32,
+    /// Fragments fly off this many times faster than the asteroid they came from.
+    pub fragment_speedup_min: f32,
+    pub fragment_speedup_max: f32,
+}
+impl Default for AsteroidConfig {
+    fn default() -> AsteroidConfig {
+        AsteroidConfig {
+            spawn_interval: 1.0,
+            large_size: 48.0,
+            medium_size: 32.0,
+            small_size: 16.0,
+            max_rotation: 6.0,
+            fragment_count_min: 2,
+            fragment_count_max: 3,
+            fragment_spread: 0.6,
+            fragment_jitter: 0.2,
+            fragment_speedup_min: 1.0,
+            fragment_speedup_max: 1.5,
+        }
+    }
+}
+impl AsteroidConfig {
+    /// Diameter of the collider of an asteroid of this size.
+    pub fn extent(&self, size: AsteroidSize) -> f32 {
+        match size {
+            AsteroidSize::Large => self.large_size,
+            AsteroidSize::Medium => self.medium_size,
+            AsteroidSize::Small => self.small_size,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PowerUpConfig {
+    /// Chance that a destroyed asteroid drops a power-up.
+    pub drop_chance: f64,
+    /// Drift speed of a dropped power-up in pixels per second.
+    pub speed: f32,
+    /// Seconds a dropped power-up stays on the playfield before it expires.
+    pub lifetime: f32,
+    /// A power-up starts blinking when it has this many seconds left.
+    pub blink_time: f32,
+    pub size: f32,
+    /// Seconds each timed effect lasts once picked up.
+    pub shield_duration: f32,
+    pub rapid_fire_duration: f32,
+    pub spread_shot_duration: f32,
+    /// Seconds between shots while holding fire with rapid fire active.
+    pub rapid_fire_interval: f32,
+    /// Angle in radians between the lasers of a spread shot.
+    pub spread_shot_angle: f32,
+}
+impl Default for PowerUpConfig {
+    fn default() -> PowerUpConfig {
+        PowerUpConfig {
+            drop_chance: 0.12,
+            speed: 40.0,
+            lifetime: 8.0,
+            blink_time: 2.0,
+            size: 20.0,
+            shield_duration: 8.0,
+            rapid_fire_duration: 10.0,
+            spread_shot_duration: 10.0,
+            rapid_fire_interval: 0.1,
+            spread_shot_angle: 0.2,
+        }
+    }
+}
+impl PowerUpConfig {
+    /// Seconds the effect lasts once picked up, or `None` if it applies at once.
+    pub fn duration(&self, kind: PowerUpKind) -> Option<f32> {
+        match kind {
+            PowerUpKind::Shield => Some(self.shield_duration),
+            PowerUpKind::RapidFire => Some(self.rapid_fire_duration),
+            PowerUpKind::SpreadShot => Some(self.spread_shot_duration),
+            PowerUpKind::ExtraLife => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SaucerConfig {
+    /// Seconds between chances for a saucer to turn up during a wave.
+    pub interval: f32,
+    /// How far above and below its course a saucer weaves, in pixels.
+    pub wave_amplitude: f32,
+    /// How fast a saucer weaves, in radians per second.
+    pub wave_frequency: f32,
+    /// Saucer shot speed in pixels per second.
+    pub shot_speed: f32,
+    /// Largest angle in radians a small saucer's aim is off by.
+    pub aim_jitter: f32,
+}
+impl Default for SaucerConfig {
+    fn default() -> SaucerConfig {
+        SaucerConfig {
+            interval: 12.0,
+            wave_amplitude: 60.0,
+            wave_frequency: 2.0,
+            shot_speed: 300.0,
+            aim_jitter: 0.15,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WaveConfig {
+    /// Seconds between the wave banner going up and the wave's first asteroid.
+    pub break_duration: f32,
+    /// Asteroids added to every wave past the end of the table.
+    pub extra_asteroids: u32,
+    pub max_asteroids: u32,
+    pub table: Vec<WaveSpec>,
+}
+impl Default for WaveConfig {
+    fn default() -> WaveConfig {
+        WaveConfig {
+            break_duration: 3.0,
+            extra_asteroids: 2,
+            max_asteroids: 40,
+            table: WAVES.to_vec(),
+        }
+    }
+}
+impl WaveConfig {
+    /// The spec for wave `wave`, counting from 1. Waves past the end of the table repeat the
+    /// last one with a few more asteroids each time.
+    pub fn spec(&self, wave: u32) -> WaveSpec {
+        let index = wave.saturating_sub(1) as usize;
+        match self.table.get(index) {
+            Some(spec) => spec.clone(),
+            None => {
+                let last = self.table[self.table.len() - 1].clone();
+                let extra = (index - (self.table.len() - 1)) as u32 * self.extra_asteroids;
+                WaveSpec {
+                    asteroids: (last.asteroids + extra).min(self.max_asteroids),
+                    ..last
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VersusConfig {
+    /// Rounds in a match. The first player to win more than half of them wins the match.
+    pub best_of: u32,
+}
+impl Default for VersusConfig {
+    fn default() -> VersusConfig {
+        VersusConfig { best_of: 3 }
+    }
+}
+impl VersusConfig {
+    /// Rounds a player has to win to take the match.
+    pub fn rounds_to_win(&self) -> u32 {
+        self.best_of / 2 + 1
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Parse(ron::error::SpannedError),
+    Invalid { field: String, reason: &'static str },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Parse(error) => write!(f, "{}", error),
+            ConfigError::Invalid { field, reason } => write!(f, "`{}` {}", field, reason),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<ron::error::SpannedError> for ConfigError {
+    fn from(error: ron::error::SpannedError) -> ConfigError {
+        ConfigError::Parse(error)
+    }
+}
+
+impl GameConfig {
+    /// Reads a config file and checks every value in it.
+    pub fn parse(bytes: &[u8]) -> Result<GameConfig, ConfigError> {
+        let config: GameConfig = ron::de::from_bytes(bytes)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks for values the game can't run with, naming the first one found.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let player = &self.player;
+        ensure(player.lives > 0, "player.lives", "must be at least 1")?;
+        ensure(
+            player.max_lives >= player.lives,
+            "player.max_lives",
+            "must be at least `player.lives`",
+        )?;
+        positive("player.respawn_duration", player.respawn_duration)?;
+        not_negative("player.thrust", player.thrust)?;
+        not_negative("player.drag", player.drag)?;
+        positive("player.max_speed", player.max_speed)?;
+        not_negative("player.rotation_speed", player.rotation_speed)?;
+        positive("player.hit_box", player.hit_box.0.min(player.hit_box.1))?;
+
+        positive("laser.speed", self.laser.speed)?;
+
+        let asteroid = &self.asteroid;
+        positive("asteroid.spawn_interval", asteroid.spawn_interval)?;
+        positive("asteroid.large_size", asteroid.large_size)?;
+        positive("asteroid.medium_size", asteroid.medium_size)?;
+        positive("asteroid.small_size", asteroid.small_size)?;
+        positive("asteroid.max_rotation", asteroid.max_rotation)?;
+        ensure(
+            asteroid.fragment_count_max >= asteroid.fragment_count_min,
+            "asteroid.fragment_count_max",
+            "must be at least `asteroid.fragment_count_min`",
+        )?;
+        not_negative("asteroid.fragment_spread", asteroid.fragment_spread)?;
+        positive("asteroid.fragment_jitter", asteroid.fragment_jitter)?;
+        positive(
+            "asteroid.fragment_speedup_min",
+            asteroid.fragment_speedup_min,
+        )?;
+        ensure(
+            asteroid.fragment_speedup_max > asteroid.fragment_speedup_min,
+            "asteroid.fragment_speedup_max",
+            "must be greater than `asteroid.fragment_speedup_min`",
+        )?;
+
+        let power_up = &self.power_up;
+        chance("power_up.drop_chance", power_up.drop_chance)?;
+        not_negative("power_up.speed", power_up.speed)?;
+        positive("power_up.lifetime", power_up.lifetime)?;
+        not_negative("power_up.blink_time", power_up.blink_time)?;
+        positive("power_up.size", power_up.size)?;
+        positive("power_up.shield_duration", power_up.shield_duration)?;
+        positive("power_up.rapid_fire_duration", power_up.rapid_fire_duration)?;
+        positive(
+            "power_up.spread_shot_duration",
+            power_up.spread_shot_duration,
+        )?;
+        positive("power_up.rapid_fire_interval", power_up.rapid_fire_interval)?;
+        not_negative("power_up.spread_shot_angle", power_up.spread_shot_angle)?;
+
+        let saucer = &self.saucer;
+        positive("saucer.interval", saucer.interval)?;
+        not_negative("saucer.wave_amplitude", saucer.wave_amplitude)?;
+        not_negative("saucer.wave_frequency", saucer.wave_frequency)?;
+        positive("saucer.shot_speed", saucer.shot_speed)?;
+        positive("saucer.aim_jitter", saucer.aim_jitter)?;
+
+        let waves = &self.waves;
+        not_negative("waves.break_duration", waves.break_duration)?;
+        ensure(
+            !waves.table.is_empty(),
+            "waves.table",
+            "must list at least one wave",
+        )?;
+        ensure(
+            waves
+                .table
+                .iter()
+                .all(|spec| spec.asteroids <= waves.max_asteroids),
+            "waves.max_asteroids",
+            "must be at least the largest wave in `waves.table`",
+        )?;
+        for (index, spec) in waves.table.iter().enumerate() {
+            let field = |name: &str| format!("waves.table[{}].{}", index, name);
+            ensure(
+                spec.asteroids > 0,
+                &field("asteroids"),
+                "must be at least 1",
+            )?;
+            not_negative(&field("speed"), spec.speed.start)?;
+            ensure(
+                spec.speed.end > spec.speed.start,
+                &field("speed"),
+                "must end above where it starts",
+            )?;
+            chance(&field("large_chance"), spec.large_chance)?;
+            chance(&field("saucer_chance"), spec.saucer_chance)?;
+            chance(&field("small_saucer_chance"), spec.small_saucer_chance)?;
+        }
+
+        ensure(
+            self.versus.best_of % 2 == 1,
+            "versus.best_of",
+            "must be an odd number of rounds",
+        )?;
+
+        Ok(())
+    }
+}
+
+fn ensure(condition: bool, field: &str, reason: &'static str) -> Result<(), ConfigError> {
+    if condition {
+        Ok(())
+    } else {
+        Err(ConfigError::Invalid {
+            field: field.to_string(),
+            reason,
+        })
+    }
+}
+
+fn positive(field: &str, value: f32) -> Result<(), ConfigError> {
+    ensure(value > 0.0, field, "must be greater than 0")
+}
+
+fn not_negative(field: &str, value: f32) -> Result<(), ConfigError> {
+    ensure(value >= 0.0, field, "must not be negative")
+}
+
+fn chance(field: &str, value: f64) -> Result<(), ConfigError> {
+    ensure(
+        (0.0..=1.0).contains(&value),
+        field,
+        "must be between 0 and 1",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invalid_field(source: &str) -> String {
+        match GameConfig::parse(source.as_bytes()) {
+            Err(ConfigError::Invalid { field, .. }) => field,
+            other => panic!("expected an invalid field, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn the_shipped_config_matches_the_defaults() {
+        let source = include_str!("../../assets/game.config.ron");
+        assert_eq!(
+            GameConfig::parse(source.as_bytes()).unwrap(),
+            GameConfig::default()
+        );
+    }
+
+    #[test]
+    fn missing_values_keep_their_defaults() {
+        let config = GameConfig::parse(b"(player: (lives: 5), laser: (speed: 900.0))").unwrap();
+
+        assert_eq!(config.player.lives, 5);
+        assert_eq!(config.player.max_lives, PlayerConfig::default().max_lives);
+        assert_eq!(config.laser.speed, 900.0);
+        assert_eq!(config.waves, WaveConfig::default());
+    }
+
+    #[test]
+    fn validation_names_the_bad_field() {
+        assert_eq!(invalid_field("(player: (lives: 0))"), "player.lives");
+        assert_eq!(
+            invalid_field("(power_up: (drop_chance: 1.5))"),
+            "power_up.drop_chance"
+        );
+        assert_eq!(
+            invalid_field(
+                "(waves: (table: [(asteroids: 4, speed: (start: 90.0, end: 60.0), \
+                 large_chance: 0.5, saucer_chance: 0.0, small_saucer_chance: 0.0)]))"
+            ),
+            "waves.table[0].speed"
+        );
+        assert_eq!(invalid_field("(waves: (table: []))"), "waves.table");
+        assert_eq!(invalid_field("(versus: (best_of: 4))"), "versus.best_of");
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        let error = GameConfig::parse(b"(laser: (sped: 900.0))").unwrap_err();
+        assert!(matches!(error, ConfigError::Parse(_)));
+        assert!(error.to_string().contains("sped"));
+    }
+
+    #[test]
+    fn waves_past_the_table_keep_getting_bigger() {
+        let waves = WaveConfig::default();
+        let last = waves.table.len() as u32;
+
+        assert_eq!(waves.spec(last), waves.table[waves.table.len() - 1]);
+        assert!(waves.spec(last + 1).asteroids > waves.spec(last).asteroids);
+        assert_eq!(waves.spec(last + 1).speed, waves.spec(last).speed);
+        assert_eq!(waves.spec(1000).asteroids, waves.max_asteroids);
+    }
+}
@@ -0,0 +1,7 @@
+pub mod game_config;
+pub mod plugin;
+
+mod loader;
+mod system;
+#[cfg(test)]
+mod tests;
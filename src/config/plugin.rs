@@ -0,0 +1,26 @@
+use bevy::prelude::*;
+
+use super::game_config::GameConfig;
+use super::loader::GameConfigLoader;
+use super::system::*;
+
+/// Loads [`GameConfig`] from the assets folder, replacing the defaults the game starts with.
+///
+/// A config file that fails to parse or validate is logged and ignored, so the game carries on
+/// with the values it had. Hot reloading needs the asset server to watch for changes, which
+/// debug builds turn on.
+pub struct ConfigPlugin;
+
+/// Keeps the config asset loaded so changes to it are seen.
+#[derive(Resource)]
+pub struct GameConfigHandle(pub Handle<GameConfig>);
+
+impl Plugin for ConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameConfig>()
+            .add_asset::<GameConfig>()
+            .init_asset_loader::<GameConfigLoader>()
+            .add_startup_system(load_game_config)
+            .add_system(apply_game_config);
+    }
+}
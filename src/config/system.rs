@@ -0,0 +1,31 @@
+use bevy::prelude::*;
+
+use super::game_config::{GameConfig, CONFIG_PATH};
+use super::plugin::GameConfigHandle;
+
+pub fn load_game_config(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(GameConfigHandle(asset_server.load(CONFIG_PATH)));
+}
+
+/// Copies the config asset into the [`GameConfig`] resource whenever it is loaded or changed
+/// on disk.
+pub fn apply_game_config(
+    mut config: ResMut<GameConfig>,
+    handle: Res<GameConfigHandle>,
+    configs: Res<Assets<GameConfig>>,
+    mut asset_events: EventReader<AssetEvent<GameConfig>>,
+) {
+    for event in asset_events.iter() {
+        match event {
+            AssetEvent::Created { handle: changed } | AssetEvent::Modified { handle: changed }
+                if *changed == handle.0 =>
+            {
+                if let Some(loaded) = configs.get(changed) {
+                    info!("Applying game config from {}", CONFIG_PATH);
+                    *config = loaded.clone();
+                }
+            }
+            _ => {}
+        }
+    }
+}
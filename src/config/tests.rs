@@ -0,0 +1,36 @@
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+
+use crate::testing::Harness;
+
+use super::game_config::GameConfig;
+use super::plugin::{ConfigPlugin, GameConfigHandle};
+
+const SEED: u64 = 7;
+
+#[test]
+fn changes_to_the_config_asset_reach_the_game() {
+    let mut harness = Harness::build(SEED, |app| {
+        app.add_plugin(ConfigPlugin);
+    });
+
+    let handle = harness.app.world.resource::<GameConfigHandle>().0.clone();
+    while harness
+        .app
+        .world
+        .resource::<AssetServer>()
+        .get_load_state(&handle)
+        != LoadState::Loaded
+    {
+        assert!(harness.frames() < 600, "the config never loaded");
+        harness.step();
+    }
+    harness.step();
+    assert_eq!(*harness.config(), GameConfig::default());
+
+    // What the asset server does when the file is saved again
+    let mut configs = harness.app.world.resource_mut::<Assets<GameConfig>>();
+    configs.get_mut(&handle).unwrap().laser.speed = 900.0;
+    harness.run_ticks(2);
+    assert_eq!(harness.config().laser.speed, 900.0);
+}
@@ -0,0 +1,232 @@
+use bevy::prelude::*;
+
+/// The shape an entity collides with, in world units around its translation.
+///
+/// Shapes turn with the entity's rotation but ignore its scale, which only sizes the sprite.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub enum Collider {
+    Circle {
+        radius: f32,
+    },
+    /// A box around the centre, `half_extents` out along each local axis.
+    Box {
+        half_extents: Vec2,
+    },
+    /// A convex polygon, with its points in order around the centre.
+    Polygon {
+        points: Vec<Vec2>,
+    },
+}
+
+impl Collider {
+    pub fn circle(radius: f32) -> Collider {
+        Collider::Circle { radius }
+    }
+
+    pub fn rect(size: Vec2) -> Collider {
+        Collider::Box {
+            half_extents: size / 2.0,
+        }
+    }
+
+    pub fn polygon(points: Vec<Vec2>) -> Collider {
+        Collider::Polygon { points }
+    }
+
+    /// The distance from the centre to the furthest point of the shape, whatever its rotation.
+    pub fn bounding_radius(&self) -> f32 {
+        match self {
+            Collider::Circle { radius } => *radius,
+            Collider::Box { half_extents } => half_extents.length(),
+            Collider::Polygon { points } => points
+                .iter()
+                .map(|point| point.length())
+                .fold(0.0, f32::max),
+        }
+    }
+
+    /// Whether this shape, placed at `transform`, overlaps `other` placed at `other_transform`.
+    /// Shapes that only touch count as overlapping.
+    pub fn intersects(
+        &self,
+        transform: &Transform,
+        other: &Collider,
+        other_transform: &Transform,
+    ) -> bool {
+        let shape = self.to_world(transform);
+        let other_shape = other.to_world(other_transform);
+
+        match (&shape, &other_shape) {
+            (
+                WorldShape::Circle(center, radius),
+                WorldShape::Circle(other_center, other_radius),
+            ) => center.distance_squared(*other_center) <= (radius + other_radius).powi(2),
+            (WorldShape::Circle(center, radius), WorldShape::Polygon(points))
+            | (WorldShape::Polygon(points), WorldShape::Circle(center, radius)) => {
+                circle_hits_polygon(*center, *radius, points)
+            }
+            (WorldShape::Polygon(points), WorldShape::Polygon(other_points)) => {
+                polygons_overlap(points, other_points)
+            }
+        }
+    }
+
+    fn to_world(&self, transform: &Transform) -> WorldShape {
+        let center = transform.translation.truncate();
+        let place = |point: Vec2| center + (transform.rotation * point.extend(0.0)).truncate();
+
+        match self {
+            Collider::Circle { radius } => WorldShape::Circle(center, *radius),
+            Collider::Box { half_extents } => WorldShape::Polygon(
+                [
+                    Vec2::new(-half_extents.x, -half_extents.y),
+                    Vec2::new(half_extents.x, -half_extents.y),
+                    Vec2::new(half_extents.x, half_extents.y),
+                    Vec2::new(-half_extents.x, half_extents.y),
+                ]
+                .into_iter()
+                .map(place)
+                .collect(),
+            ),
+            Collider::Polygon { points } => {
+                WorldShape::Polygon(points.iter().copied().map(place).collect())
+            }
+        }
+    }
+}
+
+enum WorldShape {
+    Circle(Vec2, f32),
+    Polygon(Vec<Vec2>),
+}
+
+/// The normals of a polygon's edges, which are the only axes it can be separated along.
+fn edge_normals(points: &[Vec2]) -> impl Iterator<Item = Vec2> + '_ {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| (*b - *a).perp())
+}
+
+fn project(points: &[Vec2], axis: Vec2) -> (f32, f32) {
+    points
+        .iter()
+        .map(|point| point.dot(axis))
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+            (min.min(value), max.max(value))
+        })
+}
+
+/// Separating axis test: convex polygons overlap unless some edge normal separates them.
+fn polygons_overlap(a: &[Vec2], b: &[Vec2]) -> bool {
+    edge_normals(a).chain(edge_normals(b)).all(|axis| {
+        let (a_min, a_max) = project(a, axis);
+        let (b_min, b_max) = project(b, axis);
+        a_min <= b_max && b_min <= a_max
+    })
+}
+
+/// Separating axis test against a circle, which adds the axis towards the polygon's nearest
+/// point to the polygon's edge normals.
+fn circle_hits_polygon(center: Vec2, radius: f32, points: &[Vec2]) -> bool {
+    let Some(nearest) = points.iter().min_by(|a, b| {
+        a.distance_squared(center)
+            .total_cmp(&b.distance_squared(center))
+    }) else {
+        return false;
+    };
+
+    edge_normals(points)
+        .chain(std::iter::once(*nearest - center))
+        .all(|axis| {
+            let axis = axis.normalize_or_zero();
+            if axis == Vec2::ZERO {
+                return true;
+            }
+            let (min, max) = project(points, axis);
+            let circle = center.dot(axis);
+            circle + radius >= min && circle - radius <= max
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    use super::*;
+
+    fn at(x: f32, y: f32) -> Transform {
+        Transform::from_xyz(x, y, 0.0)
+    }
+
+    fn turned(x: f32, y: f32, angle: f32) -> Transform {
+        at(x, y).with_rotation(Quat::from_rotation_z(angle))
+    }
+
+    #[test]
+    fn circles_hit_within_their_combined_radius() {
+        let circle = Collider::circle(10.0);
+
+        assert!(circle.intersects(&at(0.0, 0.0), &circle, &at(19.0, 0.0)));
+        assert!(circle.intersects(&at(0.0, 0.0), &circle, &at(20.0, 0.0)));
+        assert!(!circle.intersects(&at(0.0, 0.0), &circle, &at(14.2, 14.2)));
+    }
+
+    #[test]
+    fn circles_miss_the_corners_of_a_box() {
+        let square = Collider::rect(Vec2::splat(20.0));
+        let circle = Collider::circle(5.0);
+
+        // Inside the bounding boxes of both, but off the square's corner
+        assert!(!circle.intersects(&at(14.0, 14.0), &square, &at(0.0, 0.0)));
+        assert!(circle.intersects(&at(14.0, 0.0), &square, &at(0.0, 0.0)));
+    }
+
+    #[test]
+    fn boxes_turn_with_their_transform() {
+        let bar = Collider::rect(Vec2::new(40.0, 4.0));
+        let circle = Collider::circle(3.0);
+
+        // Along the bar's length when it lies flat, beside it once it's stood up
+        assert!(circle.intersects(&at(18.0, 0.0), &bar, &at(0.0, 0.0)));
+        assert!(!circle.intersects(&at(18.0, 0.0), &bar, &turned(0.0, 0.0, FRAC_PI_2)));
+        assert!(circle.intersects(&at(0.0, 18.0), &bar, &turned(0.0, 0.0, FRAC_PI_2)));
+    }
+
+    #[test]
+    fn rotated_boxes_only_hit_where_they_overlap() {
+        let square = Collider::rect(Vec2::splat(20.0));
+
+        // A diamond's corner pokes out further than the square's side
+        assert!(square.intersects(&at(0.0, 0.0), &square, &turned(23.0, 0.0, FRAC_PI_4)));
+        assert!(!square.intersects(&at(0.0, 0.0), &square, &turned(25.0, 0.0, FRAC_PI_4)));
+        // Corner to corner, the diamond's tip can't reach into the square's corner region
+        assert!(!square.intersects(&at(0.0, 0.0), &square, &turned(19.0, 19.0, FRAC_PI_4)));
+        assert!(square.intersects(&at(0.0, 0.0), &square, &at(19.0, 19.0)));
+    }
+
+    #[test]
+    fn triangles_miss_beside_their_point() {
+        let ship = Collider::polygon(vec![
+            Vec2::new(0.0, 24.0),
+            Vec2::new(-16.0, -24.0),
+            Vec2::new(16.0, -24.0),
+        ]);
+        let circle = Collider::circle(4.0);
+
+        assert!(circle.intersects(&at(0.0, 26.0), &ship, &at(0.0, 0.0)));
+        assert!(!circle.intersects(&at(14.0, 20.0), &ship, &at(0.0, 0.0)));
+        // Turned to face right, the point is where the side used to be
+        let facing_right = turned(0.0, 0.0, -FRAC_PI_2);
+        assert!(circle.intersects(&at(26.0, 0.0), &ship, &facing_right));
+        assert!(!circle.intersects(&at(0.0, 26.0), &ship, &facing_right));
+    }
+
+    #[test]
+    fn scale_does_not_resize_the_shape() {
+        let circle = Collider::circle(10.0);
+        let scaled = at(0.0, 0.0).with_scale(Vec3::splat(0.3));
+
+        assert!(circle.intersects(&scaled, &circle, &at(19.0, 0.0)));
+    }
+}
@@ -1,64 +1,439 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use super::constants::{DESPAWN_MARGIN, PLAYFIELD_HEIGHT, PLAYFIELD_WIDTH, SPAWN_MARGIN};
+use crate::config::game_config::GameConfig;
+use crate::global::palette::Palette;
+
+/// Which of the players sharing the screen something belongs to, counting from 0.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PlayerId(pub usize);
+impl PlayerId {
+    pub fn label(&self) -> String {
+        format!("P{}", self.0 + 1)
+    }
 
-pub trait HitBox {
-    fn get_box(&self) -> Vec2;
+    /// The colour the player's ship is drawn in, so players can tell theirs apart.
+    pub fn tint(&self, palette: Palette) -> Color {
+        match (palette, self.0) {
+            (_, 0) => Color::WHITE,
+            (Palette::Standard, _) => Color::rgb(1.0, 0.85, 0.4),
+            (Palette::Colorblind, _) => Color::rgb(0.35, 0.7, 0.9),
+        }
+    }
+}
+
+/// Who is playing: one player, or several sharing the screen.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GameMode {
+    #[default]
+    Solo,
+    /// Two players on the same side. The game lasts until both are out of lives.
+    Coop,
+    /// Two players shooting at each other, with the asteroids in everyone's way. A round lasts
+    /// until either player is out of lives.
+    Versus,
+}
+impl GameMode {
+    pub fn players(&self) -> usize {
+        match self {
+            GameMode::Solo => 1,
+            GameMode::Coop | GameMode::Versus => 2,
+        }
+    }
 }
 
 #[derive(Component)]
 pub struct Player {
+    pub id: PlayerId,
     pub lives: u8,
     pub invincible: bool,
+    pub thrusting: bool,
     pub respawn_timer: Timer,
+    /// Time until holding fire shoots again under rapid fire.
+    pub fire_cooldown: Timer,
 }
-impl Default for Player {
-    fn default() -> Player {
+impl Player {
+    pub fn new(id: PlayerId, config: &GameConfig) -> Player {
         Player {
-            lives: 3,
+            id,
+            lives: config.player.lives,
             invincible: false,
-            respawn_timer: Timer::from_seconds(5.0, TimerMode::Once),
+            thrusting: false,
+            respawn_timer: Timer::from_seconds(config.player.respawn_duration, TimerMode::Once),
+            fire_cooldown: Timer::from_seconds(
+                config.power_up.rapid_fire_interval,
+                TimerMode::Once,
+            ),
         }
     }
 }
 
-impl HitBox for Player {
-    fn get_box(&self) -> Vec2 {
-        Vec2::new(32.0, 48.0)
-    }
-}
+#[derive(Component, Default)]
+pub struct Velocity(pub Vec2);
+
+/// Used up by a collision this tick and about to be despawned, so it can't be hit again.
+#[derive(Component)]
+pub struct Destroyed;
 
 #[derive(Resource)]
 pub struct GameState {
-    pub asteroid_rate_increase_timer: Timer,
+    /// The current wave, counting from 1.
+    pub wave: u32,
+    /// Asteroids still to be sent in during this wave.
+    pub asteroids_left: u32,
     pub asteroid_spawn_timer: Timer,
-    pub paused: bool,
+    /// Running between waves, while the banner for the next one is up.
+    pub wave_break: Option<Timer>,
+    pub saucer_spawn_timer: Timer,
+    /// Set once the game is lost, so no further ticks run before the game over screen.
+    pub finished: bool,
+}
+impl Default for GameState {
+    fn default() -> GameState {
+        GameState::new(&GameConfig::default())
+    }
+}
+impl GameState {
+    pub fn new(config: &GameConfig) -> GameState {
+        GameState {
+            wave: 1,
+            asteroids_left: 0,
+            asteroid_spawn_timer: Timer::from_seconds(
+                config.asteroid.spawn_interval,
+                TimerMode::Repeating,
+            ),
+            wave_break: Some(Timer::from_seconds(
+                config.waves.break_duration,
+                TimerMode::Once,
+            )),
+            saucer_spawn_timer: Timer::from_seconds(config.saucer.interval, TimerMode::Repeating),
+            finished: false,
+        }
+    }
+
+    /// Whether a wave is being played, rather than the break before the next one.
+    pub fn wave_in_progress(&self) -> bool {
+        self.wave_break.is_none()
+    }
+}
+
+/// The single source of randomness for the simulation.
+///
+/// Every gameplay system that needs randomness draws from this, so a game can be
+/// reproduced from its seed and inputs.
+#[derive(Resource)]
+pub struct GameRng {
+    pub seed: u64,
+    pub rng: ChaCha8Rng,
+}
+impl GameRng {
+    pub fn new(seed: u64) -> GameRng {
+        GameRng {
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+/// The seed to use for every new game, if one was chosen on the command line.
+#[derive(Resource, Default)]
+pub struct SeedConfig {
+    pub fixed_seed: Option<u64>,
+}
+
+/// The logical size of the play area in world units, centred on the origin.
+///
+/// Gameplay never looks at the window, so the simulation runs the same with or without one.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct Playfield {
+    pub width: f32,
+    pub height: f32,
+}
+impl Playfield {
+    pub fn half_size(&self) -> Vec2 {
+        Vec2::new(self.width, self.height) / 2.0
+    }
+
+    /// The area asteroids come in from. They appear on its edge, just out of sight.
+    pub fn spawn_bounds(&self) -> Rect {
+        Rect::from_center_half_size(Vec2::ZERO, self.half_size() + SPAWN_MARGIN)
+    }
+
+    /// Anything that drifts out of this area is gone for good.
+    pub fn despawn_bounds(&self) -> Rect {
+        Rect::from_center_half_size(Vec2::ZERO, self.half_size() + DESPAWN_MARGIN)
+    }
+}
+impl Default for Playfield {
+    fn default() -> Playfield {
+        Playfield {
+            width: PLAYFIELD_WIDTH,
+            height: PLAYFIELD_HEIGHT,
+        }
+    }
 }
 
 #[derive(Component)]
 pub struct Laser {
+    /// Pixels per second.
     pub velocity: Vec2,
+    /// The player who fired it, and scores whatever it hits.
+    pub owner: PlayerId,
+}
+
+/// Which meteor texture an asteroid is drawn with. Chosen from [`GameRng`] as the asteroid
+/// spawns, even without anything to draw it, so every build of the game rolls the same dice.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MeteorSprite(pub u8);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsteroidSize {
+    Large,
+    Medium,
+    Small,
+}
+impl AsteroidSize {
+    /// Sprite scale applied on top of the meteor texture.
+    pub fn scale(&self) -> f32 {
+        match self {
+            AsteroidSize::Large | AsteroidSize::Medium => 1.0,
+            AsteroidSize::Small => 0.5,
+        }
+    }
+
+    /// Score awarded for shooting an asteroid of this size.
+    pub fn score(&self) -> usize {
+        match self {
+            AsteroidSize::Large => 20,
+            AsteroidSize::Medium => 50,
+            AsteroidSize::Small => 100,
+        }
+    }
+
+    /// The size of the fragments this asteroid breaks into, if any.
+    pub fn fragment(&self) -> Option<AsteroidSize> {
+        match self {
+            AsteroidSize::Large => Some(AsteroidSize::Medium),
+            AsteroidSize::Medium => Some(AsteroidSize::Small),
+            AsteroidSize::Small => None,
+        }
+    }
 }
 
 #[derive(Component)]
 pub struct Asteroid {
+    pub size: AsteroidSize,
     pub trajectory: Vec2,
+    /// Pixels per second along `trajectory`.
     pub speed: f32,
+    /// Radians per second.
     pub rotation: f32,
-    pub width: f32,
-    pub height: f32,
 }
-impl HitBox for Asteroid {
-    fn get_box(&self) -> Vec2 {
-        Vec2::new(self.width, self.height)
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaucerSize {
+    /// Slow and big, firing in random directions.
+    Large,
+    /// Fast and small, aiming at the player.
+    Small,
+}
+impl SaucerSize {
+    /// Width and height of the saucer's collider in pixels.
+    pub fn extent(&self) -> Vec2 {
+        match self {
+            SaucerSize::Large => Vec2::new(48.0, 32.0),
+            SaucerSize::Small => Vec2::new(28.0, 20.0),
+        }
+    }
+
+    /// Sprite scale applied on top of the ship texture.
+    pub fn scale(&self) -> f32 {
+        match self {
+            SaucerSize::Large => 0.9,
+            SaucerSize::Small => 0.55,
+        }
+    }
+
+    /// Tint applied to the ship texture.
+    pub fn color(&self) -> Color {
+        match self {
+            SaucerSize::Large => Color::rgb(1.0, 0.35, 0.35),
+            SaucerSize::Small => Color::rgb(1.0, 0.7, 0.2),
+        }
+    }
+
+    /// Pixels per second across the playfield.
+    pub fn speed(&self) -> f32 {
+        match self {
+            SaucerSize::Large => 100.0,
+            SaucerSize::Small => 160.0,
+        }
+    }
+
+    /// Seconds between shots.
+    pub fn fire_interval(&self) -> f32 {
+        match self {
+            SaucerSize::Large => 1.5,
+            SaucerSize::Small => 1.0,
+        }
+    }
+
+    /// Score awarded for shooting a saucer of this size.
+    pub fn score(&self) -> usize {
+        match self {
+            SaucerSize::Large => 200,
+            SaucerSize::Small => 1000,
+        }
+    }
+}
+
+/// An enemy flying across the playfield on a wavy course, shooting as it goes.
+#[derive(Component)]
+pub struct Saucer {
+    pub size: SaucerSize,
+    /// 1 when flying right, -1 when flying left.
+    pub direction: f32,
+    /// The height the saucer weaves around.
+    pub course: f32,
+    /// Seconds since the saucer appeared, which drives the weave.
+    pub age: f32,
+    pub fire_timer: Timer,
+}
+impl Saucer {
+    pub fn new(size: SaucerSize, direction: f32, course: f32) -> Saucer {
+        Saucer {
+            size,
+            direction,
+            course,
+            age: 0.0,
+            fire_timer: Timer::from_seconds(size.fire_interval(), TimerMode::Repeating),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct SaucerShot {
+    /// Pixels per second.
+    pub velocity: Vec2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerUpKind {
+    Shield,
+    RapidFire,
+    SpreadShot,
+    ExtraLife,
+}
+impl PowerUpKind {
+    pub const ALL: [PowerUpKind; 4] = [
+        PowerUpKind::Shield,
+        PowerUpKind::RapidFire,
+        PowerUpKind::SpreadShot,
+        PowerUpKind::ExtraLife,
+    ];
+
+    pub fn color(&self, palette: Palette) -> Color {
+        match (palette, self) {
+            (Palette::Standard, PowerUpKind::Shield) => Color::rgb(0.3, 0.7, 1.0),
+            (Palette::Standard, PowerUpKind::RapidFire) => Color::rgb(1.0, 0.6, 0.1),
+            (Palette::Standard, PowerUpKind::SpreadShot) => Color::rgb(0.8, 0.3, 1.0),
+            (Palette::Standard, PowerUpKind::ExtraLife) => Color::rgb(0.3, 1.0, 0.4),
+            // Blue, orange, pink and yellow differ in brightness as well as hue
+            (Palette::Colorblind, PowerUpKind::Shield) => Color::rgb(0.0, 0.45, 0.7),
+            (Palette::Colorblind, PowerUpKind::RapidFire) => Color::rgb(0.9, 0.6, 0.0),
+            (Palette::Colorblind, PowerUpKind::SpreadShot) => Color::rgb(0.8, 0.47, 0.65),
+            (Palette::Colorblind, PowerUpKind::ExtraLife) => Color::rgb(0.95, 0.9, 0.25),
+        }
+    }
+
+    /// Name shown in the HUD while the effect is active.
+    pub fn label(&self) -> &'static str {
+        match self {
+            PowerUpKind::Shield => "Shield",
+            PowerUpKind::RapidFire => "Rapid Fire",
+            PowerUpKind::SpreadShot => "Spread Shot",
+            PowerUpKind::ExtraLife => "Extra Life",
+        }
     }
 }
 
+/// A pickup dropped by a destroyed asteroid, drifting until it is collected or expires.
+#[derive(Component)]
+pub struct PowerUp {
+    pub kind: PowerUpKind,
+    /// Pixels per second.
+    pub velocity: Vec2,
+    pub lifetime: Timer,
+}
+impl PowerUp {
+    /// A power-up that expires after `lifetime` seconds.
+    pub fn new(kind: PowerUpKind, velocity: Vec2, lifetime: f32) -> PowerUp {
+        PowerUp {
+            kind,
+            velocity,
+            lifetime: Timer::from_seconds(lifetime, TimerMode::Once),
+        }
+    }
+}
+
+/// The timed power-ups a player has picked up and not yet run out of.
+#[derive(Component, Default)]
+pub struct ActivePowerUps {
+    active: Vec<(PowerUpKind, Timer)>,
+}
+impl ActivePowerUps {
+    pub fn is_active(&self, kind: PowerUpKind) -> bool {
+        self.active.iter().any(|(active, _)| *active == kind)
+    }
+
+    /// Starts the effect, or restarts it from full if it is already active.
+    pub fn activate(&mut self, kind: PowerUpKind, seconds: f32) {
+        let timer = Timer::from_seconds(seconds, TimerMode::Once);
+        match self.active.iter_mut().find(|(active, _)| *active == kind) {
+            Some((_, existing)) => *existing = timer,
+            None => self.active.push((kind, timer)),
+        }
+    }
+
+    /// Advances every effect, dropping the ones that run out.
+    pub fn tick(&mut self, delta: Duration) {
+        for (_, timer) in self.active.iter_mut() {
+            timer.tick(delta);
+        }
+        self.active.retain(|(_, timer)| !timer.finished());
+    }
+
+    /// The active effects in the order they were picked up, with the time each has left.
+    pub fn iter(&self) -> impl Iterator<Item = (PowerUpKind, Duration)> + '_ {
+        self.active
+            .iter()
+            .map(|(kind, timer)| (*kind, timer.duration() - timer.elapsed()))
+    }
+}
+
+/// One slot of a player's life counter in the top right corner.
 #[derive(Component)]
 pub struct Life {
+    pub player: PlayerId,
     pub counter: u8,
+    pub lost: bool,
 }
 
+/// The score line of one player, in the top left corner.
+#[derive(Component)]
+pub struct ScoreBoardText(pub PlayerId);
+
+#[derive(Component)]
+pub struct PowerUpText;
+
+/// The current wave, shown at the top of the screen throughout the game.
 #[derive(Component)]
-pub struct PausedText;
+pub struct WaveText;
 
+/// The large "Wave N" shown in the middle of the screen between waves.
 #[derive(Component)]
-pub struct ScoreBoardText;
+pub struct WaveBanner;
@@ -1,9 +1,30 @@
+use bevy::math::Vec2;
 use bevy::ui::Val;
 
-pub const PLAYER_LIVES: u8 = 3;
-pub const RESPAWN_DURATION: f32 = 5.0;
+/// Simulation ticks per second.
+pub const DEFAULT_TICK_RATE: f32 = 60.0;
 
-pub const LASER_SPEED: f32 = 10.0;
+/// Default size of the play area in world units.
+pub const PLAYFIELD_WIDTH: f32 = 800.0;
+pub const PLAYFIELD_HEIGHT: f32 = 800.0;
+
+/// How far past the playfield edge the ship travels before wrapping to the other side.
+pub const WRAP_MARGIN: f32 = 24.0;
+/// How far past the playfield edge asteroids appear.
+pub const SPAWN_MARGIN: f32 = 25.0;
+/// How far past the playfield edge things that don't wrap are despawned.
+pub const DESPAWN_MARGIN: f32 = 50.0;
+
+/// Width and height of a laser's collider, lying along its heading.
+pub const LASER_SIZE: Vec2 = Vec2::new(4.0, 16.0);
+pub const SAUCER_SHOT_RADIUS: f32 = 4.0;
+
+/// Side of a collision grid cell, about the size of a large asteroid.
+pub const SPATIAL_GRID_CELL_SIZE: f32 = 64.0;
 
 pub const SCOREBOARD_TEXT_PADDING: Val = Val::Px(5.0);
+pub const POWER_UP_FONT_SIZE: f32 = 24.0;
+pub const WAVE_BANNER_FONT_SIZE: f32 = 60.0;
 pub const LIFE_PADDING: f32 = 25.0;
+/// Distance between the ships when several players start side by side.
+pub const PLAYER_SPACING: f32 = 120.0;
@@ -1,3 +1,45 @@
-pub struct FireEvent;
+use bevy::prelude::*;
 
-pub struct PlayerHitEvent;
+use super::components::AsteroidSize;
+
+/// Sent when a player's ship fires.
+pub struct FireEvent {
+    pub player: Entity,
+}
+
+/// Sent when a player's ship is hit and loses a life.
+pub struct PlayerHitEvent {
+    pub player: Entity,
+}
+
+/// What touched what in a [`CollisionEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Contact {
+    LaserAsteroid,
+    LaserSaucer,
+    PlayerAsteroid,
+    PlayerSaucer,
+    PlayerSaucerShot,
+    PlayerPowerUp,
+    /// A ship shot by another player, which only happens in versus games.
+    PlayerLaser,
+}
+
+/// Two entities found touching this tick, in the order of `contact`'s name.
+///
+/// The collision passes only report contacts. They are resolved afterwards, in the order they
+/// were found, and a contact whose entities have already been used up by an earlier one does
+/// nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollisionEvent {
+    pub contact: Contact,
+    pub entity: Entity,
+    pub other: Entity,
+}
+
+pub struct AsteroidDestroyedEvent {
+    pub translation: Vec3,
+    pub size: AsteroidSize,
+    pub trajectory: Vec2,
+    pub speed: f32,
+}
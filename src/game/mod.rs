@@ -1,6 +1,11 @@
+pub mod collider;
 pub mod components;
+pub mod constants;
 pub mod event;
 pub mod plugin;
+pub mod spatial;
+pub mod wave;
 
-mod constants;
-mod system;
+pub(crate) mod system;
+#[cfg(test)]
+mod tests;
@@ -1,21 +1,50 @@
+use bevy::ecs::event::Event;
 use bevy::prelude::*;
 
-use super::components::GameState;
+use super::components::{GameMode, GameState, Player, Playfield, SeedConfig};
+use super::constants::DEFAULT_TICK_RATE;
+use super::spatial::SpatialGrid;
 use super::system::asteroid::*;
 use super::system::collision::*;
+use super::system::hud::*;
 use super::system::input::*;
 use super::system::movement::*;
 use super::system::player::*;
+use super::system::power_up::*;
+use super::system::saucer::*;
 use super::system::setup::*;
+use super::system::sprite::*;
+use super::system::wave::*;
 
 use super::event::*;
-use crate::clear_game_objects;
+use crate::action::plugin::SampleActions;
+use crate::config::game_config::GameConfig;
+use crate::global::event::GameOverEvent;
+use crate::global::palette::Palette;
 use crate::global::state::AppState;
+use crate::global::system::clear_game_objects;
 
-pub struct GamePlugin;
+/// Runs the gameplay simulation on a fixed timestep.
+///
+/// All gameplay systems live in [`CoreSchedule::FixedUpdate`] and advance by exactly
+/// one tick period, so the simulation behaves the same at any frame rate.
+pub struct GamePlugin {
+    /// Simulation ticks per second.
+    pub tick_rate: f32,
+}
+
+impl Default for GamePlugin {
+    fn default() -> GamePlugin {
+        GamePlugin {
+            tick_rate: DEFAULT_TICK_RATE,
+        }
+    }
+}
 
+/// The stages of a gameplay tick, in the order they run. They only run while a game is in
+/// progress, so other plugins can add systems to them to take part in the simulation.
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
-enum GameSet {
+pub enum GameSet {
     Input,
     Spawning,
     Movement,
@@ -25,72 +54,193 @@ enum GameSet {
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        app
+        app.insert_resource(FixedTime::new_from_secs(1.0 / self.tick_rate))
             // Events
-            .add_event::<FireEvent>()
-            .add_event::<PlayerHitEvent>()
-            // Setup new game
-            .add_startup_system(setup_game_state)
+            .add_fixed_event::<FireEvent>()
+            .add_fixed_event::<PlayerHitEvent>()
+            .add_fixed_event::<CollisionEvent>()
+            .add_fixed_event::<AsteroidDestroyedEvent>()
+            .add_fixed_event::<GameOverEvent>()
+            .init_resource::<GameConfig>()
+            .init_resource::<GameState>()
+            .init_resource::<GameMode>()
+            .init_resource::<SpatialGrid>()
+            .init_resource::<Palette>()
+            .init_resource::<SeedConfig>()
+            .init_resource::<Playfield>()
+            // Setup new game, but not when resuming from the pause menu
             .add_systems(
-                (spawn_player, setup_scoreboard, setup_life_counter)
+                (setup_game_state, setup_players, spawn_player)
+                    .distributive_run_if(is_new_game)
                     .in_schedule(OnEnter(AppState::InGame)),
             )
-            // Update in game
+            // Fixed tick
+            .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+                schedule.configure_sets(
+                    (
+                        GameSet::Input,
+                        GameSet::Spawning,
+                        GameSet::Movement,
+                        GameSet::Collision,
+                        GameSet::Updates,
+                    )
+                        .chain(),
+                );
+                schedule.configure_set(GameSet::Input.after(SampleActions));
+                // Wave progress looks for asteroids and saucers in the same tick they spawn in
+                schedule.add_system(
+                    apply_system_buffers
+                        .after(GameSet::Spawning)
+                        .before(GameSet::Movement),
+                );
+                for set in [
+                    GameSet::Input,
+                    GameSet::Spawning,
+                    GameSet::Movement,
+                    GameSet::Collision,
+                    GameSet::Updates,
+                ] {
+                    schedule
+                        .configure_set(set.run_if(in_state(AppState::InGame)).run_if(is_running));
+                }
+            })
             .add_systems(
-                (check_laser_collisions, check_player_collisions)
+                (aiming_handler, thrust_handler, shooting_handler)
                     .chain()
-                    .distributive_run_if(is_running)
-                    .in_set(OnUpdate(AppState::InGame))
-                    .in_set(GameSet::Collision),
+                    .in_set(GameSet::Input)
+                    .in_schedule(CoreSchedule::FixedUpdate),
             )
             .add_systems(
-                (laser_movement, asteroid_movement)
+                (
+                    shoot,
+                    spawn_asteroid.run_if(asteroid_spawn_timer),
+                    spawn_asteroid_fragments,
+                    spawn_power_ups,
+                    spawn_saucer.run_if(saucer_spawn_timer),
+                    saucer_shooting,
+                )
                     .chain()
-                    .distributive_run_if(is_running)
-                    .in_set(OnUpdate(AppState::InGame))
-                    .in_set(GameSet::Movement),
+                    .in_set(GameSet::Spawning)
+                    .in_schedule(CoreSchedule::FixedUpdate),
             )
             .add_systems(
-                (aiming_handler, shooting_handler)
+                (
+                    player_movement,
+                    laser_movement,
+                    asteroid_movement,
+                    power_up_movement,
+                    saucer_movement,
+                    saucer_shot_movement,
+                )
                     .chain()
-                    .distributive_run_if(is_running)
-                    .in_set(OnUpdate(AppState::InGame))
-                    .in_set(GameSet::Input),
+                    .in_set(GameSet::Movement)
+                    .in_schedule(CoreSchedule::FixedUpdate),
             )
             .add_systems(
-                (shoot, spawn_asteroid.run_if(asteroid_spawn_timer))
+                (
+                    update_spatial_grid,
+                    check_laser_collisions,
+                    check_player_collisions,
+                    check_laser_player_collisions,
+                    check_saucer_collisions,
+                    check_saucer_shot_collisions,
+                    check_power_up_pickups,
+                    resolve_collisions,
+                    apply_system_buffers,
+                    despawn_destroyed,
+                )
                     .chain()
-                    .distributive_run_if(is_running)
-                    .in_set(OnUpdate(AppState::InGame))
-                    .in_set(GameSet::Spawning),
+                    .in_set(GameSet::Collision)
+                    .in_schedule(CoreSchedule::FixedUpdate),
             )
             .add_systems(
                 (
                     update_asteroid_spawn_timer,
-                    update_scoreboard,
-                    update_life_counter,
+                    update_saucer_spawn_timer,
+                    update_waves,
+                    lose_life_listener,
+                    update_active_power_ups,
                     game_over_listener,
                     player_invincibility_listener,
                     player_respawn_timer.run_if(player_is_respawning),
                 )
                     .chain()
-                    .in_set(OnUpdate(AppState::InGame))
                     .in_set(GameSet::Updates)
-                    .distributive_run_if(is_running),
+                    .in_schedule(CoreSchedule::FixedUpdate),
             )
-            .add_system(
-                pause_handler
-                    .before(GameSet::Updates)
-                    .in_set(OnUpdate(AppState::InGame)),
+            // Per-frame input
+            .add_system(pause_listener.in_set(OnUpdate(AppState::InGame)))
+            .add_system(clear_game_objects.in_schedule(OnEnter(AppState::GameOver)));
+    }
+}
+
+/// Draws what [`GamePlugin`] simulates: a sprite for each ship, asteroid, shot, saucer and
+/// power-up, and the in-game HUD. A game without a window, like the dedicated server, goes
+/// without it.
+pub struct GameVisualsPlugin;
+
+impl Plugin for GameVisualsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (setup_scoreboard, setup_life_counter, setup_wave_hud)
+                .distributive_run_if(is_new_game)
+                .in_schedule(OnEnter(AppState::InGame)),
+        )
+        .add_systems(
+            (
+                add_ship_sprites,
+                add_asteroid_sprites,
+                add_laser_sprites,
+                add_saucer_sprites,
+                add_saucer_shot_sprites,
+                add_power_up_sprites,
+                tint_ships,
+                blink_power_ups,
             )
-            .add_system(clear_game_objects.in_schedule(OnExit(AppState::InGame)))
-            .configure_set(
-                // Run systems in the Movement set before systems in the CollisionDetection set
-                GameSet::Movement.before(GameSet::Collision),
-            );
+                .chain(),
+        )
+        .add_systems(
+            (
+                update_wave_hud,
+                update_scoreboard,
+                update_life_counter,
+                update_power_up_hud,
+            )
+                .chain()
+                .distributive_run_if(in_state(AppState::InGame)),
+        );
     }
 }
 
-fn is_running(game_state: Res<GameState>) -> bool {
-    !game_state.paused
+pub fn is_running(game_state: Res<GameState>) -> bool {
+    !game_state.finished
+}
+
+/// Whether entering [`AppState::InGame`] starts a new game, rather than resuming a paused one.
+///
+/// The game's entities outlive the pause menu and are only cleared once the game is over or
+/// abandoned.
+pub fn is_new_game(player_query: Query<(), With<Player>>) -> bool {
+    player_query.is_empty()
+}
+
+trait AddFixedEvent {
+    /// Registers an event that is cleared at the end of every fixed tick instead of every frame.
+    ///
+    /// Events written between two ticks are always seen by the next tick, no matter how many
+    /// frames pass in between.
+    fn add_fixed_event<T: Event>(&mut self) -> &mut Self;
+}
+
+impl AddFixedEvent for App {
+    fn add_fixed_event<T: Event>(&mut self) -> &mut Self {
+        if !self.world.contains_resource::<Events<T>>() {
+            self.init_resource::<Events<T>>().add_system(
+                Events::<T>::update_system
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                    .after(GameSet::Updates),
+            );
+        }
+        self
+    }
 }
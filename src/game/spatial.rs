@@ -0,0 +1,229 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use super::collider::Collider;
+use super::constants::SPATIAL_GRID_CELL_SIZE;
+
+/// A uniform grid of every collider on the playfield, rebuilt each tick.
+///
+/// Collision passes ask it for the entities near a shape and only run the exact test against
+/// those, instead of against everything of the kind they collide with.
+#[derive(Resource, Debug)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<Entity>>,
+}
+
+impl Default for SpatialGrid {
+    fn default() -> SpatialGrid {
+        SpatialGrid::new(SPATIAL_GRID_CELL_SIZE)
+    }
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> SpatialGrid {
+        SpatialGrid {
+            cell_size,
+            cells: HashMap::default(),
+        }
+    }
+
+    /// Empties every cell, keeping their storage for the next rebuild.
+    pub fn clear(&mut self) {
+        for entities in self.cells.values_mut() {
+            entities.clear();
+        }
+    }
+
+    /// Adds `entity` to every cell its bounding circle touches.
+    pub fn insert(&mut self, entity: Entity, center: Vec2, radius: f32) {
+        let (min, max) = self.cell_range(center, radius);
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                self.cells.entry(IVec2::new(x, y)).or_default().push(entity);
+            }
+        }
+    }
+
+    pub fn insert_collider(&mut self, entity: Entity, transform: &Transform, collider: &Collider) {
+        self.insert(
+            entity,
+            transform.translation.truncate(),
+            collider.bounding_radius(),
+        );
+    }
+
+    /// The entities sharing a cell with the circle, each once and in a stable order. They may
+    /// not actually overlap it.
+    pub fn query(&self, center: Vec2, radius: f32) -> Vec<Entity> {
+        let (min, max) = self.cell_range(center, radius);
+        let mut found = Vec::new();
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                if let Some(entities) = self.cells.get(&IVec2::new(x, y)) {
+                    found.extend_from_slice(entities);
+                }
+            }
+        }
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+
+    /// The entities that might overlap `collider` placed at `transform`.
+    pub fn nearby(&self, transform: &Transform, collider: &Collider) -> Vec<Entity> {
+        self.query(transform.translation.truncate(), collider.bounding_radius())
+    }
+
+    fn cell_range(&self, center: Vec2, radius: f32) -> (IVec2, IVec2) {
+        let cell = |position: Vec2| (position / self.cell_size).floor().as_ivec2();
+        (cell(center - radius), cell(center + radius))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    struct Shape {
+        entity: Entity,
+        transform: Transform,
+        collider: Collider,
+    }
+
+    /// Scatters `count` circles, boxes and triangles at random angles over a square field.
+    fn scatter(random: &mut ChaCha8Rng, count: u32, first_index: u32, field: f32) -> Vec<Shape> {
+        (0..count)
+            .map(|index| {
+                let collider = match random.gen_range(0..3) {
+                    0 => Collider::circle(random.gen_range(2.0..24.0)),
+                    1 => Collider::rect(Vec2::new(
+                        random.gen_range(2.0..40.0),
+                        random.gen_range(2.0..40.0),
+                    )),
+                    _ => Collider::polygon(vec![
+                        Vec2::new(0.0, 24.0),
+                        Vec2::new(-16.0, -24.0),
+                        Vec2::new(16.0, -24.0),
+                    ]),
+                };
+                let transform = Transform::from_xyz(
+                    random.gen_range(-field..field),
+                    random.gen_range(-field..field),
+                    0.0,
+                )
+                .with_rotation(Quat::from_rotation_z(random.gen_range(0.0..6.3)));
+
+                Shape {
+                    entity: Entity::from_raw(first_index + index),
+                    transform,
+                    collider,
+                }
+            })
+            .collect()
+    }
+
+    fn brute_force_pairs(hitters: &[Shape], targets: &[Shape]) -> Vec<(Entity, Entity)> {
+        let mut pairs = Vec::new();
+        for hitter in hitters {
+            for target in targets {
+                if hitter.collider.intersects(
+                    &hitter.transform,
+                    &target.collider,
+                    &target.transform,
+                ) {
+                    pairs.push((hitter.entity, target.entity));
+                }
+            }
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+
+    fn grid_pairs(hitters: &[Shape], targets: &[Shape]) -> Vec<(Entity, Entity)> {
+        let mut grid = SpatialGrid::default();
+        for target in targets {
+            grid.insert_collider(target.entity, &target.transform, &target.collider);
+        }
+        let by_entity: HashMap<Entity, &Shape> = targets
+            .iter()
+            .map(|target| (target.entity, target))
+            .collect();
+
+        let mut pairs = Vec::new();
+        for hitter in hitters {
+            for entity in grid.nearby(&hitter.transform, &hitter.collider) {
+                let target = by_entity[&entity];
+                if hitter.collider.intersects(
+                    &hitter.transform,
+                    &target.collider,
+                    &target.transform,
+                ) {
+                    pairs.push((hitter.entity, target.entity));
+                }
+            }
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+
+    #[test]
+    fn finds_the_same_pairs_as_checking_everything() {
+        let mut random = ChaCha8Rng::seed_from_u64(7);
+        let targets = scatter(&mut random, 400, 0, 400.0);
+        let hitters = scatter(&mut random, 100, 1000, 450.0);
+
+        let pairs = brute_force_pairs(&hitters, &targets);
+        assert!(!pairs.is_empty());
+        assert_eq!(grid_pairs(&hitters, &targets), pairs);
+    }
+
+    #[test]
+    fn shapes_bigger_than_a_cell_are_found_from_every_side() {
+        let mut grid = SpatialGrid::new(10.0);
+        let big = Entity::from_raw(1);
+        grid.insert(big, Vec2::ZERO, 25.0);
+
+        for probe in [
+            Vec2::new(-24.0, 0.0),
+            Vec2::new(24.0, 24.0),
+            Vec2::new(0.0, -24.0),
+        ] {
+            assert_eq!(grid.query(probe, 1.0), vec![big]);
+        }
+        assert!(grid.query(Vec2::new(45.0, 0.0), 1.0).is_empty());
+
+        grid.clear();
+        assert!(grid.query(Vec2::ZERO, 1.0).is_empty());
+    }
+
+    #[test]
+    fn a_crowded_field_only_checks_shapes_near_each_other() {
+        let mut random = ChaCha8Rng::seed_from_u64(7);
+        let targets = scatter(&mut random, 1000, 0, 1500.0);
+        let hitters = scatter(&mut random, 200, 10_000, 1500.0);
+
+        assert_eq!(
+            grid_pairs(&hitters, &targets),
+            brute_force_pairs(&hitters, &targets)
+        );
+
+        let mut grid = SpatialGrid::default();
+        for target in &targets {
+            grid.insert_collider(target.entity, &target.transform, &target.collider);
+        }
+        let candidates: usize = hitters
+            .iter()
+            .map(|hitter| grid.nearby(&hitter.transform, &hitter.collider).len())
+            .sum();
+        assert!(
+            candidates * 100 < hitters.len() * targets.len(),
+            "{} candidate pairs out of {}",
+            candidates,
+            hitters.len() * targets.len()
+        );
+    }
+}
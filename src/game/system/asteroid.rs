@@ -1,78 +1,154 @@
-use bevy::{prelude::*, window::PrimaryWindow};
+use bevy::prelude::*;
 use rand::Rng;
 
 use crate::{
-    game::components::{Asteroid, GameState},
+    config::game_config::GameConfig,
+    game::collider::Collider,
+    game::components::{Asteroid, AsteroidSize, GameRng, GameState, MeteorSprite, Playfield},
+    game::event::AsteroidDestroyedEvent,
     global::component::GameObject,
 };
 
-use super::utils::normalize_coords_in_window;
-
 pub fn spawn_asteroid(
     mut commands: Commands,
-    windows: Query<&Window, With<PrimaryWindow>>,
-    asset_server: Res<AssetServer>,
+    config: Res<GameConfig>,
+    playfield: Res<Playfield>,
+    mut game_state: ResMut<GameState>,
+    mut game_rng: ResMut<GameRng>,
 ) {
-    let window = windows.get_single().unwrap();
-    let mut random = rand::thread_rng();
+    let random = &mut game_rng.rng;
+    let spec = config.waves.spec(game_state.wave);
+    game_state.asteroids_left -= 1;
 
+    // Come in from a random point on one edge, just out of sight, heading somewhere inside
+    let bounds = playfield.spawn_bounds();
     let side = random.gen_range(0..4);
     let spawn_x = match side {
-        1 => -25.0,
-        3 => window.width() + 25.0,
-        _ => random.gen_range(-25.0..825.0),
+        1 => bounds.min.x,
+        3 => bounds.max.x,
+        _ => random.gen_range(bounds.min.x..bounds.max.x),
     };
     let spawn_y = match side {
-        0 => -25.0,
-        2 => window.height() + 25.0,
-        _ => random.gen_range(-25.0..825.0),
+        0 => bounds.min.y,
+        2 => bounds.max.y,
+        _ => random.gen_range(bounds.min.y..bounds.max.y),
     };
-    let spawn_coords = normalize_coords_in_window(window, Vec3::new(spawn_x, spawn_y, 0.0));
+    let spawn = Vec2::new(spawn_x, spawn_y);
 
-    let target_x = random.gen_range(0.0..window.width());
-    let target_y = random.gen_range(0.0..window.height());
-    let speed = random.gen_range(1.0..8.0);
+    let half_size = playfield.half_size();
+    let target = Vec2::new(
+        random.gen_range(-half_size.x..half_size.x),
+        random.gen_range(-half_size.y..half_size.y),
+    );
+    let speed = random.gen_range(spec.speed);
 
-    let trajectory = (Vec2::new(spawn_x, spawn_y) - Vec2::new(target_x, target_y)).normalize();
+    let trajectory = (target - spawn).normalize();
 
-    let asteroid_type = random.gen_range(1..5);
-    let asteroid_size = match asteroid_type {
-        1 | 3 => 48.0,
-        2 | 4 => 32.0,
-        _ => 0.0,
+    let size = if random.gen_bool(spec.large_chance) {
+        AsteroidSize::Large
+    } else {
+        AsteroidSize::Medium
     };
+    let max_rotation = config.asteroid.max_rotation;
+    let rotation = random.gen_range(-max_rotation..max_rotation);
+    let extent = config.asteroid.extent(size);
 
-    commands
-        .spawn(SpriteBundle {
-            transform: Transform {
-                translation: Vec3::new(spawn_coords.x, spawn_coords.y, 0.0),
-                ..Default::default()
-            },
-            texture: asset_server.load(format!("sprites/meteor/{}.png", asteroid_type)),
-            ..Default::default()
-        })
-        .insert(Asteroid {
+    insert_asteroid(
+        &mut commands,
+        random,
+        spawn.extend(0.0),
+        Asteroid {
+            size,
             trajectory,
             speed,
-            rotation: random.gen_range(-0.1..0.1),
-            width: asteroid_size,
-            height: asteroid_size,
-        })
+            rotation,
+        },
+        Collider::circle(extent / 2.0),
+    );
+}
+
+pub fn spawn_asteroid_fragments(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    mut game_rng: ResMut<GameRng>,
+    mut destroyed_reader: EventReader<AsteroidDestroyedEvent>,
+) {
+    let random = &mut game_rng.rng;
+    let asteroid_config = &config.asteroid;
+
+    for destroyed in destroyed_reader.iter() {
+        let Some(size) = destroyed.size.fragment() else {
+            continue;
+        };
+
+        let count = random
+            .gen_range(asteroid_config.fragment_count_min..=asteroid_config.fragment_count_max);
+        let extent = asteroid_config.extent(size);
+        for index in 0..count {
+            // Fan the fragments out evenly around the parent's heading, with a little jitter
+            let jitter = asteroid_config.fragment_jitter;
+            let offset = (index as f32 - (count - 1) as f32 / 2.0)
+                * asteroid_config.fragment_spread
+                + random.gen_range(-jitter..jitter);
+            let trajectory = Vec2::from_angle(offset).rotate(destroyed.trajectory);
+            let speed = destroyed.speed
+                * random.gen_range(
+                    asteroid_config.fragment_speedup_min..asteroid_config.fragment_speedup_max,
+                );
+            let max_rotation = asteroid_config.max_rotation;
+            let rotation = random.gen_range(-max_rotation..max_rotation);
+
+            insert_asteroid(
+                &mut commands,
+                random,
+                destroyed.translation,
+                Asteroid {
+                    size,
+                    trajectory,
+                    speed,
+                    rotation,
+                },
+                Collider::circle(extent / 2.0),
+            );
+        }
+    }
+}
+
+fn insert_asteroid(
+    commands: &mut Commands,
+    random: &mut impl Rng,
+    translation: Vec3,
+    asteroid: Asteroid,
+    collider: Collider,
+) {
+    // Sprites 1 and 3 are the large meteors, 2 and 4 the medium ones. Small fragments
+    // reuse the medium sprites at half scale.
+    let sprite = match asteroid.size {
+        AsteroidSize::Large => [1, 3][random.gen_range(0..2)],
+        AsteroidSize::Medium | AsteroidSize::Small => [2, 4][random.gen_range(0..2)],
+    };
+    let scale = asteroid.size.scale();
+
+    commands
+        .spawn(SpatialBundle::from_transform(Transform {
+            translation,
+            scale: Vec3::new(scale, scale, 1.0),
+            ..default()
+        }))
+        .insert(asteroid)
+        .insert(MeteorSprite(sprite))
+        .insert(collider)
         .insert(GameObject);
 }
 
 pub fn update_asteroid_spawn_timer(time: Res<FixedTime>, mut game_state: ResMut<GameState>) {
-    game_state.asteroid_spawn_timer.tick(time.period);
-    game_state.asteroid_rate_increase_timer.tick(time.period);
-
-    if game_state.asteroid_rate_increase_timer.just_finished() {
-        game_state.asteroid_spawn_timer = Timer::new(
-            game_state.asteroid_spawn_timer.duration().mul_f32(0.8),
-            TimerMode::Repeating,
-        );
+    if game_state.wave_in_progress() {
+        game_state.asteroid_spawn_timer.tick(time.period);
     }
 }
 
 pub fn asteroid_spawn_timer(game_state: Res<GameState>) -> bool {
-    game_state.asteroid_spawn_timer.just_finished()
+    game_state.wave_in_progress()
+        && game_state.asteroids_left > 0
+        && game_state.asteroid_spawn_timer.just_finished()
 }
@@ -1,58 +1,312 @@
 use bevy::prelude::*;
+use bevy::utils::HashSet;
 
+use crate::config::game_config::GameConfig;
+use crate::game::collider::Collider;
 use crate::game::components::*;
 use crate::game::event::*;
+use crate::game::spatial::SpatialGrid;
 use crate::global::component::Scoreboard;
 
-use bevy::sprite::collide_aabb::collide;
+/// An entity's collider and where it is.
+type Placed<'a> = (Entity, &'a Transform, &'a Collider);
+/// Entities of kind `T` that haven't been used up by a collision yet.
+type Live<T> = (With<T>, Without<Destroyed>);
 
-type LaserQualifiers = (With<Laser>, Without<Asteroid>);
+/// Rebuilds the broad phase from where everything ended up after this tick's movement.
+pub fn update_spatial_grid(mut grid: ResMut<SpatialGrid>, collider_query: Query<Placed>) {
+    grid.clear();
+    for (entity, transform, collider) in collider_query.iter() {
+        grid.insert_collider(entity, transform, collider);
+    }
+}
 
 pub fn check_player_collisions(
-    mut commands: Commands,
-    asteroid_query: Query<(Entity, &Transform, &Asteroid), Without<Player>>,
-    player_query: Query<(&Transform, &Player), Without<Asteroid>>,
-    mut player_hit_writer: EventWriter<PlayerHitEvent>,
+    grid: Res<SpatialGrid>,
+    asteroid_query: Query<Placed, Live<Asteroid>>,
+    player_query: Query<Placed, With<Player>>,
+    mut collision_writer: EventWriter<CollisionEvent>,
+) {
+    for (player_entity, player_transform, player_collider) in player_query.iter() {
+        let nearby = grid.nearby(player_transform, player_collider);
+
+        for (asteroid_entity, asteroid_transform, asteroid_collider) in
+            asteroid_query.iter_many(nearby)
+        {
+            let player_collision =
+                player_collider.intersects(player_transform, asteroid_collider, asteroid_transform);
+
+            if player_collision {
+                collision_writer.send(CollisionEvent {
+                    contact: Contact::PlayerAsteroid,
+                    entity: player_entity,
+                    other: asteroid_entity,
+                });
+            }
+        }
+    }
+}
+
+pub fn check_saucer_collisions(
+    grid: Res<SpatialGrid>,
+    saucer_query: Query<Placed, Live<Saucer>>,
+    laser_query: Query<Placed, Live<Laser>>,
+    player_query: Query<Placed, With<Player>>,
+    mut collision_writer: EventWriter<CollisionEvent>,
+) {
+    for (saucer_entity, saucer_transform, saucer_collider) in saucer_query.iter() {
+        let nearby = grid.nearby(saucer_transform, saucer_collider);
+        for (laser_entity, laser_transform, laser_collider) in laser_query.iter_many(&nearby) {
+            let shot =
+                laser_collider.intersects(laser_transform, saucer_collider, saucer_transform);
+
+            if shot {
+                collision_writer.send(CollisionEvent {
+                    contact: Contact::LaserSaucer,
+                    entity: laser_entity,
+                    other: saucer_entity,
+                });
+            }
+        }
+
+        for (player_entity, player_transform, player_collider) in player_query.iter_many(&nearby) {
+            let rammed =
+                player_collider.intersects(player_transform, saucer_collider, saucer_transform);
+            if rammed {
+                collision_writer.send(CollisionEvent {
+                    contact: Contact::PlayerSaucer,
+                    entity: player_entity,
+                    other: saucer_entity,
+                });
+            }
+        }
+    }
+}
+
+pub fn check_saucer_shot_collisions(
+    grid: Res<SpatialGrid>,
+    shot_query: Query<Placed, Live<SaucerShot>>,
+    player_query: Query<Placed, With<Player>>,
+    mut collision_writer: EventWriter<CollisionEvent>,
 ) {
-    let (player_transform, player) = player_query.single();
+    for (player_entity, player_transform, player_collider) in player_query.iter() {
+        let nearby = grid.nearby(player_transform, player_collider);
 
-    for (asteroid_entity, asteroid_transform, asteroid) in asteroid_query.iter() {
-        let player_collision = collide(
-            player_transform.translation,
-            player.get_box(),
-            asteroid_transform.translation,
-            asteroid.get_box(),
-        );
+        for (shot_entity, shot_transform, shot_collider) in shot_query.iter_many(nearby) {
+            let hit = player_collider.intersects(player_transform, shot_collider, shot_transform);
 
-        if player_collision.is_some() {
-            commands.entity(asteroid_entity).despawn();
-            if !player.invincible {
-                player_hit_writer.send(PlayerHitEvent);
+            if hit {
+                collision_writer.send(CollisionEvent {
+                    contact: Contact::PlayerSaucerShot,
+                    entity: player_entity,
+                    other: shot_entity,
+                });
+            }
+        }
+    }
+}
+
+pub fn check_power_up_pickups(
+    grid: Res<SpatialGrid>,
+    power_up_query: Query<Placed, Live<PowerUp>>,
+    player_query: Query<Placed, With<Player>>,
+    mut collision_writer: EventWriter<CollisionEvent>,
+) {
+    for (player_entity, player_transform, player_collider) in player_query.iter() {
+        let nearby = grid.nearby(player_transform, player_collider);
+
+        for (power_up_entity, power_up_transform, power_up_collider) in
+            power_up_query.iter_many(nearby)
+        {
+            let pickup =
+                player_collider.intersects(player_transform, power_up_collider, power_up_transform);
+
+            if pickup {
+                collision_writer.send(CollisionEvent {
+                    contact: Contact::PlayerPowerUp,
+                    entity: player_entity,
+                    other: power_up_entity,
+                });
+            }
+        }
+    }
+}
+
+/// Lasers hitting any ship but the one that fired them, in versus games only.
+pub fn check_laser_player_collisions(
+    mode: Res<GameMode>,
+    grid: Res<SpatialGrid>,
+    laser_query: Query<(Entity, &Transform, &Collider, &Laser), Without<Destroyed>>,
+    player_query: Query<(Entity, &Transform, &Collider, &Player)>,
+    mut collision_writer: EventWriter<CollisionEvent>,
+) {
+    if *mode != GameMode::Versus {
+        return;
+    }
+
+    for (player_entity, player_transform, player_collider, player) in player_query.iter() {
+        let nearby = grid.nearby(player_transform, player_collider);
+
+        for (laser_entity, laser_transform, laser_collider, laser) in laser_query.iter_many(nearby)
+        {
+            let hit = laser.owner != player.id
+                && player_collider.intersects(player_transform, laser_collider, laser_transform);
+
+            if hit {
+                collision_writer.send(CollisionEvent {
+                    contact: Contact::PlayerLaser,
+                    entity: player_entity,
+                    other: laser_entity,
+                });
             }
         }
     }
 }
 
 pub fn check_laser_collisions(
+    grid: Res<SpatialGrid>,
+    asteroid_query: Query<Placed, Live<Asteroid>>,
+    laser_query: Query<Placed, Live<Laser>>,
+    mut collision_writer: EventWriter<CollisionEvent>,
+) {
+    // Only asteroids near a laser can be hit. They are still visited in query order, so the
+    // destroyed events (and the fragments they spawn) come out the same as without the grid.
+    let targets: HashSet<Entity> = laser_query
+        .iter()
+        .flat_map(|(_, laser_transform, laser_collider)| {
+            grid.nearby(laser_transform, laser_collider)
+        })
+        .collect();
+
+    for (asteroid_entity, asteroid_transform, asteroid_collider) in asteroid_query.iter() {
+        if !targets.contains(&asteroid_entity) {
+            continue;
+        }
+
+        let nearby = grid.nearby(asteroid_transform, asteroid_collider);
+        for (laser_entity, laser_transform, laser_collider) in laser_query.iter_many(nearby) {
+            let collision =
+                laser_collider.intersects(laser_transform, asteroid_collider, asteroid_transform);
+
+            if collision {
+                collision_writer.send(CollisionEvent {
+                    contact: Contact::LaserAsteroid,
+                    entity: laser_entity,
+                    other: asteroid_entity,
+                });
+            }
+        }
+    }
+}
+
+/// Applies this tick's contacts in the order they were found.
+///
+/// Everything but the players is used up by its first contact and marked [`Destroyed`], so a
+/// laser only ever destroys one thing and an asteroid only scores once, for whoever fired the
+/// laser. Each player only takes one hit per tick.
+#[allow(clippy::too_many_arguments)]
+pub fn resolve_collisions(
     mut commands: Commands,
+    config: Res<GameConfig>,
     mut scoreboard: ResMut<Scoreboard>,
-    asteroid_query: Query<(Entity, &Transform, &Asteroid), Without<Laser>>,
-    laser_query: Query<(Entity, &Transform), LaserQualifiers>,
+    mut collision_reader: EventReader<CollisionEvent>,
+    laser_query: Query<&Laser>,
+    asteroid_query: Query<(&Transform, &Asteroid)>,
+    saucer_query: Query<&Saucer>,
+    power_up_query: Query<&PowerUp>,
+    destroyed_query: Query<(), With<Destroyed>>,
+    mut player_query: Query<(Entity, &mut Player, &mut ActivePowerUps)>,
+    mut player_hit_writer: EventWriter<PlayerHitEvent>,
+    mut destroyed_writer: EventWriter<AsteroidDestroyedEvent>,
 ) {
-    for (asteroid_entity, asteroid_transform, asteroid) in asteroid_query.iter() {
-        for (laser_entity, laser_transform) in laser_query.iter() {
-            let collision = collide(
-                laser_transform.translation,
-                laser_transform.scale.truncate(),
-                asteroid_transform.translation,
-                asteroid.get_box(),
-            );
+    let mut protected: HashSet<Entity> = player_query
+        .iter()
+        .filter(|(_, player, power_ups)| is_protected(player, power_ups))
+        .map(|(entity, _, _)| entity)
+        .collect();
+    // Markers only land once commands are applied, so this tick's are tracked here as well
+    let mut used_up: HashSet<Entity> = HashSet::default();
 
-            if collision.is_some() {
-                scoreboard.score += asteroid.speed as usize;
-                commands.entity(asteroid_entity).despawn();
-                commands.entity(laser_entity).despawn();
+    for collision in collision_reader.iter() {
+        let CollisionEvent {
+            contact,
+            entity,
+            other,
+        } = *collision;
+        let player_contact = !matches!(contact, Contact::LaserAsteroid | Contact::LaserSaucer);
+        let is_used_up =
+            |entity: Entity| used_up.contains(&entity) || destroyed_query.contains(entity);
+        if is_used_up(other) || (!player_contact && is_used_up(entity)) {
+            continue;
+        }
+
+        match contact {
+            Contact::LaserAsteroid => {
+                let Ok((transform, asteroid)) = asteroid_query.get(other) else {
+                    continue;
+                };
+                award(&mut scoreboard, &laser_query, entity, asteroid.size.score());
+                destroyed_writer.send(AsteroidDestroyedEvent {
+                    translation: transform.translation,
+                    size: asteroid.size,
+                    trajectory: asteroid.trajectory,
+                    speed: asteroid.speed,
+                });
+            }
+            Contact::LaserSaucer => {
+                let Ok(saucer) = saucer_query.get(other) else {
+                    continue;
+                };
+                award(&mut scoreboard, &laser_query, entity, saucer.size.score());
+            }
+            Contact::PlayerAsteroid
+            | Contact::PlayerSaucer
+            | Contact::PlayerSaucerShot
+            | Contact::PlayerLaser => {
+                if protected.insert(entity) {
+                    player_hit_writer.send(PlayerHitEvent { player: entity });
+                }
             }
+            Contact::PlayerPowerUp => {
+                let (Ok(power_up), Ok((_, mut player, mut power_ups))) =
+                    (power_up_query.get(other), player_query.get_mut(entity))
+                else {
+                    continue;
+                };
+                match config.power_up.duration(power_up.kind) {
+                    Some(seconds) => power_ups.activate(power_up.kind, seconds),
+                    None => player.lives = (player.lives + 1).min(config.player.max_lives),
+                }
+            }
+        }
+
+        let mut use_up = |entity: Entity| {
+            used_up.insert(entity);
+            commands.entity(entity).insert(Destroyed);
+        };
+        use_up(other);
+        if !player_contact {
+            use_up(entity);
+        }
+    }
+}
+
+pub fn despawn_destroyed(mut commands: Commands, destroyed_query: Query<Entity, With<Destroyed>>) {
+    for entity in destroyed_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Adds `score` to the score of whoever fired `laser`.
+fn award(scoreboard: &mut Scoreboard, laser_query: &Query<&Laser>, laser: Entity, score: usize) {
+    if let Ok(laser) = laser_query.get(laser) {
+        if let Some(total) = scoreboard.scores.get_mut(laser.owner.0) {
+            *total += score;
         }
     }
 }
+
+/// Whether the player can't be hurt right now, after a respawn or behind a shield.
+fn is_protected(player: &Player, power_ups: &ActivePowerUps) -> bool {
+    player.invincible || power_ups.is_active(PowerUpKind::Shield)
+}
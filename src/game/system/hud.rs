@@ -0,0 +1,329 @@
+//! The in-game HUD: scores, lives, power-ups and the wave. Only drawn, so a game without a
+//! window gets by without it.
+
+use bevy::prelude::*;
+
+use crate::config::game_config::GameConfig;
+use crate::game::components::*;
+use crate::game::constants::*;
+use crate::global::component::{GameObject, Scoreboard};
+use crate::global::constants::SCOREBOARD_FONT_SIZE;
+use crate::global::palette::Palette;
+
+/// The score of each player, one line each, with the active power-ups underneath.
+pub fn setup_scoreboard(
+    mut commands: Commands,
+    mode: Res<GameMode>,
+    palette: Res<Palette>,
+    asset_server: Res<AssetServer>,
+) {
+    let font: Handle<Font> = asset_server.load("fonts/Excluded.ttf");
+    let players = mode.players();
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: SCOREBOARD_TEXT_PADDING,
+                    left: SCOREBOARD_TEXT_PADDING,
+                    ..default()
+                },
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            for id in (0..players).map(PlayerId) {
+                // With several players each line is labelled, and drawn in the ship's colour
+                let (label, color) = match players {
+                    1 => ("Score: ".to_string(), Color::WHITE),
+                    _ => (format!("{} ", id.label()), id.tint(*palette)),
+                };
+                let style = TextStyle {
+                    font: font.clone(),
+                    font_size: SCOREBOARD_FONT_SIZE,
+                    color,
+                };
+                parent
+                    .spawn(TextBundle::from_sections([
+                        TextSection::new(label, style.clone()),
+                        TextSection::from_style(style),
+                    ]))
+                    .insert(ScoreBoardText(id))
+                    .insert(GameObject);
+            }
+
+            // The active power-ups go below the score lines, however many there are
+            parent
+                .spawn(
+                    TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: POWER_UP_FONT_SIZE,
+                            color: Color::WHITE,
+                        },
+                    )
+                    .with_style(Style {
+                        margin: UiRect {
+                            top: SCOREBOARD_TEXT_PADDING,
+                            ..default()
+                        },
+                        ..default()
+                    }),
+                )
+                .insert(PowerUpText)
+                .insert(GameObject);
+        })
+        .insert(GameObject);
+}
+
+pub fn setup_life_counter(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    mode: Res<GameMode>,
+    palette: Res<Palette>,
+    playfield: Res<Playfield>,
+    asset_server: Res<AssetServer>,
+) {
+    for player in (0..mode.players()).map(PlayerId) {
+        let tint = player.tint(*palette);
+        for life in 1..=config.player.lives {
+            spawn_life(
+                &mut commands,
+                &asset_server,
+                &playfield,
+                Life {
+                    player,
+                    counter: life,
+                    lost: false,
+                },
+                tint,
+            );
+        }
+    }
+}
+
+/// Spawns one slot of a player's life counter, counting leftwards from the top right corner.
+/// Each player has their own row, below the one before.
+pub fn spawn_life(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    playfield: &Playfield,
+    life: Life,
+    tint: Color,
+) {
+    let Life {
+        player,
+        counter,
+        lost,
+    } = life;
+
+    let (texture, scale) = if lost {
+        (asset_server.load("sprites/lost_life.png"), 0.6)
+    } else {
+        (asset_server.load("sprites/life.png"), 1.0)
+    };
+
+    commands
+        .spawn(SpriteBundle {
+            transform: Transform {
+                translation: Vec3::new(
+                    (playfield.width / 2.0) - (counter as f32 * LIFE_PADDING),
+                    (playfield.height / 2.0) - (player.0 + 1) as f32 * LIFE_PADDING,
+                    1.0,
+                ),
+                scale: Vec3::new(scale, scale, 1.0),
+                ..Default::default()
+            },
+            sprite: Sprite {
+                color: tint,
+                ..default()
+            },
+            texture,
+            ..Default::default()
+        })
+        .insert(life)
+        .insert(GameObject);
+}
+
+pub fn setup_wave_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font: Handle<Font> = asset_server.load("fonts/Excluded.ttf");
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::width(Val::Percent(100.0)),
+                position: UiRect {
+                    top: SCOREBOARD_TEXT_PADDING,
+                    ..default()
+                },
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: SCOREBOARD_FONT_SIZE,
+                        color: Color::WHITE,
+                    },
+                ))
+                .insert(WaveText)
+                .insert(GameObject);
+        })
+        .insert(GameObject);
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("fonts/ExcludedItalic.ttf"),
+                        font_size: WAVE_BANNER_FONT_SIZE,
+                        color: Color::WHITE,
+                    },
+                ))
+                .insert(WaveBanner)
+                .insert(GameObject);
+        })
+        .insert(GameObject);
+}
+
+pub fn update_scoreboard(
+    scoreboard: Res<Scoreboard>,
+    mut query: Query<(&mut Text, &ScoreBoardText)>,
+) {
+    for (mut text, ScoreBoardText(player)) in query.iter_mut() {
+        text.sections[1].value = scoreboard.scores[player.0].to_string();
+    }
+}
+
+/// Rebuilds the life counter whenever it no longer matches the players' lives. A player who is
+/// out of the game keeps a row of lost lives.
+#[allow(clippy::too_many_arguments)]
+pub fn update_life_counter(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    mode: Res<GameMode>,
+    palette: Res<Palette>,
+    playfield: Res<Playfield>,
+    player_query: Query<&Player>,
+    life_query: Query<(Entity, &Life)>,
+    asset_server: Res<AssetServer>,
+) {
+    let lives: Vec<u8> = (0..mode.players())
+        .map(|index| {
+            player_query
+                .iter()
+                .find(|player| player.id.0 == index)
+                .map_or(0, |player| player.lives)
+        })
+        .collect();
+    let slots = |lives: u8| lives.max(config.player.lives);
+
+    let up_to_date = life_query.iter().count()
+        == lives
+            .iter()
+            .map(|lives| slots(*lives) as usize)
+            .sum::<usize>()
+        && life_query.iter().all(|(_, life)| {
+            lives
+                .get(life.player.0)
+                .is_some_and(|lives| life.lost == (life.counter > *lives))
+        });
+    if up_to_date {
+        return;
+    }
+
+    for (entity, _) in life_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    for (player, lives) in lives.into_iter().enumerate() {
+        let player = PlayerId(player);
+        for counter in 1..=slots(lives) {
+            spawn_life(
+                &mut commands,
+                &asset_server,
+                &playfield,
+                Life {
+                    player,
+                    counter,
+                    lost: counter > lives,
+                },
+                player.tint(*palette),
+            );
+        }
+    }
+}
+
+pub fn update_power_up_hud(
+    mode: Res<GameMode>,
+    player_query: Query<(&Player, &ActivePowerUps)>,
+    mut text_query: Query<&mut Text, With<PowerUpText>>,
+) {
+    let mut players: Vec<_> = player_query.iter().collect();
+    players.sort_by_key(|(player, _)| player.id);
+
+    // With several players each line says whose power-up it is
+    let label = players
+        .into_iter()
+        .flat_map(|(player, power_ups)| {
+            let prefix = match *mode {
+                GameMode::Solo => String::new(),
+                _ => format!("{} ", player.id.label()),
+            };
+            power_ups.iter().map(move |(kind, remaining)| {
+                format!(
+                    "{}{} {}",
+                    prefix,
+                    kind.label(),
+                    remaining.as_secs_f32().ceil()
+                )
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value.clone_from(&label);
+    }
+}
+
+pub fn update_wave_hud(
+    game_state: Res<GameState>,
+    mut wave_text_query: Query<&mut Text, (With<WaveText>, Without<WaveBanner>)>,
+    mut banner_query: Query<(&mut Text, &mut Visibility), With<WaveBanner>>,
+) {
+    let label = format!("Wave {}", game_state.wave);
+
+    for mut text in wave_text_query.iter_mut() {
+        text.sections[0].value = label.clone();
+    }
+    for (mut text, mut visibility) in banner_query.iter_mut() {
+        text.sections[0].value = label.clone();
+        *visibility = if game_state.wave_in_progress() {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+    }
+}
@@ -1,70 +1,69 @@
-use bevy::{prelude::*, window::PrimaryWindow};
+use bevy::prelude::*;
 
 use std::f32::consts::PI;
 
+use crate::action::state::{Action, ActionState, TickInput};
+use crate::config::game_config::GameConfig;
 use crate::game::{components::*, event::FireEvent};
+use crate::global::state::AppState;
 
-use super::utils::{calculate_angle, normalize_coords_in_window};
+use super::utils::calculate_angle;
 
 pub fn aiming_handler(
-    windows: Query<&Window, With<PrimaryWindow>>,
-    mut player_transform_query: Query<&mut Transform, With<Player>>,
+    time: Res<FixedTime>,
+    config: Res<GameConfig>,
+    input: Res<TickInput>,
+    mut player_transform_query: Query<(&mut Transform, &Player)>,
 ) {
-    let window = windows.get_single().unwrap();
-
-    if let Some(_position) = window.cursor_position() {
-        for mut transform in player_transform_query.iter_mut() {
-            let player_pos = normalize_coords_in_window(window, transform.translation);
-            let angle = calculate_angle(_position, player_pos);
+    for (mut transform, player) in player_transform_query.iter_mut() {
+        let Some(actions) = input.player(player.id.0) else {
+            continue;
+        };
+        if let Some(target) = actions.aim {
+            let angle = calculate_angle(target, transform.translation.truncate());
 
             transform.rotation = Quat::from_axis_angle(Vec3::new(0., 0., 1.), angle + (PI / 2.0));
+        } else if actions.pressed(Action::Rotate) {
+            let turn_rate = actions.rotation * config.player.rotation_speed;
+            transform.rotate_z(turn_rate * time.period.as_secs_f32());
         }
     }
 }
 
-pub fn shooting_handler(buttons: Res<Input<MouseButton>>, mut fire_writer: EventWriter<FireEvent>) {
-    if buttons.just_pressed(MouseButton::Left) {
-        fire_writer.send(FireEvent);
+pub fn thrust_handler(input: Res<TickInput>, mut player_query: Query<&mut Player>) {
+    for mut player in player_query.iter_mut() {
+        player.thrusting = input
+            .player(player.id.0)
+            .is_some_and(|actions| actions.pressed(Action::Thrust));
     }
 }
 
-pub fn pause_handler(
-    mut commands: Commands,
-    keyboard_input: Res<Input<KeyCode>>,
-    mut game_state: ResMut<GameState>,
-    paused_text_query: Query<Entity, With<PausedText>>,
-    asset_server: Res<AssetServer>,
+pub fn shooting_handler(
+    time: Res<FixedTime>,
+    input: Res<TickInput>,
+    mut player_query: Query<(Entity, &mut Player, &ActivePowerUps)>,
+    mut fire_writer: EventWriter<FireEvent>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::Escape) {
-        if !game_state.paused {
-            commands
-                .spawn(
-                    TextBundle::from_section(
-                        "Paused",
-                        TextStyle {
-                            font: asset_server.load("fonts/ExcludedItalic.ttf"),
-                            font_size: 60.0,
-                            color: Color::WHITE,
-                        },
-                    )
-                    .with_style(Style {
-                        display: Display::Flex,
-                        position_type: PositionType::Absolute,
-                        position: UiRect {
-                            top: Val::Px(200.0),
-                            left: Val::Px(275.0),
-                            ..default()
-                        },
-                        ..default()
-                    }),
-                )
-                .insert(PausedText);
+    for (entity, mut player, power_ups) in player_query.iter_mut() {
+        player.fire_cooldown.tick(time.period);
+        let Some(actions) = input.player(player.id.0) else {
+            continue;
+        };
+
+        // Rapid fire keeps shooting for as long as fire is held
+        let auto_fire = power_ups.is_active(PowerUpKind::RapidFire)
+            && actions.pressed(Action::Fire)
+            && player.fire_cooldown.finished();
 
-            game_state.paused = true;
-        } else {
-            let entity = paused_text_query.get_single().unwrap();
-            commands.entity(entity).despawn();
-            game_state.paused = false;
+        if actions.just_pressed(Action::Fire) || auto_fire {
+            player.fire_cooldown.reset();
+            fire_writer.send(FireEvent { player: entity });
         }
     }
 }
+
+pub fn pause_listener(actions: Res<ActionState>, mut next_state: ResMut<NextState<AppState>>) {
+    if actions.just_pressed(Action::Pause) {
+        next_state.set(AppState::Paused);
+    }
+}
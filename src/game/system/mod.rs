@@ -1,8 +1,13 @@
 pub mod asteroid;
 pub mod collision;
+pub mod hud;
 pub mod input;
 pub mod movement;
 pub mod player;
+pub mod power_up;
+pub mod saucer;
 pub mod setup;
+pub mod sprite;
+pub mod wave;
 
 mod utils;
@@ -1,53 +1,134 @@
-use bevy::{prelude::*, window::PrimaryWindow};
+use bevy::prelude::*;
 
-use crate::game::components::Asteroid;
-use crate::game::components::Laser;
+use crate::config::game_config::GameConfig;
+use crate::game::components::*;
+use crate::game::constants::*;
 
-use super::utils::normalize_coords_in_window;
+use super::utils::wrap_in_playfield;
+
+pub fn player_movement(
+    time: Res<FixedTime>,
+    config: Res<GameConfig>,
+    playfield: Res<Playfield>,
+    mut player_query: Query<(&mut Transform, &mut Velocity, &Player)>,
+) {
+    let delta = time.period.as_secs_f32();
+    let player_config = &config.player;
+
+    for (mut transform, mut velocity, player) in player_query.iter_mut() {
+        if player.thrusting {
+            let facing = (transform.rotation * Vec3::Y).truncate();
+            velocity.0 += facing * player_config.thrust * delta;
+        }
+
+        velocity.0 *= (1.0 - player_config.drag * delta).max(0.0);
+        velocity.0 = velocity.0.clamp_length_max(player_config.max_speed);
+
+        transform.translation += velocity.0.extend(0.0) * delta;
+        wrap_in_playfield(&playfield, &mut transform.translation, WRAP_MARGIN);
+    }
+}
 
 pub fn laser_movement(
     mut commands: Commands,
-    windows: Query<&Window, With<PrimaryWindow>>,
+    time: Res<FixedTime>,
+    playfield: Res<Playfield>,
     mut laser_transforms: Query<(Entity, &mut Transform, &Laser)>,
 ) {
-    let window = windows.get_single().unwrap();
+    let delta = time.period.as_secs_f32();
 
     for (entity, mut transform, laser) in laser_transforms.iter_mut() {
-        let coords = normalize_coords_in_window(window, transform.translation);
-        if coords.x > window.width() + 50.0
-            || coords.y > window.height() + 50.0
-            || coords.x < -50.0
-            || coords.y < -50.0
+        if !playfield
+            .despawn_bounds()
+            .contains(transform.translation.truncate())
         {
             commands.entity(entity).despawn();
         } else {
-            transform.translation += Vec3::new(laser.velocity.x, laser.velocity.y, 0.0);
+            transform.translation += laser.velocity.extend(0.0) * delta;
         }
     }
 }
 
 pub fn asteroid_movement(
     mut commands: Commands,
-    windows: Query<&Window, With<PrimaryWindow>>,
+    time: Res<FixedTime>,
+    playfield: Res<Playfield>,
     mut asteroid_transforms: Query<(Entity, &mut Transform, &Asteroid)>,
 ) {
-    let window = windows.get_single().unwrap();
+    let delta = time.period.as_secs_f32();
 
     for (entity, mut transform, asteroid) in asteroid_transforms.iter_mut() {
-        let coords = normalize_coords_in_window(window, transform.translation);
-        if coords.x > window.width() + 50.0
-            || coords.y > window.height() + 50.0
-            || coords.x < -50.0
-            || coords.y < -50.0
+        if !playfield
+            .despawn_bounds()
+            .contains(transform.translation.truncate())
+        {
+            commands.entity(entity).despawn();
+        } else {
+            transform.translation += (asteroid.trajectory * asteroid.speed).extend(0.0) * delta;
+            transform.rotate_z(asteroid.rotation * delta);
+        }
+    }
+}
+
+pub fn saucer_movement(
+    mut commands: Commands,
+    time: Res<FixedTime>,
+    config: Res<GameConfig>,
+    playfield: Res<Playfield>,
+    mut saucer_query: Query<(Entity, &mut Transform, &mut Saucer)>,
+) {
+    let delta = time.period.as_secs_f32();
+
+    for (entity, mut transform, mut saucer) in saucer_query.iter_mut() {
+        // Saucers leave once they've crossed to the far side
+        if transform.translation.x * saucer.direction > playfield.despawn_bounds().max.x {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        saucer.age += delta;
+        transform.translation.x += saucer.direction * saucer.size.speed() * delta;
+        let weave = (saucer.age * config.saucer.wave_frequency).sin();
+        transform.translation.y = saucer.course + config.saucer.wave_amplitude * weave;
+    }
+}
+
+pub fn saucer_shot_movement(
+    mut commands: Commands,
+    time: Res<FixedTime>,
+    playfield: Res<Playfield>,
+    mut shot_query: Query<(Entity, &mut Transform, &SaucerShot)>,
+) {
+    let delta = time.period.as_secs_f32();
+
+    for (entity, mut transform, shot) in shot_query.iter_mut() {
+        if !playfield
+            .despawn_bounds()
+            .contains(transform.translation.truncate())
         {
             commands.entity(entity).despawn();
         } else {
-            transform.translation += Vec3::new(
-                asteroid.trajectory.x * asteroid.speed,
-                asteroid.trajectory.y * asteroid.speed,
-                0.0,
-            );
-            transform.rotate_z(asteroid.rotation);
+            transform.translation += shot.velocity.extend(0.0) * delta;
         }
     }
 }
+
+pub fn power_up_movement(
+    mut commands: Commands,
+    time: Res<FixedTime>,
+    playfield: Res<Playfield>,
+    mut power_up_query: Query<(Entity, &mut Transform, &mut PowerUp)>,
+) {
+    let delta = time.period.as_secs_f32();
+
+    for (entity, mut transform, mut power_up) in power_up_query.iter_mut() {
+        power_up.lifetime.tick(time.period);
+        if power_up.lifetime.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        transform.translation += power_up.velocity.extend(0.0) * delta;
+        wrap_in_playfield(&playfield, &mut transform.translation, WRAP_MARGIN);
+    }
+}
@@ -1,9 +1,9 @@
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
 
-use super::utils::normalize_coords_in_window;
+use crate::config::game_config::GameConfig;
+use crate::game::collider::Collider;
 use crate::game::components::*;
-use crate::game::constants::*;
+use crate::game::constants::LASER_SIZE;
 use crate::game::event::*;
 use crate::global::component::*;
 use crate::global::event::GameOverEvent;
@@ -11,92 +11,78 @@ use crate::global::state::AppState;
 
 pub fn shoot(
     mut commands: Commands,
+    config: Res<GameConfig>,
     mut fire_reader: EventReader<FireEvent>,
-    mut player_transform: Query<&mut Transform, With<Player>>,
-    windows: Query<&Window, With<PrimaryWindow>>,
-    asset_server: Res<AssetServer>,
+    player_query: Query<(&Transform, &Player, &ActivePowerUps)>,
 ) {
-    if fire_reader.iter().next().is_some() {
-        let window = windows.get_single().unwrap();
-        if let Some(_position) = window.cursor_position() {
-            let mut transform = *player_transform.get_single_mut().unwrap();
+    for fire in fire_reader.iter() {
+        let Ok((transform, player, power_ups)) = player_query.get(fire.player) else {
+            continue;
+        };
+        let spread = config.power_up.spread_shot_angle;
+        let angles: &[f32] = if power_ups.is_active(PowerUpKind::SpreadShot) {
+            &[-spread, 0.0, spread]
+        } else {
+            &[0.0]
+        };
+
+        for angle in angles {
+            let mut transform = *transform;
+            transform.rotate_z(*angle);
             transform.scale = Vec3::new(0.3, 0.3, 0.0);
 
-            let trajectory =
-                (_position - normalize_coords_in_window(window, transform.translation)).normalize();
+            let trajectory = (transform.rotation * Vec3::Y).truncate();
 
             commands
-                .spawn(SpriteBundle {
-                    transform,
-                    texture: asset_server.load("sprites/effect_yellow.png"),
-                    ..Default::default()
-                })
+                .spawn(SpatialBundle::from_transform(transform))
                 .insert(Laser {
-                    velocity: Vec2::new(trajectory.x * LASER_SPEED, trajectory.y * LASER_SPEED),
+                    velocity: trajectory * config.laser.speed,
+                    owner: player.id,
                 })
+                .insert(Collider::rect(LASER_SIZE))
                 .insert(GameObject);
         }
     }
 }
 
-pub fn update_scoreboard(
-    scoreboard: Res<Scoreboard>,
-    mut query: Query<&mut Text, With<ScoreBoardText>>,
-) {
-    let mut text = query.single_mut();
-    text.sections[1].value = scoreboard.score.to_string();
-}
-
-pub fn update_life_counter(
+/// Takes a life from each ship that was hit. A ship that runs out of lives leaves the game
+/// while anyone else is still playing, and the game is over once nobody is. A versus round is
+/// over as soon as anyone is out.
+pub fn lose_life_listener(
     mut commands: Commands,
-    windows: Query<&Window, With<PrimaryWindow>>,
-    mut player_query: Query<&mut Player>,
-    mut life_query: Query<(Entity, &Life)>,
-    asset_server: Res<AssetServer>,
+    mode: Res<GameMode>,
+    mut player_query: Query<(Entity, &mut Player)>,
     mut player_hit_reader: EventReader<PlayerHitEvent>,
     mut game_over_writer: EventWriter<GameOverEvent>,
 ) {
-    let lost_life_image: Handle<Image> = asset_server.load("sprites/lost_life.png");
-    let window = windows.get_single().unwrap();
-
-    if player_hit_reader.iter().next().is_some() {
-        let mut player = player_query.get_single_mut().unwrap();
-        player.lives -= 1;
-
-        if player.lives == 0 {
-            game_over_writer.send(GameOverEvent);
+    for hit in player_hit_reader.iter() {
+        if let Ok((_, mut player)) = player_query.get_mut(hit.player) {
+            player.lives = player.lives.saturating_sub(1);
         }
+    }
+
+    let (out, playing): (Vec<_>, Vec<_>) = player_query
+        .iter()
+        .partition(|(_, player)| player.lives == 0);
+    if out.is_empty() {
+        return;
+    }
 
-        for (entity, life) in life_query.iter_mut() {
-            if life.counter > player.lives {
-                commands.entity(entity).despawn();
-                commands
-                    .spawn(SpriteBundle {
-                        transform: Transform {
-                            translation: Vec3::new(
-                                (window.width() / 2.0) - (life.counter as f32 * LIFE_PADDING),
-                                (window.height() / 2.0) - LIFE_PADDING,
-                                1.0,
-                            ),
-                            scale: Vec3::new(0.6, 0.6, 1.0),
-                            ..Default::default()
-                        },
-                        texture: lost_life_image.clone(),
-                        ..Default::default()
-                    })
-                    .insert(GameObject);
-            }
+    if playing.is_empty() || *mode == GameMode::Versus {
+        game_over_writer.send(GameOverEvent);
+    } else {
+        for (entity, _) in out {
+            commands.entity(entity).despawn();
         }
     }
 }
 
 pub fn player_invincibility_listener(
-    mut player_query: Query<(&mut Sprite, &mut Player)>,
+    mut player_query: Query<&mut Player>,
     mut player_hit_reader: EventReader<PlayerHitEvent>,
 ) {
-    if player_hit_reader.iter().next().is_some() {
-        for (mut sprite, mut player) in player_query.iter_mut() {
-            sprite.color.set_a(0.3);
+    for hit in player_hit_reader.iter() {
+        if let Ok(mut player) = player_query.get_mut(hit.player) {
             player.invincible = true;
         }
     }
@@ -104,32 +90,37 @@ pub fn player_invincibility_listener(
 
 pub fn player_respawn_timer(
     time: Res<FixedTime>,
-    mut player_query: Query<(&mut Sprite, &mut Player)>,
+    config: Res<GameConfig>,
+    mut player_query: Query<&mut Player>,
 ) {
-    for (mut sprite, mut player) in player_query.iter_mut() {
+    for mut player in player_query.iter_mut() {
+        if !player.invincible {
+            continue;
+        }
         player.respawn_timer.tick(time.period);
         if player.respawn_timer.just_finished() {
-            sprite.color.set_a(1.0);
             player.invincible = false;
-            player.respawn_timer = Timer::from_seconds(RESPAWN_DURATION, TimerMode::Once);
+            player.respawn_timer =
+                Timer::from_seconds(config.player.respawn_duration, TimerMode::Once);
         }
     }
 }
 
 pub fn player_is_respawning(player_query: Query<&Player>) -> bool {
-    if player_query.is_empty() {
-        return false;
-    }
-
-    let player = player_query.get_single().unwrap();
-    player.invincible
+    player_query.iter().any(|player| player.invincible)
 }
 
 pub fn game_over_listener(
+    mode: Res<GameMode>,
     mut next_state: ResMut<NextState<AppState>>,
+    mut game_state: ResMut<GameState>,
     mut game_over_reader: EventReader<GameOverEvent>,
 ) {
     if game_over_reader.iter().next().is_some() {
-        next_state.set(AppState::GameOver);
+        game_state.finished = true;
+        next_state.set(match *mode {
+            GameMode::Versus => AppState::RoundResults,
+            _ => AppState::GameOver,
+        });
     }
 }
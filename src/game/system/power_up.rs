@@ -0,0 +1,52 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::config::game_config::GameConfig;
+use crate::game::collider::Collider;
+use crate::game::components::*;
+use crate::game::event::AsteroidDestroyedEvent;
+use crate::global::component::GameObject;
+
+pub fn spawn_power_ups(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    mut game_rng: ResMut<GameRng>,
+    mut destroyed_reader: EventReader<AsteroidDestroyedEvent>,
+) {
+    let random = &mut game_rng.rng;
+    let power_up_config = &config.power_up;
+
+    for destroyed in destroyed_reader.iter() {
+        if !random.gen_bool(power_up_config.drop_chance) {
+            continue;
+        }
+
+        let kind = PowerUpKind::ALL[random.gen_range(0..PowerUpKind::ALL.len())];
+        let heading = Vec2::from_angle(random.gen_range(0.0..TAU));
+
+        commands
+            .spawn(SpatialBundle::from_transform(
+                // A square stood on its corner
+                Transform::from_translation(destroyed.translation)
+                    .with_rotation(Quat::from_rotation_z(TAU / 8.0)),
+            ))
+            .insert(PowerUp::new(
+                kind,
+                heading * power_up_config.speed,
+                power_up_config.lifetime,
+            ))
+            .insert(Collider::rect(Vec2::splat(power_up_config.size)))
+            .insert(GameObject);
+    }
+}
+
+pub fn update_active_power_ups(
+    time: Res<FixedTime>,
+    mut power_ups_query: Query<&mut ActivePowerUps>,
+) {
+    for mut power_ups in power_ups_query.iter_mut() {
+        power_ups.tick(time.period);
+    }
+}
@@ -0,0 +1,108 @@
+use std::f32::consts::{PI, TAU};
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::config::game_config::GameConfig;
+use crate::game::collider::Collider;
+use crate::game::components::*;
+use crate::game::constants::*;
+use crate::global::component::GameObject;
+
+pub fn spawn_saucer(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    playfield: Res<Playfield>,
+    game_state: Res<GameState>,
+    mut game_rng: ResMut<GameRng>,
+    saucer_query: Query<(), With<Saucer>>,
+) {
+    // Only one saucer at a time
+    if !saucer_query.is_empty() {
+        return;
+    }
+
+    let random = &mut game_rng.rng;
+    let spec = config.waves.spec(game_state.wave);
+    if !random.gen_bool(spec.saucer_chance) {
+        return;
+    }
+
+    let size = if random.gen_bool(spec.small_saucer_chance) {
+        SaucerSize::Small
+    } else {
+        SaucerSize::Large
+    };
+
+    let half_size = playfield.half_size();
+    let direction = if random.gen_bool(0.5) { 1.0 } else { -1.0 };
+    let course = random.gen_range(-half_size.y * 0.7..half_size.y * 0.7);
+    let translation = Vec3::new(-direction * (half_size.x + WRAP_MARGIN), course, 0.0);
+
+    commands
+        .spawn(SpatialBundle::from_transform(Transform {
+            translation,
+            // The ship sprite faces up, so flip it to tell saucers apart from the player
+            rotation: Quat::from_rotation_z(PI),
+            scale: Vec3::new(size.scale(), size.scale(), 1.0),
+        }))
+        .insert(Saucer::new(size, direction, course))
+        .insert(Collider::rect(size.extent()))
+        .insert(GameObject);
+}
+
+pub fn saucer_shooting(
+    mut commands: Commands,
+    time: Res<FixedTime>,
+    config: Res<GameConfig>,
+    mut game_rng: ResMut<GameRng>,
+    mut saucer_query: Query<(&Transform, &mut Saucer)>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let random = &mut game_rng.rng;
+
+    for (transform, mut saucer) in saucer_query.iter_mut() {
+        saucer.fire_timer.tick(time.period);
+        if !saucer.fire_timer.just_finished() {
+            continue;
+        }
+
+        // Small saucers go for whichever ship is closest
+        let target = player_query.iter().min_by(|a, b| {
+            let distance = |player: &Transform| player.translation.distance(transform.translation);
+            distance(a).total_cmp(&distance(b))
+        });
+        let aim = match (saucer.size, target) {
+            (SaucerSize::Small, Some(player_transform)) => {
+                let to_player = (player_transform.translation - transform.translation).truncate();
+                let max_jitter = config.saucer.aim_jitter;
+                let jitter = random.gen_range(-max_jitter..max_jitter);
+                Vec2::from_angle(jitter).rotate(to_player.normalize_or_zero())
+            }
+            _ => Vec2::from_angle(random.gen_range(0.0..TAU)),
+        };
+
+        commands
+            .spawn(SpatialBundle::from_transform(Transform {
+                translation: transform.translation,
+                // The shot sprite is a vertical beam
+                rotation: Quat::from_rotation_arc_2d(Vec2::Y, aim),
+                scale: Vec3::new(0.3, 0.3, 0.0),
+            }))
+            .insert(SaucerShot {
+                velocity: aim * config.saucer.shot_speed,
+            })
+            .insert(Collider::circle(SAUCER_SHOT_RADIUS))
+            .insert(GameObject);
+    }
+}
+
+pub fn update_saucer_spawn_timer(time: Res<FixedTime>, mut game_state: ResMut<GameState>) {
+    if game_state.wave_in_progress() {
+        game_state.saucer_spawn_timer.tick(time.period);
+    }
+}
+
+pub fn saucer_spawn_timer(game_state: Res<GameState>) -> bool {
+    game_state.wave_in_progress() && game_state.saucer_spawn_timer.just_finished()
+}
@@ -1,96 +1,48 @@
-use std::time::Duration;
-
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
 
+use crate::action::state::ActionState;
+use crate::config::game_config::GameConfig;
 use crate::game::components::*;
 use crate::game::constants::*;
 use crate::global::component::GameObject;
 use crate::global::component::Scoreboard;
-use crate::global::constants::SCOREBOARD_FONT_SIZE;
-
-pub fn spawn_player(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands
-        .spawn(SpriteBundle {
-            transform: Transform {
-                ..Default::default()
-            },
-            texture: asset_server.load("sprites/ship_sidesA.png"),
-            ..Default::default()
-        })
-        .insert(Player::default())
-        .insert(GameObject);
-}
 
-pub fn setup_scoreboard(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let font: Handle<Font> = asset_server.load("fonts/Excluded.ttf");
-    commands
-        .spawn(
-            TextBundle::from_sections([
-                TextSection::new(
-                    "Score: ",
-                    TextStyle {
-                        font: font.clone(),
-                        font_size: SCOREBOARD_FONT_SIZE,
-                        color: Color::WHITE,
-                    },
-                ),
-                TextSection::from_style(TextStyle {
-                    font,
-                    font_size: SCOREBOARD_FONT_SIZE,
-                    color: Color::WHITE,
-                }),
-            ])
-            .with_style(Style {
-                position_type: PositionType::Absolute,
-                position: UiRect {
-                    top: SCOREBOARD_TEXT_PADDING,
-                    left: SCOREBOARD_TEXT_PADDING,
-                    ..default()
-                },
-                ..default()
-            }),
-        )
-        .insert(ScoreBoardText)
-        .insert(GameObject);
-    commands.insert_resource(Scoreboard { score: 0 })
+/// Where each player's ship starts: the middle for one player, side by side for more.
+pub fn start_position(player: PlayerId, players: usize) -> Vec3 {
+    let offset = player.0 as f32 - (players - 1) as f32 / 2.0;
+    Vec3::new(offset * PLAYER_SPACING, 0.0, 0.0)
 }
 
-pub fn setup_life_counter(
-    mut commands: Commands,
-    windows: Query<&Window, With<PrimaryWindow>>,
-    asset_server: Res<AssetServer>,
-) {
-    let life_sprite: Handle<Image> = asset_server.load("sprites/life.png");
-    let window = windows.get_single().unwrap();
-
-    for life in 1..PLAYER_LIVES + 1 {
-        let offset = LIFE_PADDING * life as f32;
+pub fn spawn_player(mut commands: Commands, config: Res<GameConfig>, mode: Res<GameMode>) {
+    let players = mode.players();
+    for id in (0..players).map(PlayerId) {
         commands
-            .spawn(SpriteBundle {
-                transform: Transform {
-                    translation: Vec3::new(
-                        (window.width() / 2.0) - offset,
-                        (window.height() / 2.0) - LIFE_PADDING,
-                        1.0,
-                    ),
-                    ..Default::default()
-                },
-                texture: life_sprite.clone(),
-                ..Default::default()
-            })
-            .insert(Life { counter: life })
+            .spawn(SpatialBundle::from_transform(Transform::from_translation(
+                start_position(id, players),
+            )))
+            .insert(Player::new(id, &config))
+            .insert(config.player.collider())
+            .insert(Velocity::default())
+            .insert(ActivePowerUps::default())
             .insert(GameObject);
     }
 }
 
-pub fn setup_game_state(mut commands: Commands) {
-    commands.insert_resource(GameState {
-        asteroid_rate_increase_timer: Timer::new(
-            Duration::from_secs_f32(15.0),
-            TimerMode::Repeating,
-        ),
-        asteroid_spawn_timer: Timer::new(Duration::from_secs_f32(1.0), TimerMode::Repeating),
-        paused: false,
-    });
+/// Gives every player in the new game their own set of actions.
+pub fn setup_players(mode: Res<GameMode>, mut actions: ResMut<ActionState>) {
+    actions.set_players(mode.players());
+}
+
+pub fn setup_game_state(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    mode: Res<GameMode>,
+    seed_config: Res<SeedConfig>,
+) {
+    let seed = seed_config.fixed_seed.unwrap_or_else(rand::random::<u64>);
+    info!("Starting game with seed {}", seed);
+
+    commands.insert_resource(GameState::new(&config));
+    commands.insert_resource(Scoreboard::new(mode.players()));
+    commands.insert_resource(GameRng::new(seed));
 }
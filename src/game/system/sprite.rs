@@ -0,0 +1,172 @@
+//! Sprites for the simulation's entities. Each entity gets its sprite once it has spawned, so
+//! the simulation itself never loads a texture.
+
+use bevy::prelude::*;
+
+use crate::config::game_config::GameConfig;
+use crate::game::components::*;
+use crate::global::palette::Palette;
+
+pub fn ship_sprite(
+    player: PlayerId,
+    palette: Palette,
+    asset_server: &AssetServer,
+) -> (Sprite, Handle<Image>) {
+    (
+        Sprite {
+            color: player.tint(palette),
+            ..default()
+        },
+        asset_server.load("sprites/ship_sidesA.png"),
+    )
+}
+
+pub fn asteroid_sprite(
+    MeteorSprite(sprite): MeteorSprite,
+    asset_server: &AssetServer,
+) -> (Sprite, Handle<Image>) {
+    (
+        Sprite::default(),
+        asset_server.load(format!("sprites/meteor/{}.png", sprite)),
+    )
+}
+
+pub fn laser_sprite(asset_server: &AssetServer) -> (Sprite, Handle<Image>) {
+    (
+        Sprite::default(),
+        asset_server.load("sprites/effect_yellow.png"),
+    )
+}
+
+pub fn saucer_sprite(size: SaucerSize, asset_server: &AssetServer) -> (Sprite, Handle<Image>) {
+    (
+        Sprite {
+            color: size.color(),
+            ..default()
+        },
+        asset_server.load("sprites/ship_sidesA.png"),
+    )
+}
+
+pub fn saucer_shot_sprite(asset_server: &AssetServer) -> (Sprite, Handle<Image>) {
+    (
+        Sprite::default(),
+        asset_server.load("sprites/effect_purple.png"),
+    )
+}
+
+/// Power-ups are plain coloured squares, a little smaller than what picks them up.
+pub fn power_up_sprite(
+    kind: PowerUpKind,
+    palette: Palette,
+    config: &GameConfig,
+) -> (Sprite, Handle<Image>) {
+    (
+        Sprite {
+            color: kind.color(palette),
+            custom_size: Some(Vec2::splat(config.power_up.size * 0.7)),
+            ..default()
+        },
+        Handle::default(),
+    )
+}
+
+pub fn add_ship_sprites(
+    mut commands: Commands,
+    palette: Res<Palette>,
+    asset_server: Res<AssetServer>,
+    player_query: Query<(Entity, &Player), Added<Player>>,
+) {
+    for (entity, player) in player_query.iter() {
+        commands
+            .entity(entity)
+            .insert(ship_sprite(player.id, *palette, &asset_server));
+    }
+}
+
+pub fn add_asteroid_sprites(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    asteroid_query: Query<(Entity, &MeteorSprite), Added<MeteorSprite>>,
+) {
+    for (entity, sprite) in asteroid_query.iter() {
+        commands
+            .entity(entity)
+            .insert(asteroid_sprite(*sprite, &asset_server));
+    }
+}
+
+pub fn add_laser_sprites(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    laser_query: Query<Entity, Added<Laser>>,
+) {
+    for entity in laser_query.iter() {
+        commands.entity(entity).insert(laser_sprite(&asset_server));
+    }
+}
+
+pub fn add_saucer_sprites(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    saucer_query: Query<(Entity, &Saucer), Added<Saucer>>,
+) {
+    for (entity, saucer) in saucer_query.iter() {
+        commands
+            .entity(entity)
+            .insert(saucer_sprite(saucer.size, &asset_server));
+    }
+}
+
+pub fn add_saucer_shot_sprites(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    shot_query: Query<Entity, Added<SaucerShot>>,
+) {
+    for entity in shot_query.iter() {
+        commands
+            .entity(entity)
+            .insert(saucer_shot_sprite(&asset_server));
+    }
+}
+
+pub fn add_power_up_sprites(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    palette: Res<Palette>,
+    power_up_query: Query<(Entity, &PowerUp), Added<PowerUp>>,
+) {
+    for (entity, power_up) in power_up_query.iter() {
+        commands
+            .entity(entity)
+            .insert(power_up_sprite(power_up.kind, *palette, &config));
+    }
+}
+
+/// Tints each ship while it's shielded, and fades it while it's invincible after a hit.
+pub fn tint_ships(
+    palette: Res<Palette>,
+    mut player_query: Query<(&Player, &ActivePowerUps, &mut Sprite)>,
+) {
+    for (player, power_ups, mut sprite) in player_query.iter_mut() {
+        let tint = if power_ups.is_active(PowerUpKind::Shield) {
+            PowerUpKind::Shield.color(*palette)
+        } else {
+            player.id.tint(*palette)
+        };
+        sprite.color = tint.with_a(if player.invincible { 0.3 } else { 1.0 });
+    }
+}
+
+/// Blinks each power-up for the last couple of seconds, so the player knows it's about to go.
+pub fn blink_power_ups(
+    config: Res<GameConfig>,
+    mut power_up_query: Query<(&PowerUp, &mut Sprite)>,
+) {
+    for (power_up, mut sprite) in power_up_query.iter_mut() {
+        let remaining = power_up.lifetime.duration() - power_up.lifetime.elapsed();
+        let visible = remaining.as_secs_f32() > config.power_up.blink_time
+            || ((remaining.as_secs_f32() * 8.0) as u32).is_multiple_of(2);
+        sprite.color.set_a(if visible { 1.0 } else { 0.2 });
+    }
+}
@@ -1,13 +1,26 @@
 use bevy::prelude::*;
 
+use crate::game::components::Playfield;
+
 pub fn calculate_angle(pos1: Vec2, pos2: Vec2) -> f32 {
     let diff = pos2 - pos1;
     diff.y.atan2(diff.x)
 }
 
-pub fn normalize_coords_in_window(window: &Window, coords: Vec3) -> Vec2 {
-    Vec2::new(
-        (window.width() / 2.) - coords.x,
-        (window.height() / 2.0) - coords.y,
-    )
+/// Moves a translation that has left the playfield back in from the opposite edge.
+pub fn wrap_in_playfield(playfield: &Playfield, translation: &mut Vec3, margin: f32) {
+    let half_width = playfield.width / 2.0 + margin;
+    let half_height = playfield.height / 2.0 + margin;
+
+    if translation.x > half_width {
+        translation.x -= half_width * 2.0;
+    } else if translation.x < -half_width {
+        translation.x += half_width * 2.0;
+    }
+
+    if translation.y > half_height {
+        translation.y -= half_height * 2.0;
+    } else if translation.y < -half_height {
+        translation.y += half_height * 2.0;
+    }
 }
@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+
+use crate::config::game_config::GameConfig;
+use crate::game::components::*;
+
+/// Starts each wave once its break is over, and the break for the next one once the field is
+/// clear.
+pub fn update_waves(
+    time: Res<FixedTime>,
+    config: Res<GameConfig>,
+    mut game_state: ResMut<GameState>,
+    asteroid_query: Query<(), With<Asteroid>>,
+    saucer_query: Query<(), With<Saucer>>,
+) {
+    let game_state = &mut *game_state;
+
+    match &mut game_state.wave_break {
+        Some(wave_break) => {
+            wave_break.tick(time.period);
+            if wave_break.finished() {
+                game_state.wave_break = None;
+                game_state.asteroids_left = config.waves.spec(game_state.wave).asteroids;
+                game_state.asteroid_spawn_timer.reset();
+                game_state.saucer_spawn_timer.reset();
+            }
+        }
+        None => {
+            let field_clear = asteroid_query.is_empty() && saucer_query.is_empty();
+            if game_state.asteroids_left == 0 && field_clear {
+                game_state.wave += 1;
+                game_state.wave_break = Some(Timer::from_seconds(
+                    config.waves.break_duration,
+                    TimerMode::Once,
+                ));
+            }
+        }
+    }
+}
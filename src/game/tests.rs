@@ -0,0 +1,641 @@
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+use crate::action::state::Action;
+use crate::config::game_config::GameConfig;
+use crate::global::component::Scoreboard;
+use crate::global::state::AppState;
+use crate::testing::Harness;
+
+use super::collider::Collider;
+use super::components::{
+    ActivePowerUps, Asteroid, AsteroidSize, GameMode, GameState, Laser, Life, Player, PlayerId,
+    Playfield, PowerUpKind, PowerUpText, Saucer, SaucerShot, SaucerSize, ScoreBoardText, WaveText,
+};
+use super::constants::LASER_SIZE;
+use super::event::PlayerHitEvent;
+
+const SEED: u64 = 7;
+
+fn ticks_in(harness: &Harness, seconds: f32) -> u32 {
+    (seconds / harness.period().as_secs_f32()).ceil() as u32
+}
+
+fn game_state(harness: &mut Harness) -> Mut<'_, GameState> {
+    harness.app.world.resource_mut::<GameState>()
+}
+
+#[test]
+fn shooting_an_asteroid_scores_it() {
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    // The ship starts at the origin facing up
+    let asteroid = harness.spawn_asteroid(AsteroidSize::Small, Vec2::new(0.0, 150.0));
+    harness.actions().press(Action::Fire);
+    harness.step();
+    harness.actions().release(Action::Fire);
+    harness.run_ticks(30);
+
+    assert!(!harness.exists(asteroid));
+    assert_eq!(harness.score(), AsteroidSize::Small.score());
+}
+
+#[test]
+fn getting_hit_costs_a_life_and_grants_invincibility() {
+    let config = GameConfig::default();
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    harness.spawn_asteroid(AsteroidSize::Large, Vec2::ZERO);
+    harness.step();
+    assert_eq!(harness.player().lives, config.player.lives - 1);
+    assert!(harness.player().invincible);
+
+    // A second hit while invincible destroys the asteroid but costs nothing
+    let asteroid = harness.spawn_asteroid(AsteroidSize::Large, Vec2::ZERO);
+    harness.step();
+    assert!(!harness.exists(asteroid));
+    assert_eq!(harness.player().lives, config.player.lives - 1);
+
+    let respawn_ticks = ticks_in(&harness, config.player.respawn_duration);
+    harness.run_ticks(respawn_ticks);
+    assert!(!harness.player().invincible);
+}
+
+#[test]
+fn asteroids_beside_the_nose_of_the_ship_miss_it() {
+    let config = GameConfig::default();
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    // Inside the ship's bounding box, but clear of its sloping sides
+    harness.spawn_asteroid(AsteroidSize::Small, Vec2::new(14.0, 20.0));
+    harness.step();
+    assert_eq!(harness.player().lives, config.player.lives);
+
+    harness.spawn_asteroid(AsteroidSize::Small, Vec2::new(0.0, 28.0));
+    harness.step();
+    assert_eq!(harness.player().lives, config.player.lives - 1);
+}
+
+#[test]
+fn losing_the_last_life_ends_the_game() {
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    while harness.player().lives > 1 {
+        assert!(harness.frames() < 2000, "the player never lost a life");
+        if !harness.player().invincible {
+            harness.spawn_asteroid(AsteroidSize::Large, Vec2::ZERO);
+        }
+        harness.step();
+    }
+    while harness.player().invincible {
+        harness.step();
+    }
+
+    harness.spawn_asteroid(AsteroidSize::Large, Vec2::ZERO);
+    harness.step();
+    assert_eq!(harness.player().lives, 0);
+    assert!(harness.app.world.resource::<GameState>().finished);
+
+    harness.step();
+    assert_eq!(harness.state(), AppState::GameOver);
+}
+
+#[test]
+fn each_wave_sends_in_its_asteroids_after_a_break() {
+    let config = GameConfig::default();
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+    // Stray asteroids must not end the game before the wave has played out
+    shield_player(&mut harness, 60.0);
+
+    let break_ticks = ticks_in(&harness, config.waves.break_duration);
+    harness.run_ticks(break_ticks - 2);
+    assert!(!game_state(&mut harness).wave_in_progress());
+    assert_eq!(harness.count::<Asteroid>(), 0);
+
+    harness.run_ticks(2);
+    let asteroids = config.waves.spec(1).asteroids;
+    assert!(game_state(&mut harness).wave_in_progress());
+    assert_eq!(game_state(&mut harness).asteroids_left, asteroids);
+
+    let spawn_ticks = ticks_in(&harness, config.asteroid.spawn_interval * asteroids as f32);
+    harness.run_ticks(spawn_ticks + 1);
+    assert_eq!(game_state(&mut harness).asteroids_left, 0);
+    assert_eq!(game_state(&mut harness).wave, 1);
+}
+
+#[test]
+fn asteroids_come_in_from_just_past_the_edge() {
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+    shield_player(&mut harness, 60.0);
+
+    let playfield = *harness.app.world.resource::<Playfield>();
+    let mut seen = HashSet::new();
+    while seen.len() < 5 {
+        assert!(harness.frames() < 2000, "not enough asteroids turned up");
+        harness.step();
+
+        let mut query = harness.app.world.query::<(Entity, &Transform, &Asteroid)>();
+        for (entity, transform, asteroid) in query.iter(&harness.app.world) {
+            if !seen.insert(entity) {
+                continue;
+            }
+            let position = transform.translation.truncate();
+            assert!(
+                position.abs().cmpgt(playfield.half_size()).any(),
+                "in sight"
+            );
+            assert!(playfield.despawn_bounds().contains(position));
+            assert!(asteroid.trajectory.dot(-position) > 0.0, "heading away");
+        }
+    }
+}
+
+#[test]
+fn clearing_the_field_starts_the_next_wave() {
+    let config = GameConfig::default();
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    let break_ticks = ticks_in(&harness, config.waves.break_duration);
+    harness.run_ticks(break_ticks);
+    assert!(game_state(&mut harness).wave_in_progress());
+
+    // The wave isn't over while anything it sent in is still around
+    game_state(&mut harness).asteroids_left = 0;
+    let asteroid = harness.spawn_asteroid(AsteroidSize::Small, Vec2::new(0.0, 300.0));
+    harness.run_ticks(10);
+    assert_eq!(game_state(&mut harness).wave, 1);
+
+    harness.app.world.despawn(asteroid);
+    harness.step();
+    assert_eq!(game_state(&mut harness).wave, 2);
+    assert!(!game_state(&mut harness).wave_in_progress());
+
+    harness.run_ticks(break_ticks);
+    assert_eq!(
+        game_state(&mut harness).asteroids_left,
+        config.waves.spec(2).asteroids
+    );
+}
+
+#[test]
+fn pausing_freezes_the_game_until_it_resumes() {
+    let config = GameConfig::default();
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+    harness.actions().press(Action::Thrust);
+    harness.run_ticks(10);
+
+    let asteroid = harness.spawn_asteroid(AsteroidSize::Small, Vec2::new(0.0, 300.0));
+    harness.step();
+    let score = harness.score();
+    let elapsed = |harness: &Harness| {
+        harness
+            .app
+            .world
+            .resource::<GameState>()
+            .wave_break
+            .as_ref()
+            .unwrap()
+            .elapsed()
+    };
+    let position = |harness: &mut Harness| {
+        let mut player_query = harness
+            .app
+            .world
+            .query_filtered::<&Transform, With<Player>>();
+        player_query.single(&harness.app.world).translation
+    };
+    let (paused_at, paused_position) = (elapsed(&harness), position(&mut harness));
+
+    harness.enter(AppState::Paused);
+    harness.run_ticks(60);
+    assert_eq!(elapsed(&harness), paused_at);
+    assert_eq!(position(&mut harness), paused_position);
+
+    // Resuming carries on with the same game rather than setting up a new one
+    harness.enter(AppState::InGame);
+    assert!(harness.exists(asteroid));
+    assert_eq!(harness.score(), score);
+    assert_eq!(harness.player().lives, config.player.lives);
+    assert_ne!(position(&mut harness), paused_position);
+}
+
+fn power_up_active(harness: &mut Harness, kind: PowerUpKind) -> bool {
+    let mut player_query = harness.app.world.query::<&ActivePowerUps>();
+    player_query.single(&harness.app.world).is_active(kind)
+}
+
+#[test]
+fn an_extra_life_adds_to_the_life_counter() {
+    let config = GameConfig::default();
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    let power_up = harness.spawn_power_up(PowerUpKind::ExtraLife, Vec2::ZERO);
+    harness.step();
+    assert!(!harness.exists(power_up));
+    assert_eq!(harness.player().lives, config.player.lives + 1);
+
+    harness.step();
+    let mut life_query = harness.app.world.query::<&Life>();
+    let lives_shown = life_query
+        .iter(&harness.app.world)
+        .filter(|life| !life.lost)
+        .count();
+    assert_eq!(lives_shown, config.player.lives as usize + 1);
+}
+
+#[test]
+fn a_shield_absorbs_hits_until_it_runs_out() {
+    let config = GameConfig::default();
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    harness.spawn_power_up(PowerUpKind::Shield, Vec2::ZERO);
+    harness.step();
+    harness.spawn_asteroid(AsteroidSize::Large, Vec2::ZERO);
+    harness.step();
+    assert_eq!(harness.player().lives, config.player.lives);
+    assert!(!harness.player().invincible);
+
+    let shield_ticks = ticks_in(&harness, config.power_up.shield_duration);
+    harness.run_ticks(shield_ticks);
+    assert!(!power_up_active(&mut harness, PowerUpKind::Shield));
+}
+
+#[test]
+fn spread_shot_fires_three_lasers() {
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    harness.spawn_power_up(PowerUpKind::SpreadShot, Vec2::ZERO);
+    harness.step();
+    assert!(power_up_active(&mut harness, PowerUpKind::SpreadShot));
+
+    harness.actions().press(Action::Fire);
+    harness.step();
+    assert_eq!(harness.count::<Laser>(), 3);
+}
+
+#[test]
+fn rapid_fire_keeps_shooting_while_fire_is_held() {
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    harness.actions().press(Action::Fire);
+    harness.run_ticks(30);
+    assert_eq!(harness.count::<Laser>(), 1);
+
+    harness.actions().release(Action::Fire);
+    harness.spawn_power_up(PowerUpKind::RapidFire, Vec2::ZERO);
+    harness.run_ticks(60);
+    harness.actions().press(Action::Fire);
+    harness.run_ticks(30);
+    assert!(harness.count::<Laser>() >= 5);
+}
+
+#[test]
+fn uncollected_power_ups_expire() {
+    let config = GameConfig::default();
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    let power_up = harness.spawn_power_up(PowerUpKind::Shield, Vec2::new(200.0, 200.0));
+    let lifetime_ticks = ticks_in(&harness, config.power_up.lifetime);
+    harness.run_ticks(lifetime_ticks - 1);
+    assert!(harness.exists(power_up));
+    harness.run_ticks(2);
+    assert!(!harness.exists(power_up));
+}
+
+fn shield_player(harness: &mut Harness, seconds: f32) {
+    let mut player_query = harness.app.world.query::<&mut ActivePowerUps>();
+    player_query
+        .single_mut(&mut harness.app.world)
+        .activate(PowerUpKind::Shield, seconds);
+}
+
+#[test]
+fn shooting_a_saucer_scores_it() {
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    let saucer = harness.spawn_saucer(SaucerSize::Small, Vec2::new(0.0, 80.0), 0.0);
+    harness.actions().press(Action::Fire);
+    harness.run_ticks(20);
+
+    assert!(!harness.exists(saucer));
+    assert_eq!(harness.score(), SaucerSize::Small.score());
+}
+
+#[test]
+fn saucer_shots_hit_the_player() {
+    let config = GameConfig::default();
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    harness.app.world.spawn((
+        SpatialBundle::from_transform(Transform::from_xyz(0.0, 60.0, 0.0)),
+        SaucerShot {
+            velocity: Vec2::new(0.0, -300.0),
+        },
+        Collider::circle(4.0),
+    ));
+    harness.run_ticks(10);
+
+    assert_eq!(harness.player().lives, config.player.lives - 1);
+    assert_eq!(harness.count::<SaucerShot>(), 0);
+}
+
+#[test]
+fn ramming_a_saucer_costs_a_life_and_the_saucer() {
+    let config = GameConfig::default();
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    let saucer = harness.spawn_saucer(SaucerSize::Large, Vec2::new(0.0, 20.0), 0.0);
+    harness.run_ticks(2);
+
+    assert_eq!(harness.player().lives, config.player.lives - 1);
+    assert!(!harness.exists(saucer));
+}
+
+#[test]
+fn small_saucers_aim_at_the_player() {
+    let config = GameConfig::default();
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    harness.spawn_saucer(SaucerSize::Small, Vec2::new(200.0, 0.0), 0.0);
+    while harness.count::<SaucerShot>() == 0 {
+        assert!(harness.frames() < 200, "the saucer never fired");
+        harness.step();
+    }
+
+    let mut shot_query = harness.app.world.query::<(&Transform, &SaucerShot)>();
+    let (transform, shot) = shot_query.single(&harness.app.world);
+    let to_player = -transform.translation.truncate();
+    assert!(shot.velocity.angle_between(to_player).abs() <= config.saucer.aim_jitter + 1e-3);
+}
+
+#[test]
+fn saucers_only_turn_up_in_later_waves() {
+    let config = GameConfig::default();
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+    shield_player(&mut harness, 60.0);
+
+    let wave_ticks = ticks_in(
+        &harness,
+        config.waves.break_duration + config.saucer.interval,
+    );
+    harness.run_ticks(wave_ticks);
+    assert_eq!(harness.count::<Saucer>(), 0);
+
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+    shield_player(&mut harness, 60.0);
+    game_state(&mut harness).wave = config.waves.table.len() as u32;
+
+    while harness.count::<Saucer>() == 0 {
+        assert!(harness.frames() < wave_ticks * 4, "no saucer turned up");
+        harness.step();
+    }
+}
+
+/// Places a motionless laser pointing up, without a sprite.
+fn spawn_laser(harness: &mut Harness, translation: Vec2) -> Entity {
+    harness
+        .app
+        .world
+        .spawn((
+            SpatialBundle::from_transform(Transform::from_translation(translation.extend(0.0))),
+            Laser {
+                velocity: Vec2::ZERO,
+                owner: PlayerId(0),
+            },
+            Collider::rect(LASER_SIZE),
+        ))
+        .id()
+}
+
+#[test]
+fn a_laser_across_two_asteroids_only_destroys_one() {
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    let left = harness.spawn_asteroid(AsteroidSize::Small, Vec2::new(-6.0, 200.0));
+    let right = harness.spawn_asteroid(AsteroidSize::Small, Vec2::new(6.0, 200.0));
+    let laser = spawn_laser(&mut harness, Vec2::new(0.0, 200.0));
+    harness.step();
+
+    assert!(!harness.exists(laser));
+    assert_eq!(harness.count::<Asteroid>(), 1);
+    assert!(harness.exists(left) != harness.exists(right));
+    assert_eq!(harness.score(), AsteroidSize::Small.score());
+}
+
+#[test]
+fn two_lasers_in_one_asteroid_only_score_it_once() {
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    let asteroid = harness.spawn_asteroid(AsteroidSize::Small, Vec2::new(0.0, 200.0));
+    spawn_laser(&mut harness, Vec2::new(-3.0, 200.0));
+    spawn_laser(&mut harness, Vec2::new(3.0, 200.0));
+    harness.step();
+
+    assert!(!harness.exists(asteroid));
+    assert_eq!(harness.count::<Laser>(), 1);
+    assert_eq!(harness.score(), AsteroidSize::Small.score());
+}
+
+#[test]
+fn a_laser_that_destroys_an_asteroid_misses_the_saucer_behind_it() {
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    harness.spawn_asteroid(AsteroidSize::Small, Vec2::new(0.0, 205.0));
+    let saucer = harness.spawn_saucer(SaucerSize::Large, Vec2::new(0.0, 180.0), 0.0);
+    spawn_laser(&mut harness, Vec2::new(0.0, 195.0));
+    harness.step();
+
+    assert!(harness.exists(saucer));
+    assert_eq!(harness.score(), AsteroidSize::Small.score());
+}
+
+#[test]
+fn being_hit_by_several_things_at_once_is_one_hit() {
+    let config = GameConfig::default();
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    harness.spawn_asteroid(AsteroidSize::Small, Vec2::new(0.0, 10.0));
+    harness.spawn_asteroid(AsteroidSize::Small, Vec2::new(0.0, -10.0));
+    harness.spawn_saucer(SaucerSize::Small, Vec2::ZERO, 0.0);
+    harness.step();
+
+    let hits = harness.app.world.resource::<Events<PlayerHitEvent>>();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(harness.player().lives, config.player.lives - 1);
+    assert_eq!(harness.count::<Asteroid>(), 0);
+    assert_eq!(harness.count::<Saucer>(), 0);
+}
+
+#[test]
+fn each_co_op_player_flies_and_scores_on_their_own() {
+    let mut harness = Harness::new(SEED);
+    harness.start_game_in(GameMode::Coop);
+    assert_eq!(harness.count::<Player>(), 2);
+
+    // The second player's ship starts on the right, facing up
+    let ship = harness
+        .app
+        .world
+        .query::<(&Transform, &Player)>()
+        .iter(&harness.app.world)
+        .find(|(_, player)| player.id == PlayerId(1))
+        .map(|(transform, _)| transform.translation.truncate())
+        .unwrap();
+    let asteroid = harness.spawn_asteroid(AsteroidSize::Small, ship + Vec2::new(0.0, 150.0));
+    harness.actions().player_mut(1).press(Action::Fire);
+    harness.step();
+    harness.actions().player_mut(1).release(Action::Fire);
+    harness.run_ticks(30);
+
+    assert!(!harness.exists(asteroid));
+    let scores = &harness.app.world.resource::<Scoreboard>().scores;
+    assert_eq!(scores, &vec![0, AsteroidSize::Small.score()]);
+}
+
+#[test]
+fn a_co_op_game_ends_once_both_players_are_out_of_lives() {
+    let mut harness = Harness::new(SEED);
+    harness.start_game_in(GameMode::Coop);
+
+    let hit_last_life = |harness: &mut Harness, id: PlayerId| {
+        let mut player_query = harness.app.world.query::<(&Transform, &mut Player)>();
+        let (transform, mut player) = player_query
+            .iter_mut(&mut harness.app.world)
+            .find(|(_, player)| player.id == id)
+            .unwrap();
+        player.lives = 1;
+        let translation = transform.translation.truncate();
+        harness.spawn_asteroid(AsteroidSize::Small, translation);
+        harness.step();
+    };
+
+    hit_last_life(&mut harness, PlayerId(0));
+    harness.step();
+    assert_eq!(harness.state(), AppState::InGame);
+    assert_eq!(harness.count::<Player>(), 1);
+    assert_eq!(
+        harness.player_with(PlayerId(1)).lives,
+        GameConfig::default().player.lives
+    );
+
+    hit_last_life(&mut harness, PlayerId(1));
+    harness.step();
+    assert_eq!(harness.state(), AppState::GameOver);
+}
+
+/// Turns player one's ship to face player two's, fires once and waits for the laser to arrive.
+fn fire_across(harness: &mut Harness) {
+    let mut player_query = harness.app.world.query::<(&mut Transform, &Player)>();
+    for (mut transform, player) in player_query.iter_mut(&mut harness.app.world) {
+        if player.id == PlayerId(0) {
+            transform.rotation = Quat::from_rotation_z(-std::f32::consts::FRAC_PI_2);
+        }
+    }
+    harness.actions().press(Action::Fire);
+    harness.step();
+    harness.actions().release(Action::Fire);
+    harness.run_ticks(30);
+}
+
+#[test]
+fn in_versus_a_laser_costs_the_other_player_a_life() {
+    let lives = GameConfig::default().player.lives;
+    let mut harness = Harness::new(SEED);
+    harness.start_game_in(GameMode::Versus);
+
+    fire_across(&mut harness);
+
+    assert_eq!(harness.player_with(PlayerId(1)).lives, lives - 1);
+    assert_eq!(harness.player_with(PlayerId(0)).lives, lives);
+    assert_eq!(harness.count::<Laser>(), 0);
+}
+
+#[test]
+fn co_op_lasers_pass_through_the_other_ship() {
+    let lives = GameConfig::default().player.lives;
+    let mut harness = Harness::new(SEED);
+    harness.start_game_in(GameMode::Coop);
+
+    fire_across(&mut harness);
+
+    assert_eq!(harness.player_with(PlayerId(1)).lives, lives);
+}
+
+#[test]
+fn the_simulation_runs_without_assets_sprites_or_hud() {
+    let mut harness = Harness::build_headless(SEED, |_| {});
+    harness.start_game();
+
+    harness.actions().press(Action::Fire);
+    harness.step();
+    harness.actions().release(Action::Fire);
+    assert_eq!(harness.count::<Laser>(), 1);
+
+    harness.run_ticks(ticks_in(&harness, 10.0));
+    assert!(harness.count::<Asteroid>() > 0);
+    assert_eq!(harness.count::<Sprite>(), 0);
+    assert_eq!(harness.count::<Text>(), 0);
+}
+
+#[test]
+fn entities_are_given_sprites_once_they_spawn() {
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    harness.actions().press(Action::Fire);
+    harness.step();
+    harness.actions().release(Action::Fire);
+
+    let mut laser_query = harness
+        .app
+        .world
+        .query_filtered::<(), (With<Laser>, With<Sprite>)>();
+    assert_eq!(laser_query.iter(&harness.app.world).count(), 1);
+    let mut player_query = harness.app.world.query::<(&Player, &Sprite)>();
+    let (_, sprite) = player_query.single(&harness.app.world);
+    assert_eq!(sprite.color, Color::WHITE);
+}
+
+#[test]
+fn the_hud_can_be_left_out_of_a_game_with_visuals() {
+    let mut harness = Harness::new(SEED);
+    harness.start_game();
+
+    // As an app drawing its own HUD would
+    let mut hud_query = harness.app.world.query_filtered::<Entity, Or<(
+        With<ScoreBoardText>,
+        With<PowerUpText>,
+        With<WaveText>,
+    )>>();
+    let hud: Vec<Entity> = hud_query.iter(&harness.app.world).collect();
+    assert!(!hud.is_empty());
+    for entity in hud {
+        harness.app.world.despawn(entity);
+    }
+
+    harness.run_ticks(ticks_in(&harness, 1.0));
+    assert_eq!(harness.state(), AppState::InGame);
+}
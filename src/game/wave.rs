@@ -0,0 +1,31 @@
+use std::ops::Range;
+
+use serde::Deserialize;
+
+/// How one wave of the game plays out.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WaveSpec {
+    /// Asteroids sent in over the course of the wave. The wave ends once they are all gone.
+    pub asteroids: u32,
+    /// Asteroid speed in pixels per second.
+    pub speed: Range<f32>,
+    /// Chance that an asteroid is a large one rather than a medium one.
+    pub large_chance: f64,
+    /// Chance that a saucer turns up each time the saucer timer comes round.
+    pub saucer_chance: f64,
+    /// Chance that a saucer is a small one, which aims at the player.
+    pub small_saucer_chance: f64,
+}
+
+/// The waves played when the config doesn't list its own.
+#[rustfmt::skip]
+pub const WAVES: [WaveSpec; 7] = [
+    WaveSpec { asteroids: 4,  speed: 60.0..160.0,  large_chance: 0.8, saucer_chance: 0.0, small_saucer_chance: 0.0 },
+    WaveSpec { asteroids: 6,  speed: 70.0..200.0,  large_chance: 0.7, saucer_chance: 0.3, small_saucer_chance: 0.0 },
+    WaveSpec { asteroids: 8,  speed: 80.0..240.0,  large_chance: 0.6, saucer_chance: 0.5, small_saucer_chance: 0.2 },
+    WaveSpec { asteroids: 10, speed: 90.0..300.0,  large_chance: 0.5, saucer_chance: 0.6, small_saucer_chance: 0.35 },
+    WaveSpec { asteroids: 12, speed: 100.0..360.0, large_chance: 0.5, saucer_chance: 0.7, small_saucer_chance: 0.5 },
+    WaveSpec { asteroids: 14, speed: 120.0..420.0, large_chance: 0.4, saucer_chance: 0.8, small_saucer_chance: 0.65 },
+    WaveSpec { asteroids: 16, speed: 140.0..480.0, large_chance: 0.4, saucer_chance: 0.9, small_saucer_chance: 0.8 },
+];
@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+
+/// Present while the player is typing their name for a new high score.
+#[derive(Resource)]
+pub struct NameEntry {
+    pub name: String,
+    pub score: usize,
+}
+
+/// The column of text in the middle of the game over screen.
+#[derive(Component)]
+pub struct GameOverScreen;
+
+#[derive(Component)]
+pub struct NameEntryPrompt;
+
+#[derive(Component)]
+pub struct NameEntryText;
+
+/// Counts down until the game over screen takes a confirm, so a player still firing as the
+/// game ends doesn't skip straight past it.
+#[derive(Resource)]
+pub struct GameOverDelay(pub Timer);
@@ -1,4 +1,5 @@
-use crate::{clear_game_objects, global::state::AppState};
+use crate::global::state::AppState;
+use crate::global::system::clear_game_objects;
 use bevy::prelude::*;
 
 use super::system::*;
@@ -11,7 +12,16 @@ impl Plugin for GameOverPlugin {
             // Setup game over
             .add_system(show_game_over_screen.in_schedule(OnEnter(AppState::GameOver)))
             // Game Over Listener
-            .add_system(play_again_listener.in_set(OnUpdate(AppState::GameOver)))
+            .add_systems(
+                (
+                    tick_game_over_delay,
+                    name_entry_input,
+                    submit_name_entry,
+                    play_again_listener,
+                )
+                    .chain()
+                    .in_set(OnUpdate(AppState::GameOver)),
+            )
             // Clean up game over
             .add_system(clear_game_objects.in_schedule(OnExit(AppState::GameOver)));
     }
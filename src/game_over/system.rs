@@ -1,97 +1,258 @@
 use bevy::prelude::*;
 
+use crate::action::state::{Action, ActionState};
+use crate::game::components::{GameMode, GameRng, GameState, PlayerId};
 use crate::global::{
     component::{GameObject, Scoreboard},
     constants::SCOREBOARD_FONT_SIZE,
+    palette::Palette,
     state::AppState,
+    ui::spawn_line,
 };
+use crate::high_score::{plugin::HighScores, table::MAX_NAME_LENGTH};
+use crate::replay::plugin::Playback;
 
+use super::components::*;
+
+/// Seconds the game over screen stays up before it takes a confirm.
+const GAME_OVER_DELAY: f32 = 0.75;
+
+#[allow(clippy::too_many_arguments)]
 pub fn show_game_over_screen(
     mut commands: Commands,
     scoreboard: Res<Scoreboard>,
+    game_rng: Res<GameRng>,
+    game_state: Res<GameState>,
+    mode: Res<GameMode>,
+    palette: Res<Palette>,
+    high_scores: Res<HighScores>,
+    playback: Option<Res<Playback>>,
     asset_server: Res<AssetServer>,
 ) {
-    let score = scoreboard.score;
+    let score = scoreboard.total();
+    let scores = scoreboard.scores.clone();
 
     commands.remove_resource::<Scoreboard>();
 
     let font: Handle<Font> = asset_server.load("fonts/Excluded.ttf");
     let italic_font: Handle<Font> = asset_server.load("fonts/ExcludedItalic.ttf");
+    // Replays don't earn high scores a second time, and the table is for solo games
+    let new_high_score =
+        playback.is_none() && *mode == GameMode::Solo && high_scores.table.qualifies(score);
+
     commands
-        .spawn(
-            TextBundle::from_section(
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            spawn_line(
+                parent,
                 "Game Over",
                 TextStyle {
                     font: italic_font,
                     font_size: 60.0,
                     color: Color::WHITE,
                 },
-            )
-            .with_style(Style {
-                display: Display::Flex,
-                position_type: PositionType::Absolute,
-                position: UiRect {
-                    top: Val::Px(300.0),
-                    left: Val::Px(225.0),
-                    ..default()
+            );
+            spawn_line(
+                parent,
+                format!("Reached wave {}", game_state.wave),
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 24.0,
+                    color: Color::WHITE,
+                },
+            );
+            if scores.len() > 1 {
+                for (player, score) in scores.iter().enumerate() {
+                    let player = PlayerId(player);
+                    spawn_line(
+                        parent,
+                        format!("{}: {}", player.label(), score),
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: SCOREBOARD_FONT_SIZE,
+                            color: player.tint(*palette),
+                        },
+                    );
+                }
+            }
+            spawn_line(
+                parent,
+                match scores.len() {
+                    1 => format!("Final score: {}", score),
+                    _ => format!("Team score: {}", score),
                 },
-                ..default()
-            }),
-        )
-        .insert(GameObject);
-
-    commands
-        .spawn(
-            TextBundle::from_section(
-                format!("Final score: {}", score),
                 TextStyle {
                     font: font.clone(),
                     font_size: SCOREBOARD_FONT_SIZE,
                     color: Color::WHITE,
                 },
-            )
-            .with_style(Style {
-                display: Display::Flex,
-                position_type: PositionType::Absolute,
-                position: UiRect {
-                    top: Val::Px(400.0),
-                    left: Val::Px(225.0),
-                    ..default()
-                },
-                ..default()
-            }),
-        )
+            );
+
+            if new_high_score {
+                spawn_line(
+                    parent,
+                    "New high score! Type your name and press Enter:",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 24.0,
+                        color: Color::WHITE,
+                    },
+                )
+                .insert(NameEntryPrompt);
+                spawn_line(
+                    parent,
+                    "_",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: SCOREBOARD_FONT_SIZE,
+                        color: Color::YELLOW,
+                    },
+                )
+                .insert(NameEntryText)
+                .insert(NameEntryPrompt);
+            } else {
+                spawn_play_again_text(parent, font.clone());
+            }
+        })
+        .insert(GameOverScreen)
         .insert(GameObject);
 
     commands
         .spawn(
             TextBundle::from_section(
-                "Click anywhere to play again.",
+                format!("Seed: {}", game_rng.seed),
                 TextStyle {
                     font,
-                    font_size: 24.0,
-                    color: Color::WHITE,
+                    font_size: 20.0,
+                    color: Color::GRAY,
                 },
             )
             .with_style(Style {
                 display: Display::Flex,
                 position_type: PositionType::Absolute,
                 position: UiRect {
-                    top: Val::Px(450.0),
-                    left: Val::Px(175.0),
+                    bottom: Val::Px(10.0),
+                    left: Val::Px(10.0),
                     ..default()
                 },
                 ..default()
             }),
         )
         .insert(GameObject);
+    commands.insert_resource(GameOverDelay(Timer::from_seconds(
+        GAME_OVER_DELAY,
+        TimerMode::Once,
+    )));
+
+    if new_high_score {
+        commands.insert_resource(NameEntry {
+            name: String::new(),
+            score,
+        });
+    }
+}
+
+fn spawn_play_again_text(parent: &mut ChildBuilder, font: Handle<Font>) {
+    spawn_line(
+        parent,
+        "Click anywhere to play again.",
+        TextStyle {
+            font,
+            font_size: 24.0,
+            color: Color::WHITE,
+        },
+    );
+}
+
+pub fn name_entry_input(
+    name_entry: Option<ResMut<NameEntry>>,
+    mut character_reader: EventReader<ReceivedCharacter>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut name_text_query: Query<&mut Text, With<NameEntryText>>,
+) {
+    let Some(mut name_entry) = name_entry else {
+        character_reader.clear();
+        return;
+    };
+
+    for character in character_reader.iter() {
+        if character.char.is_control() {
+            continue;
+        }
+        if name_entry.name.chars().count() < MAX_NAME_LENGTH {
+            name_entry.name.push(character.char);
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::Back) {
+        name_entry.name.pop();
+    }
+
+    if name_entry.is_changed() {
+        for mut text in name_text_query.iter_mut() {
+            text.sections[0].value = format!("{}_", name_entry.name);
+        }
+    }
+}
+
+pub fn tick_game_over_delay(time: Res<Time>, mut delay: ResMut<GameOverDelay>) {
+    delay.0.tick(time.delta());
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn submit_name_entry(
+    mut commands: Commands,
+    delay: Res<GameOverDelay>,
+    name_entry: Option<Res<NameEntry>>,
+    mut high_scores: ResMut<HighScores>,
+    actions: Res<ActionState>,
+    prompt_query: Query<Entity, With<NameEntryPrompt>>,
+    screen_query: Query<Entity, With<GameOverScreen>>,
+    asset_server: Res<AssetServer>,
+) {
+    let Some(name_entry) = name_entry else {
+        return;
+    };
+
+    // Fire and confirm share a gamepad button
+    if delay.0.finished() && actions.just_pressed(Action::Confirm) {
+        high_scores.table.insert(&name_entry.name, name_entry.score);
+        high_scores.save();
+
+        commands.remove_resource::<NameEntry>();
+        for entity in prompt_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        let font = asset_server.load("fonts/Excluded.ttf");
+        for screen in screen_query.iter() {
+            commands
+                .entity(screen)
+                .with_children(|parent| spawn_play_again_text(parent, font.clone()));
+        }
+    }
 }
 
 pub fn play_again_listener(
+    delay: Res<GameOverDelay>,
     buttons: Res<Input<MouseButton>>,
+    actions: Res<ActionState>,
+    name_entry: Option<Res<NameEntry>>,
     mut next_state: ResMut<NextState<AppState>>,
 ) {
-    if buttons.just_pressed(MouseButton::Left) {
+    // Fire and confirm share a gamepad button, and the mouse fires as well as clicks
+    if name_entry.is_some() || !delay.0.finished() {
+        return;
+    }
+
+    if buttons.just_pressed(MouseButton::Left) || actions.just_pressed(Action::Confirm) {
         next_state.set(AppState::InGame);
     }
 }
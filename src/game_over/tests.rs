@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+
+use crate::game::components::{AsteroidSize, Player};
+use crate::global::component::Scoreboard;
+use crate::global::state::AppState;
+use crate::high_score::plugin::HighScores;
+use crate::testing::Harness;
+
+use super::components::NameEntry;
+use super::plugin::GameOverPlugin;
+
+const SEED: u64 = 13;
+
+fn harness() -> Harness {
+    Harness::build(SEED, |app| {
+        app.init_resource::<Input<KeyCode>>()
+            .init_resource::<Gamepads>()
+            .init_resource::<Input<GamepadButton>>()
+            .init_resource::<Axis<GamepadAxis>>()
+            .add_event::<CursorMoved>()
+            .add_plugin(GameOverPlugin)
+            .insert_resource(HighScores {
+                table: default(),
+                // Nothing is saved, so the tests leave no files behind
+                path: None,
+            });
+    })
+}
+
+/// Presses and lets go of confirm, a frame apart.
+fn press_confirm(harness: &mut Harness) {
+    let mut keys = harness.app.world.resource_mut::<Input<KeyCode>>();
+    keys.press(KeyCode::Return);
+    harness.step();
+    let mut keys = harness.app.world.resource_mut::<Input<KeyCode>>();
+    keys.reset(KeyCode::Return);
+    harness.step();
+}
+
+/// Takes the player's last life, pressing confirm just as the game ends.
+fn lose_pressing_confirm(harness: &mut Harness) {
+    let mut player_query = harness.app.world.query::<(&Transform, &mut Player)>();
+    let (transform, mut player) = player_query.single_mut(&mut harness.app.world);
+    player.lives = 1;
+    let translation = transform.translation.truncate();
+    harness.spawn_asteroid(AsteroidSize::Small, translation);
+    harness.step();
+    press_confirm(harness);
+}
+
+#[test]
+fn the_game_over_screen_stays_up_through_a_confirm_pressed_as_the_game_ends() {
+    let mut harness = harness();
+    harness.start_game();
+
+    lose_pressing_confirm(&mut harness);
+    assert_eq!(harness.state(), AppState::GameOver);
+
+    harness.run_ticks(60);
+    press_confirm(&mut harness);
+    assert_eq!(harness.state(), AppState::InGame);
+}
+
+#[test]
+fn a_confirm_pressed_as_the_game_ends_doesnt_enter_a_blank_name() {
+    let mut harness = harness();
+    harness.start_game();
+    harness.app.world.resource_mut::<Scoreboard>().scores[0] = 100;
+
+    lose_pressing_confirm(&mut harness);
+    assert!(harness.app.world.contains_resource::<NameEntry>());
+
+    harness.run_ticks(60);
+    press_confirm(&mut harness);
+    assert!(!harness.app.world.contains_resource::<NameEntry>());
+    let high_scores = harness.app.world.resource::<HighScores>();
+    assert_eq!(high_scores.table.entries().len(), 1);
+    assert_eq!(harness.state(), AppState::GameOver);
+}
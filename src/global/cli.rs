@@ -0,0 +1,153 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::game::components::GameMode;
+use crate::game::plugin::GamePlugin;
+
+/// Environment variable that fixes the seed when `--seed` isn't given.
+pub const SEED_ENV: &str = "ASTEROIDS_SEED";
+/// Where the dedicated server listens unless told otherwise.
+pub const DEFAULT_PORT: u16 = 7450;
+
+/// Options read from the command line and environment at startup.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LaunchOptions {
+    /// Seed every game with this instead of a random seed.
+    pub seed: Option<u64>,
+    /// Play back this replay file instead of showing the main menu.
+    pub replay: Option<PathBuf>,
+    /// Join the online game run by the server at this address.
+    pub connect: Option<String>,
+}
+
+impl LaunchOptions {
+    pub fn from_env() -> LaunchOptions {
+        LaunchOptions::parse(std::env::args().skip(1), std::env::var(SEED_ENV).ok())
+    }
+
+    fn parse(args: impl IntoIterator<Item = String>, seed_env: Option<String>) -> LaunchOptions {
+        let mut options = LaunchOptions {
+            seed: seed_env.and_then(|seed| parse_seed(&seed, SEED_ENV)),
+            ..LaunchOptions::default()
+        };
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => match args.next() {
+                    Some(seed) => options.seed = parse_seed(&seed, "--seed").or(options.seed),
+                    None => eprintln!("--seed needs a value"),
+                },
+                "--replay" => match args.next() {
+                    Some(path) => options.replay = Some(PathBuf::from(path)),
+                    None => eprintln!("--replay needs a file"),
+                },
+                "--connect" => match args.next() {
+                    Some(address) => options.connect = Some(address),
+                    None => eprintln!("--connect needs a server address"),
+                },
+                _ => eprintln!("Ignoring unknown argument {}", arg),
+            }
+        }
+
+        options
+    }
+}
+
+/// Options for the dedicated server, read from the command line and environment at startup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerOptions {
+    /// UDP port to listen on.
+    pub port: u16,
+    /// Simulation ticks per second.
+    pub tick_rate: f32,
+    /// Seed every game with this instead of a random seed.
+    pub seed: Option<u64>,
+    /// Players in each game. The game starts once this many have joined.
+    pub max_players: usize,
+}
+
+impl Default for ServerOptions {
+    fn default() -> ServerOptions {
+        ServerOptions {
+            port: DEFAULT_PORT,
+            tick_rate: GamePlugin::default().tick_rate,
+            seed: None,
+            max_players: GameMode::Coop.players(),
+        }
+    }
+}
+
+impl ServerOptions {
+    pub fn from_env() -> ServerOptions {
+        ServerOptions::parse(std::env::args().skip(1), std::env::var(SEED_ENV).ok())
+    }
+
+    fn parse(args: impl IntoIterator<Item = String>, seed_env: Option<String>) -> ServerOptions {
+        let mut options = ServerOptions {
+            seed: seed_env.and_then(|seed| parse_seed(&seed, SEED_ENV)),
+            ..ServerOptions::default()
+        };
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--port" => match args.next() {
+                    Some(port) => {
+                        options.port = parse_value(&port, "--port", "port").unwrap_or(options.port)
+                    }
+                    None => eprintln!("--port needs a value"),
+                },
+                "--tick-rate" => match args
+                    .next()
+                    .map(|rate| parse_value(&rate, "--tick-rate", "tick rate"))
+                {
+                    Some(Some(rate)) if rate > 0.0 => options.tick_rate = rate,
+                    Some(Some(_)) => eprintln!("Ignoring --tick-rate: it has to be above zero"),
+                    Some(None) => {}
+                    None => eprintln!("--tick-rate needs a value"),
+                },
+                "--seed" => match args.next() {
+                    Some(seed) => options.seed = parse_seed(&seed, "--seed").or(options.seed),
+                    None => eprintln!("--seed needs a value"),
+                },
+                "--max-players" => match args
+                    .next()
+                    .map(|players| parse_value(&players, "--max-players", "player count"))
+                {
+                    Some(Some(players @ 1..=2)) => options.max_players = players,
+                    Some(Some(_)) => {
+                        eprintln!("Ignoring --max-players: games have one or two players")
+                    }
+                    Some(None) => {}
+                    None => eprintln!("--max-players needs a value"),
+                },
+                _ => eprintln!("Ignoring unknown argument {}", arg),
+            }
+        }
+
+        options
+    }
+
+    /// The kind of game played by `max_players` players.
+    pub fn mode(&self) -> GameMode {
+        match self.max_players {
+            1 => GameMode::Solo,
+            _ => GameMode::Coop,
+        }
+    }
+}
+
+fn parse_seed(value: &str, source: &str) -> Option<u64> {
+    parse_value(value, source, "seed")
+}
+
+fn parse_value<T: FromStr>(value: &str, source: &str, what: &str) -> Option<T> {
+    match value.trim().parse() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            eprintln!("Ignoring {}: {:?} is not a valid {}", source, value, what);
+            None
+        }
+    }
+}
@@ -3,7 +3,21 @@ use bevy::prelude::*;
 #[derive(Component)]
 pub struct GameObject;
 
+/// The score of each player in the game, in player order.
 #[derive(Resource)]
 pub struct Scoreboard {
-    pub score: usize,
+    pub scores: Vec<usize>,
+}
+
+impl Scoreboard {
+    pub fn new(players: usize) -> Scoreboard {
+        Scoreboard {
+            scores: vec![0; players],
+        }
+    }
+
+    /// Everyone's scores added together.
+    pub fn total(&self) -> usize {
+        self.scores.iter().sum()
+    }
 }
@@ -1 +1,8 @@
+use bevy::prelude::Color;
+
 pub const SCOREBOARD_FONT_SIZE: f32 = 40.0;
+
+pub const NORMAL_BUTTON: Color = Color::rgb(0.15, 0.15, 0.15);
+pub const HOVERED_BUTTON: Color = Color::rgb(0.25, 0.25, 0.25);
+pub const PRESSED_BUTTON: Color = Color::rgb(0.9, 0.9, 0.9);
+pub const DISABLED_BUTTON: Color = Color::rgb(0.1, 0.1, 0.1);
@@ -1,5 +1,8 @@
+pub mod cli;
 pub mod component;
 pub mod constants;
 pub mod event;
+pub mod palette;
 pub mod state;
 pub mod system;
+pub mod ui;
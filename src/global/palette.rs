@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The colours used to tell things apart, chosen in the settings.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Palette {
+    #[default]
+    Standard,
+    /// Colours that stay distinct with the common forms of colour blindness.
+    Colorblind,
+}
+
+impl Palette {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Palette::Standard => "Standard",
+            Palette::Colorblind => "Colorblind",
+        }
+    }
+}
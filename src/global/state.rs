@@ -7,4 +7,19 @@ pub enum AppState {
     InGame,
     Paused,
     GameOver,
+    /// Between the rounds of a versus match, and after the last one.
+    RoundResults,
+    HighScores,
+    Settings,
+    /// Playing a game run by a server elsewhere.
+    Online,
+}
+
+/// Adds [`AppState`], starting at the main menu. Every other plugin expects it.
+pub struct AppStatePlugin;
+
+impl Plugin for AppStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<AppState>();
+    }
 }
@@ -0,0 +1,153 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+
+use super::component::GameObject;
+use super::constants::{DISABLED_BUTTON, HOVERED_BUTTON, NORMAL_BUTTON, PRESSED_BUTTON};
+
+/// How big a button and its label are.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ButtonLook {
+    pub size: Size,
+    pub margin: f32,
+    pub font_size: f32,
+}
+
+impl ButtonLook {
+    /// The large buttons of the main and pause menus.
+    pub const MENU: ButtonLook = ButtonLook {
+        size: Size::new(Val::Px(250.0), Val::Px(65.0)),
+        margin: 10.0,
+        font_size: 40.0,
+    };
+
+    /// Slimmer buttons for screens with many of them, such as the settings.
+    pub const COMPACT: ButtonLook = ButtonLook {
+        size: Size::new(Val::Px(440.0), Val::Px(40.0)),
+        margin: 3.0,
+        font_size: 26.0,
+    };
+}
+
+/// A button that can't be clicked, drawn greyed out.
+#[derive(Component)]
+pub struct Disabled;
+
+/// Sent when a button tagged with `T` is clicked, carrying the tag.
+pub struct ButtonClicked<T>(pub T);
+
+/// Spawns a menu button labelled `label`, tagged with `button` so its menu can tell which was
+/// clicked.
+pub fn spawn_button<'w, 's, 'a>(
+    parent: &'a mut ChildBuilder<'w, 's, '_>,
+    asset_server: &AssetServer,
+    label: &str,
+    button: impl Component,
+) -> EntityCommands<'w, 's, 'a> {
+    spawn_button_with_look(parent, asset_server, label, button, ButtonLook::MENU)
+}
+
+pub fn spawn_button_with_look<'w, 's, 'a>(
+    parent: &'a mut ChildBuilder<'w, 's, '_>,
+    asset_server: &AssetServer,
+    label: &str,
+    button: impl Component,
+    look: ButtonLook,
+) -> EntityCommands<'w, 's, 'a> {
+    let mut entity_commands = parent.spawn(ButtonBundle {
+        style: Style {
+            size: look.size,
+            margin: UiRect::all(Val::Px(look.margin)),
+            // horizontally center child text
+            justify_content: JustifyContent::Center,
+            // vertically center child text
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        background_color: NORMAL_BUTTON.into(),
+        ..default()
+    });
+    entity_commands
+        .with_children(|parent| {
+            parent
+                .spawn(TextBundle::from_section(
+                    label,
+                    TextStyle {
+                        font: asset_server.load("fonts/Excluded.ttf"),
+                        font_size: look.font_size,
+                        color: Color::rgb(0.9, 0.9, 0.9),
+                    },
+                ))
+                .insert(GameObject);
+        })
+        .insert(button)
+        .insert(GameObject);
+    entity_commands
+}
+
+/// Adds a line of text to a screen laid out as a column, such as the game over screen.
+pub fn spawn_line<'w, 's, 'a>(
+    parent: &'a mut ChildBuilder<'w, 's, '_>,
+    text: impl Into<String>,
+    style: TextStyle,
+) -> EntityCommands<'w, 's, 'a> {
+    let mut line = parent.spawn(TextBundle::from_section(text, style).with_style(Style {
+        margin: UiRect::all(Val::Px(10.0)),
+        ..default()
+    }));
+    line.insert(GameObject);
+    line
+}
+
+type InteractionQualifiers = (Changed<Interaction>, With<Button>);
+
+/// Colours every button for how the pointer is interacting with it.
+pub fn button_feedback(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor, Option<&Disabled>),
+        InteractionQualifiers,
+    >,
+) {
+    for (interaction, mut color, disabled) in &mut interaction_query {
+        *color = match (*interaction, disabled.is_some()) {
+            (_, true) => DISABLED_BUTTON,
+            (Interaction::Clicked, false) => PRESSED_BUTTON,
+            (Interaction::Hovered, false) => HOVERED_BUTTON,
+            (Interaction::None, false) => NORMAL_BUTTON,
+        }
+        .into();
+    }
+}
+
+/// Sends a [`ButtonClicked`] for every click on an enabled button tagged with `T`.
+pub fn button_clicks<T: Component + Clone>(
+    interaction_query: Query<(&Interaction, &T), (InteractionQualifiers, Without<Disabled>)>,
+    mut clicked_writer: EventWriter<ButtonClicked<T>>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            clicked_writer.send(ButtonClicked(button.clone()));
+        }
+    }
+}
+
+/// Colours buttons as they are hovered and clicked.
+pub struct ButtonPlugin;
+
+impl Plugin for ButtonPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(button_feedback);
+    }
+}
+
+pub trait AddMenuButtons {
+    /// Registers buttons tagged with `T`, so their clicks arrive as [`ButtonClicked<T>`]
+    /// events that the menu can listen for.
+    fn add_menu_buttons<T: Component + Clone>(&mut self) -> &mut Self;
+}
+
+impl AddMenuButtons for App {
+    fn add_menu_buttons<T: Component + Clone>(&mut self) -> &mut Self {
+        self.add_event::<ButtonClicked<T>>()
+            .add_system(button_clicks::<T>)
+    }
+}
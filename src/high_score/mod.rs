@@ -0,0 +1,4 @@
+pub mod plugin;
+pub mod table;
+
+mod system;
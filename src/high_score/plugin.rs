@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+
+use crate::global::state::AppState;
+use crate::global::system::clear_game_objects;
+
+use super::system::*;
+use super::table::{default_path, HighScoreTable};
+
+pub struct HighScorePlugin;
+
+/// The persisted high score table and where it is saved.
+#[derive(Resource)]
+pub struct HighScores {
+    pub table: HighScoreTable,
+    pub path: Option<std::path::PathBuf>,
+}
+
+impl HighScores {
+    pub fn save(&self) {
+        if let Some(path) = &self.path {
+            if let Err(error) = self.table.save(path) {
+                warn!(
+                    "Failed to save high scores to {}: {}",
+                    path.display(),
+                    error
+                );
+            }
+        }
+    }
+}
+
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        let path = default_path();
+        let table = path
+            .as_deref()
+            .map(HighScoreTable::load)
+            .unwrap_or_default();
+
+        app.insert_resource(HighScores { table, path })
+            .add_system(show_high_scores.in_schedule(OnEnter(AppState::HighScores)))
+            .add_system(back_to_menu_listener.in_set(OnUpdate(AppState::HighScores)))
+            .add_system(clear_game_objects.in_schedule(OnExit(AppState::HighScores)));
+    }
+}
@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+
+use crate::action::state::{Action, ActionState};
+use crate::global::{component::GameObject, state::AppState};
+
+use super::plugin::HighScores;
+use super::table::MAX_ENTRIES;
+
+pub fn show_high_scores(
+    mut commands: Commands,
+    high_scores: Res<HighScores>,
+    asset_server: Res<AssetServer>,
+) {
+    let font: Handle<Font> = asset_server.load("fonts/Excluded.ttf");
+    let italic_font: Handle<Font> = asset_server.load("fonts/ExcludedItalic.ttf");
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn(
+                    TextBundle::from_section(
+                        "High Scores",
+                        TextStyle {
+                            font: italic_font,
+                            font_size: 60.0,
+                            color: Color::WHITE,
+                        },
+                    )
+                    .with_style(Style {
+                        margin: UiRect::bottom(Val::Px(30.0)),
+                        ..default()
+                    }),
+                )
+                .insert(GameObject);
+
+            for rank in 0..MAX_ENTRIES {
+                let line = match high_scores.table.entries().get(rank) {
+                    Some(entry) => {
+                        format!("{:>2}. {:<12} {:>7}", rank + 1, entry.name, entry.score)
+                    }
+                    None => format!("{:>2}. {:<12} {:>7}", rank + 1, "---", "-"),
+                };
+                parent
+                    .spawn(TextBundle::from_section(
+                        line,
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 28.0,
+                            color: Color::WHITE,
+                        },
+                    ))
+                    .insert(GameObject);
+            }
+
+            parent
+                .spawn(
+                    TextBundle::from_section(
+                        "Click anywhere to return.",
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            color: Color::WHITE,
+                        },
+                    )
+                    .with_style(Style {
+                        margin: UiRect::top(Val::Px(30.0)),
+                        ..default()
+                    }),
+                )
+                .insert(GameObject);
+        })
+        .insert(GameObject);
+}
+
+pub fn back_to_menu_listener(
+    buttons: Res<Input<MouseButton>>,
+    actions: Res<ActionState>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if buttons.just_pressed(MouseButton::Left)
+        || actions.just_pressed(Action::Confirm)
+        || actions.just_pressed(Action::Pause)
+    {
+        next_state.set(AppState::MainMenu);
+    }
+}
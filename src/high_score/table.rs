@@ -0,0 +1,239 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Number of entries kept in the table.
+pub const MAX_ENTRIES: usize = 10;
+/// Longest name that can be entered on the game over screen.
+pub const MAX_NAME_LENGTH: usize = 12;
+
+const FILE_HEADER: &str = "asteroids-high-scores";
+const FILE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighScoreEntry {
+    pub name: String,
+    pub score: usize,
+}
+
+#[derive(Debug)]
+pub enum HighScoreError {
+    Io(io::Error),
+    MissingHeader,
+    UnsupportedVersion(String),
+    MalformedEntry(usize),
+}
+
+impl fmt::Display for HighScoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HighScoreError::Io(error) => write!(f, "{}", error),
+            HighScoreError::MissingHeader => write!(f, "missing file header"),
+            HighScoreError::UnsupportedVersion(version) => {
+                write!(f, "unsupported file version {}", version)
+            }
+            HighScoreError::MalformedEntry(line) => write!(f, "malformed entry on line {}", line),
+        }
+    }
+}
+
+impl From<io::Error> for HighScoreError {
+    fn from(error: io::Error) -> HighScoreError {
+        HighScoreError::Io(error)
+    }
+}
+
+/// The top scores, highest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HighScoreTable {
+    entries: Vec<HighScoreEntry>,
+}
+
+impl HighScoreTable {
+    pub fn entries(&self) -> &[HighScoreEntry] {
+        &self.entries
+    }
+
+    /// Whether `score` would earn a place in the table.
+    pub fn qualifies(&self, score: usize) -> bool {
+        score > 0
+            && (self.entries.len() < MAX_ENTRIES || score > self.entries[MAX_ENTRIES - 1].score)
+    }
+
+    /// Adds a score to the table, returning its zero-based rank if it made the cut.
+    ///
+    /// Ties are ranked below the existing entries.
+    pub fn insert(&mut self, name: &str, score: usize) -> Option<usize> {
+        if !self.qualifies(score) {
+            return None;
+        }
+
+        let rank = self
+            .entries
+            .iter()
+            .position(|entry| score > entry.score)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(
+            rank,
+            HighScoreEntry {
+                name: sanitize_name(name),
+                score,
+            },
+        );
+        self.entries.truncate(MAX_ENTRIES);
+
+        Some(rank)
+    }
+
+    pub fn parse(contents: &str) -> Result<HighScoreTable, HighScoreError> {
+        let mut lines = contents.lines();
+
+        let header = lines.next().ok_or(HighScoreError::MissingHeader)?;
+        let version = header
+            .strip_prefix(FILE_HEADER)
+            .ok_or(HighScoreError::MissingHeader)?
+            .trim();
+        if version != FILE_VERSION.to_string() {
+            return Err(HighScoreError::UnsupportedVersion(version.to_string()));
+        }
+
+        let mut table = HighScoreTable::default();
+        for (index, line) in lines.enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+
+            let (score, name) = line
+                .split_once('\t')
+                .ok_or(HighScoreError::MalformedEntry(index + 2))?;
+            let score = score
+                .parse()
+                .map_err(|_| HighScoreError::MalformedEntry(index + 2))?;
+            table.insert(name, score);
+        }
+
+        Ok(table)
+    }
+
+    pub fn serialize(&self) -> String {
+        let mut contents = format!("{} {}\n", FILE_HEADER, FILE_VERSION);
+        for entry in &self.entries {
+            contents.push_str(&format!("{}\t{}\n", entry.score, entry.name));
+        }
+        contents
+    }
+
+    pub fn read(path: &Path) -> Result<HighScoreTable, HighScoreError> {
+        HighScoreTable::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Loads the table at `path`, falling back to an empty table if the file is missing or corrupt.
+    pub fn load(path: &Path) -> HighScoreTable {
+        match HighScoreTable::read(path) {
+            Ok(table) => table,
+            Err(HighScoreError::Io(error)) if error.kind() == io::ErrorKind::NotFound => {
+                HighScoreTable::default()
+            }
+            Err(error) => {
+                bevy::log::warn!("Ignoring high score file {}: {}", path.display(), error);
+                HighScoreTable::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.serialize())
+    }
+}
+
+/// Where the high score table lives, if the platform has a data directory.
+pub fn default_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("asteroids").join("high_scores.txt"))
+}
+
+/// Strips characters that would break the file format and clamps the name length.
+pub fn sanitize_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_NAME_LENGTH)
+        .collect();
+    let name = name.trim();
+
+    if name.is_empty() {
+        "Anonymous".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table_with(scores: &[usize]) -> HighScoreTable {
+        let mut table = HighScoreTable::default();
+        for score in scores {
+            table.insert("AAA", *score);
+        }
+        table
+    }
+
+    #[test]
+    fn keeps_the_top_ten_in_order() {
+        let table = table_with(&[50, 10, 90, 30, 70, 20, 100, 60, 40, 80, 5, 110]);
+
+        let scores: Vec<usize> = table.entries().iter().map(|entry| entry.score).collect();
+        assert_eq!(scores, vec![110, 100, 90, 80, 70, 60, 50, 40, 30, 20]);
+    }
+
+    #[test]
+    fn only_scores_above_a_full_table_qualify() {
+        let table = table_with(&[10; MAX_ENTRIES]);
+
+        assert!(!table.qualifies(0));
+        assert!(!table.qualifies(10));
+        assert!(table.qualifies(11));
+    }
+
+    #[test]
+    fn round_trips_through_the_file_format() {
+        let mut table = HighScoreTable::default();
+        table.insert("Ripley", 1200);
+        table.insert("Bishop\tAndroid\n", 800);
+
+        let parsed = HighScoreTable::parse(&table.serialize()).unwrap();
+        assert_eq!(parsed, table);
+        assert_eq!(parsed.entries()[1].name, "BishopAndroi");
+    }
+
+    #[test]
+    fn rejects_corrupt_files() {
+        assert!(HighScoreTable::parse("").is_err());
+        assert!(HighScoreTable::parse("asteroids-high-scores 99\n").is_err());
+        assert!(HighScoreTable::parse("asteroids-high-scores 1\nnot a score\n").is_err());
+    }
+
+    #[test]
+    fn load_falls_back_to_an_empty_table() {
+        let dir =
+            std::env::temp_dir().join(format!("asteroids-high-scores-{}", std::process::id()));
+        let path = dir.join("high_scores.txt");
+
+        assert_eq!(HighScoreTable::load(&path), HighScoreTable::default());
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, "garbage").unwrap();
+        assert_eq!(HighScoreTable::load(&path), HighScoreTable::default());
+
+        let table = table_with(&[300, 200]);
+        table.save(&path).unwrap();
+        assert_eq!(HighScoreTable::load(&path), table);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
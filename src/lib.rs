@@ -0,0 +1,43 @@
+//! Asteroids, built with Bevy.
+//!
+//! [`AsteroidsPlugins`] adds the whole game to an app, or any part of it. The game itself is
+//! `src/main.rs` and the dedicated server `src/bin/server.rs`, both thin binaries on top of
+//! this library.
+
+pub mod action;
+pub mod audio;
+pub mod camera;
+pub mod config;
+pub mod game;
+pub mod game_over;
+pub mod global;
+pub mod high_score;
+pub mod main_menu;
+pub mod net;
+pub mod particle;
+pub mod pause_menu;
+pub mod plugins;
+pub mod replay;
+pub mod settings;
+#[cfg(test)]
+mod testing;
+pub mod versus;
+
+pub use game::plugin::{GamePlugin, GameSet, GameVisualsPlugin};
+pub use game_over::plugin::GameOverPlugin;
+pub use global::state::{AppState, AppStatePlugin};
+pub use main_menu::plugin::MainMenuPlugin;
+pub use plugins::AsteroidsPlugins;
+
+/// The plugins, components and events most apps built on the game need.
+pub mod prelude {
+    pub use crate::action::state::{Action, ActionState};
+    pub use crate::game::components::*;
+    pub use crate::game::event::*;
+    pub use crate::global::component::{GameObject, Scoreboard};
+    pub use crate::global::event::GameOverEvent;
+    pub use crate::{
+        AppState, AppStatePlugin, AsteroidsPlugins, GameOverPlugin, GamePlugin, GameSet,
+        GameVisualsPlugin, MainMenuPlugin,
+    };
+}
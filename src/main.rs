@@ -1,58 +1,82 @@
-mod game;
-mod game_over;
-mod global;
-mod main_menu;
-
+use asteroids::game::components::{Playfield, SeedConfig};
+use asteroids::global::cli::LaunchOptions;
+use asteroids::net::client::NetClient;
+use asteroids::replay::plugin::PlaybackRequest;
+use asteroids::settings::plugin::SettingsPlugin;
+use asteroids::settings::preferences::{self, Preferences};
+use asteroids::AsteroidsPlugins;
 use bevy_embedded_assets::EmbeddedAssetPlugin;
-use game::event::*;
-use game::plugin::GamePlugin;
-use game_over::plugin::GameOverPlugin;
-use global::component::GameObject;
-use global::event::*;
-use global::state::AppState;
-use main_menu::plugin::MainMenuPlugin;
 
+use bevy::app::PluginGroupBuilder;
 use bevy::prelude::*;
+use bevy::window::WindowResizeConstraints;
 
 const BACKGROUND_COLOR: Color = Color::rgb(0.1, 0.1, 0.1);
+const MIN_WINDOW_SIZE: f32 = 400.0;
 
 fn main() {
-    App::new()
-        // Default and window setup
-        .add_plugins(
-            DefaultPlugins
-                .set(WindowPlugin {
-                    primary_window: Some(Window {
-                        title: "Asteroids!".into(),
-                        resolution: (800., 800.).into(),
-                        ..default()
-                    }),
-                    ..default()
-                })
-                .build()
-                .add_before::<bevy::asset::AssetPlugin, _>(EmbeddedAssetPlugin),
-        )
+    let options = LaunchOptions::from_env();
+    let playfield = Playfield::default();
+    // Read before the window is created, so it opens the way it was left
+    let preferences_path = preferences::default_path();
+    let preferences = preferences_path
+        .as_deref()
+        .map(Preferences::load)
+        .unwrap_or_default();
+
+    let mut app = App::new();
+    app.add_plugins(default_plugins(&playfield, &preferences))
         .insert_resource(ClearColor(BACKGROUND_COLOR))
-        // State
-        .add_state::<AppState>()
-        // Events
-        .add_event::<FireEvent>()
-        .add_event::<PlayerHitEvent>()
-        .add_event::<GameOverEvent>()
-        // Base systems
-        .add_startup_system(setup_camera)
-        .add_plugin(MainMenuPlugin)
-        .add_plugin(GamePlugin)
-        .add_plugin(GameOverPlugin)
-        .run();
-}
+        .insert_resource(playfield)
+        .insert_resource(SeedConfig {
+            fixed_seed: options.seed,
+        })
+        .add_plugins(AsteroidsPlugins::default().with_settings(SettingsPlugin {
+            preferences,
+            path: preferences_path,
+        }));
 
-fn setup_camera(mut commands: Commands) {
-    commands.spawn(Camera2dBundle::default());
+    if let Some(path) = options.replay {
+        app.insert_resource(PlaybackRequest(path));
+    }
+    if let Some(address) = options.connect {
+        match NetClient::connect(address.as_str()) {
+            Ok(client) => {
+                app.insert_resource(client);
+            }
+            Err(error) => error!("Can't connect to {}: {}", address, error),
+        }
+    }
+
+    app.run();
 }
 
-fn clear_game_objects(mut commands: Commands, entities_query: Query<Entity, With<GameObject>>) {
-    for entity in entities_query.iter() {
-        commands.entity(entity).despawn();
+fn default_plugins(playfield: &Playfield, preferences: &Preferences) -> PluginGroupBuilder {
+    let plugins = DefaultPlugins.set(WindowPlugin {
+        primary_window: Some(Window {
+            title: "Asteroids!".into(),
+            resolution: (playfield.width, playfield.height).into(),
+            mode: preferences.window_mode(),
+            present_mode: preferences.present_mode(),
+            // Any size works, as the camera and UI scale to fit, but too small is unplayable
+            resize_constraints: WindowResizeConstraints {
+                min_width: MIN_WINDOW_SIZE,
+                min_height: MIN_WINDOW_SIZE,
+                ..default()
+            },
+            ..default()
+        }),
+        ..default()
+    });
+
+    // Debug builds read assets from disk and watch them, so the game config can be tuned
+    // while the game runs. Release builds carry their assets inside the executable.
+    if cfg!(debug_assertions) {
+        plugins.set(AssetPlugin {
+            watch_for_changes: true,
+            ..default()
+        })
+    } else {
+        plugins.add_before::<AssetPlugin, _>(EmbeddedAssetPlugin)
     }
 }
@@ -0,0 +1,13 @@
+use bevy::prelude::*;
+
+#[derive(Component, Clone, Copy)]
+pub enum MainMenuButton {
+    Play,
+    /// Two players on one screen, on the same side.
+    Coop,
+    /// Two players on one screen, shooting at each other.
+    Versus,
+    HighScores,
+    WatchReplay,
+    Settings,
+}
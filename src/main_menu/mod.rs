@@ -1,4 +1,4 @@
 pub mod plugin;
 
-mod constants;
+pub(crate) mod components;
 mod system;
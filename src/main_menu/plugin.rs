@@ -2,7 +2,9 @@ use bevy::prelude::*;
 
 use crate::global::state::AppState;
 use crate::global::system::clear_game_objects;
+use crate::global::ui::AddMenuButtons;
 
+use super::components::MainMenuButton;
 use super::system::input::*;
 use super::system::render::*;
 
@@ -10,8 +12,9 @@ pub struct MainMenuPlugin;
 
 impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(setup.in_schedule(OnEnter(AppState::MainMenu)))
-            .add_system(input.in_set(OnUpdate(AppState::MainMenu)))
+        app.add_menu_buttons::<MainMenuButton>()
+            .add_system(setup.in_schedule(OnEnter(AppState::MainMenu)))
+            .add_systems((button_listener, confirm_listener).in_set(OnUpdate(AppState::MainMenu)))
             .add_system(clear_game_objects.in_schedule(OnExit(AppState::MainMenu)));
     }
 }
@@ -1,25 +1,55 @@
 use bevy::prelude::*;
 
-use crate::{global::state::AppState, main_menu::constants::*};
+use crate::{
+    action::state::{Action, ActionState},
+    game::components::GameMode,
+    global::state::AppState,
+    global::ui::ButtonClicked,
+    main_menu::components::MainMenuButton,
+    replay::{file::last_replay_path, plugin::PlaybackRequest},
+    settings::plugin::SettingsReturn,
+};
 
-type InteractionQualifiers = (Changed<Interaction>, With<Button>);
-
-pub fn input(
-    mut interaction_query: Query<(&Interaction, &mut BackgroundColor), InteractionQualifiers>,
+pub fn button_listener(
+    mut commands: Commands,
+    mut clicked_reader: EventReader<ButtonClicked<MainMenuButton>>,
+    mut mode: ResMut<GameMode>,
     mut next_state: ResMut<NextState<AppState>>,
 ) {
-    for (interaction, mut color) in &mut interaction_query {
-        match *interaction {
-            Interaction::Clicked => {
-                *color = PRESSED_BUTTON.into();
+    for ButtonClicked(button) in clicked_reader.iter() {
+        match button {
+            MainMenuButton::Play => {
+                *mode = GameMode::Solo;
                 next_state.set(AppState::InGame);
             }
-            Interaction::Hovered => {
-                *color = HOVERED_BUTTON.into();
+            MainMenuButton::Coop => {
+                *mode = GameMode::Coop;
+                next_state.set(AppState::InGame);
             }
-            Interaction::None => {
-                *color = NORMAL_BUTTON.into();
+            MainMenuButton::Versus => {
+                *mode = GameMode::Versus;
+                next_state.set(AppState::InGame);
+            }
+            MainMenuButton::HighScores => next_state.set(AppState::HighScores),
+            MainMenuButton::WatchReplay => match last_replay_path() {
+                Some(path) => commands.insert_resource(PlaybackRequest(path)),
+                None => warn!("No data directory to load replays from"),
+            },
+            MainMenuButton::Settings => {
+                commands.insert_resource(SettingsReturn(AppState::MainMenu));
+                next_state.set(AppState::Settings);
             }
         }
     }
 }
+
+pub fn confirm_listener(
+    actions: Res<ActionState>,
+    mut mode: ResMut<GameMode>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if actions.just_pressed(Action::Confirm) {
+        *mode = GameMode::Solo;
+        next_state.set(AppState::InGame);
+    }
+}
@@ -1,36 +1,17 @@
 use bevy::prelude::*;
 
 use crate::global::component::GameObject;
-use crate::main_menu::constants::*;
+use crate::global::constants::DISABLED_BUTTON;
+use crate::global::ui::{spawn_button, Disabled};
+use crate::main_menu::components::MainMenuButton;
+use crate::replay::file::last_replay_path;
 
 pub fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands
-        .spawn(
-            TextBundle::from_section(
-                "'Stroids",
-                TextStyle {
-                    font: asset_server.load("fonts/ExcludedItalic.ttf"),
-                    font_size: 60.0,
-                    color: Color::WHITE,
-                },
-            )
-            .with_style(Style {
-                display: Display::Flex,
-                position_type: PositionType::Absolute,
-                position: UiRect {
-                    top: Val::Px(100.0),
-                    left: Val::Px(250.0),
-                    ..default()
-                },
-                ..default()
-            }),
-        )
-        .insert(GameObject);
-
     commands
         .spawn(NodeBundle {
             style: Style {
-                size: Size::width(Val::Percent(100.0)),
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::Column,
                 align_items: AlignItems::Center,
                 justify_content: JustifyContent::Center,
                 ..default()
@@ -39,31 +20,42 @@ pub fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
         })
         .with_children(|parent| {
             parent
-                .spawn(ButtonBundle {
-                    style: Style {
-                        size: Size::new(Val::Px(150.0), Val::Px(65.0)),
-                        // horizontally center child text
-                        justify_content: JustifyContent::Center,
-                        // vertically center child text
-                        align_items: AlignItems::Center,
+                .spawn(
+                    TextBundle::from_section(
+                        "'Stroids",
+                        TextStyle {
+                            font: asset_server.load("fonts/ExcludedItalic.ttf"),
+                            font_size: 60.0,
+                            color: Color::WHITE,
+                        },
+                    )
+                    .with_style(Style {
+                        margin: UiRect::bottom(Val::Px(60.0)),
                         ..default()
-                    },
-                    background_color: NORMAL_BUTTON.into(),
-                    ..default()
-                })
-                .with_children(|parent| {
-                    parent
-                        .spawn(TextBundle::from_section(
-                            "Play",
-                            TextStyle {
-                                font: asset_server.load("fonts/Excluded.ttf"),
-                                font_size: 40.0,
-                                color: Color::rgb(0.9, 0.9, 0.9),
-                            },
-                        ))
-                        .insert(GameObject);
-                })
+                    }),
+                )
                 .insert(GameObject);
+
+            spawn_button(parent, &asset_server, "Play", MainMenuButton::Play);
+            spawn_button(parent, &asset_server, "Co-op", MainMenuButton::Coop);
+            spawn_button(parent, &asset_server, "Versus", MainMenuButton::Versus);
+            spawn_button(
+                parent,
+                &asset_server,
+                "High Scores",
+                MainMenuButton::HighScores,
+            );
+            let mut replay = spawn_button(
+                parent,
+                &asset_server,
+                "Watch Replay",
+                MainMenuButton::WatchReplay,
+            );
+            // Nothing to watch until a game has been played
+            if !last_replay_path().is_some_and(|path| path.exists()) {
+                replay.insert((Disabled, BackgroundColor(DISABLED_BUTTON)));
+            }
+            spawn_button(parent, &asset_server, "Settings", MainMenuButton::Settings);
         })
         .insert(GameObject);
 }
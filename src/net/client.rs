@@ -0,0 +1,424 @@
+use std::collections::VecDeque;
+use std::io;
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::action::plugin::SampleActions;
+use crate::action::state::{Action, ActionState, TickInput};
+use crate::config::game_config::GameConfig;
+use crate::game::components::{GameMode, PlayerId};
+use crate::game::system::sprite::*;
+use crate::global::component::GameObject;
+use crate::global::constants::SCOREBOARD_FONT_SIZE;
+use crate::global::palette::Palette;
+use crate::global::state::AppState;
+use crate::global::system::clear_game_objects;
+use crate::replay::file::TickRecord;
+
+use super::interpolation::SnapshotBuffer;
+use super::protocol::*;
+use super::{is_reset, MAX_DATAGRAM, MAX_RESETS};
+
+/// How often to ask the server for a place until it answers.
+const JOIN_INTERVAL: f32 = 0.5;
+/// How long the server can go quiet during a game before it's taken to be gone.
+const SERVER_TIMEOUT: f32 = 5.0;
+const STATUS_PADDING: Val = Val::Px(10.0);
+
+/// Plays a game run by a server elsewhere: local input goes out, and whatever the server says
+/// is on the playfield is drawn.
+///
+/// Inserting a [`NetClient`] switches to [`AppState::Online`] and joins its server.
+pub struct ClientPlugin;
+
+impl Plugin for ClientPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SnapshotBuffer>()
+            .add_system(
+                go_online
+                    .run_if(resource_added::<NetClient>())
+                    .in_base_set(CoreSet::PreUpdate),
+            )
+            .add_system(
+                receive_server_messages
+                    .run_if(resource_exists::<NetClient>())
+                    .in_base_set(CoreSet::PreUpdate),
+            )
+            .add_system(setup_online.in_schedule(OnEnter(AppState::Online)))
+            .add_systems(
+                (
+                    request_join,
+                    leave_listener,
+                    interpolate_remote_entities,
+                    sync_remote_entities,
+                    update_online_status,
+                )
+                    .chain()
+                    .distributive_run_if(resource_exists::<NetClient>())
+                    .in_set(OnUpdate(AppState::Online)),
+            )
+            .add_system(
+                send_input
+                    .run_if(resource_exists::<NetClient>())
+                    .run_if(in_state(AppState::Online))
+                    .after(SampleActions)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_systems(
+                (clear_game_objects, restore_tick_rate).in_schedule(OnExit(AppState::Online)),
+            );
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    Connecting,
+    Joined {
+        player: PlayerId,
+        players: usize,
+    },
+    Rejected(String),
+    /// The server went quiet in the middle of a game.
+    Lost,
+}
+
+/// The connection to a game server.
+#[derive(Resource)]
+pub struct NetClient {
+    socket: UdpSocket,
+    pub status: ConnectionStatus,
+    join_timer: Timer,
+    /// Time since the server was last heard from.
+    silence: Timer,
+    /// Sequence number of the next input sent.
+    sequence: u32,
+    /// The last few ticks of input, repeated in every message.
+    recent: VecDeque<TickRecord>,
+}
+
+impl NetClient {
+    /// Opens a socket for talking to the server at `server`. Joining starts once inserted.
+    pub fn connect(server: impl ToSocketAddrs) -> io::Result<NetClient> {
+        let socket = UdpSocket::bind(("0.0.0.0", 0))?;
+        socket.connect(server)?;
+        socket.set_nonblocking(true)?;
+        let mut join_timer = Timer::from_seconds(JOIN_INTERVAL, TimerMode::Repeating);
+        // Ask straight away
+        join_timer.set_elapsed(Duration::from_secs_f32(JOIN_INTERVAL));
+        Ok(NetClient {
+            socket,
+            status: ConnectionStatus::Connecting,
+            join_timer,
+            silence: Timer::from_seconds(SERVER_TIMEOUT, TimerMode::Once),
+            sequence: 0,
+            recent: VecDeque::with_capacity(INPUT_REDUNDANCY),
+        })
+    }
+
+    fn send(&self, message: &ClientMessage) {
+        // Anything lost is repeated or made up for by the next message
+        if let Err(error) = self.socket.send(&message.encode()) {
+            debug!("Failed to send to the server: {}", error);
+        }
+    }
+}
+
+/// A copy of one of the server's entities.
+#[derive(Component, Debug)]
+pub struct RemoteEntity {
+    pub id: u64,
+    pub kind: EntityKind,
+}
+
+#[derive(Component)]
+pub struct OnlineStatusText;
+
+/// The tick period local games run at, kept while playing at the server's rate.
+#[derive(Resource)]
+pub struct LocalTickPeriod(Duration);
+
+pub fn go_online(mut next_state: ResMut<NextState<AppState>>) {
+    next_state.set(AppState::Online);
+}
+
+pub fn setup_online(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut actions: ResMut<ActionState>,
+    mut buffer: ResMut<SnapshotBuffer>,
+) {
+    // Only the first player's bindings play; the others are on their own machines
+    actions.set_players(GameMode::Solo.players());
+    *buffer = SnapshotBuffer::default();
+
+    commands
+        .spawn(
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/Excluded.ttf"),
+                    font_size: SCOREBOARD_FONT_SIZE,
+                    color: Color::WHITE,
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: STATUS_PADDING,
+                    left: STATUS_PADDING,
+                    ..default()
+                },
+                ..default()
+            }),
+        )
+        .insert(OnlineStatusText)
+        .insert(GameObject);
+}
+
+pub fn request_join(time: Res<Time>, mut client: ResMut<NetClient>) {
+    if client.status != ConnectionStatus::Connecting {
+        return;
+    }
+    if client.join_timer.tick(time.delta()).just_finished() {
+        client.send(&ClientMessage::Join);
+    }
+}
+
+pub fn receive_server_messages(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut client: ResMut<NetClient>,
+    mut buffer: ResMut<SnapshotBuffer>,
+    mut fixed_time: ResMut<FixedTime>,
+) {
+    let mut bytes = [0; MAX_DATAGRAM];
+    let mut resets = 0;
+    client.silence.tick(time.delta());
+
+    loop {
+        let length = match client.socket.recv(&mut bytes) {
+            Ok(length) => length,
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => break,
+            // Refused until the server is up, on some platforms
+            Err(error) if is_reset(&error) && resets < MAX_RESETS => {
+                resets += 1;
+                continue;
+            }
+            Err(error) => {
+                warn!("Failed to receive from the server: {}", error);
+                break;
+            }
+        };
+
+        let message = ServerMessage::decode(&bytes[..length]);
+        if message.is_ok() {
+            client.silence.reset();
+        }
+
+        match message {
+            Ok(ServerMessage::Welcome {
+                player,
+                players,
+                tick_rate,
+            }) => {
+                if let ConnectionStatus::Connecting = client.status {
+                    info!("Joined as player {} of {}", player.0 + 1, players);
+                    // Input has to be sampled at the server's rate to line up with its ticks
+                    commands.insert_resource(LocalTickPeriod(fixed_time.period));
+                    fixed_time.period = Duration::from_secs_f32(1.0 / tick_rate);
+                    client.status = ConnectionStatus::Joined {
+                        player,
+                        players: players as usize,
+                    };
+                }
+            }
+            Ok(ServerMessage::Rejected(reason)) => {
+                client.status = ConnectionStatus::Rejected(reason.to_string())
+            }
+            Ok(ServerMessage::Snapshot(snapshot)) => buffer.push(snapshot),
+            Err(error) => debug!("Ignoring datagram from the server: {}", error),
+        }
+    }
+
+    // The server says nothing while waiting for players, and stops once the game is over
+    let playing = buffer.newest().is_some_and(|snapshot| !snapshot.finished);
+    if playing && client.silence.finished() {
+        client.status = ConnectionStatus::Lost;
+    }
+}
+
+/// Sends this tick's input, along with the few before it in case those went missing.
+pub fn send_input(mut client: ResMut<NetClient>, input: Res<TickInput>) {
+    if !matches!(client.status, ConnectionStatus::Joined { .. }) {
+        return;
+    }
+    let Some(actions) = input.player(0) else {
+        return;
+    };
+
+    if client.recent.len() == INPUT_REDUNDANCY {
+        client.recent.pop_front();
+    }
+    client.recent.push_back(TickRecord::from_actions(actions));
+    let message = ClientMessage::Input {
+        sequence: client.sequence,
+        records: client.recent.iter().copied().collect(),
+    };
+    client.send(&message);
+    client.sequence = client.sequence.wrapping_add(1);
+}
+
+pub fn leave_listener(
+    mut commands: Commands,
+    client: Res<NetClient>,
+    actions: Res<ActionState>,
+    buffer: Res<SnapshotBuffer>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let finished = buffer.newest().is_some_and(|snapshot| snapshot.finished);
+    let gone = matches!(
+        client.status,
+        ConnectionStatus::Rejected(_) | ConnectionStatus::Lost
+    );
+    if actions.just_pressed(Action::Pause)
+        || ((finished || gone) && actions.just_pressed(Action::Confirm))
+    {
+        client.send(&ClientMessage::Leave);
+        commands.remove_resource::<NetClient>();
+        next_state.set(AppState::MainMenu);
+    }
+}
+
+pub fn restore_tick_rate(
+    mut commands: Commands,
+    local_period: Option<Res<LocalTickPeriod>>,
+    mut fixed_time: ResMut<FixedTime>,
+) {
+    if let Some(local_period) = local_period {
+        fixed_time.period = local_period.0;
+        commands.remove_resource::<LocalTickPeriod>();
+    }
+}
+
+pub fn interpolate_remote_entities(
+    time: Res<Time>,
+    fixed_time: Res<FixedTime>,
+    mut buffer: ResMut<SnapshotBuffer>,
+) {
+    buffer.advance(time.delta_seconds_f64() / fixed_time.period.as_secs_f64());
+}
+
+pub fn sync_remote_entities(
+    mut commands: Commands,
+    buffer: Res<SnapshotBuffer>,
+    palette: Res<Palette>,
+    config: Res<GameConfig>,
+    asset_server: Res<AssetServer>,
+    mut remote_query: Query<(Entity, &RemoteEntity, &mut Transform)>,
+) {
+    let mut states: HashMap<u64, EntityState> = buffer
+        .sample()
+        .into_iter()
+        .map(|state| (state.id, state))
+        .collect();
+
+    for (entity, remote, mut transform) in remote_query.iter_mut() {
+        match states.remove(&remote.id) {
+            // The server reuses ids, so the same id can come back as something else
+            Some(state) if state.kind == remote.kind => place(&mut transform, &state),
+            Some(state) => {
+                commands.entity(entity).despawn();
+                states.insert(state.id, state);
+            }
+            None => commands.entity(entity).despawn(),
+        }
+    }
+
+    for state in states.into_values() {
+        let (sprite, texture) = remote_sprite(state.kind, *palette, &config, &asset_server);
+        let mut transform = Transform::default();
+        place(&mut transform, &state);
+        commands
+            .spawn(SpriteBundle {
+                sprite,
+                texture,
+                transform,
+                ..default()
+            })
+            .insert(RemoteEntity {
+                id: state.id,
+                kind: state.kind,
+            })
+            .insert(GameObject);
+    }
+}
+
+fn place(transform: &mut Transform, state: &EntityState) {
+    transform.translation = state.translation.extend(transform.translation.z);
+    transform.rotation = Quat::from_rotation_z(state.rotation);
+    transform.scale = Vec3::new(state.scale, state.scale, 1.0);
+}
+
+/// Looks like the entity the game itself would have drawn.
+fn remote_sprite(
+    kind: EntityKind,
+    palette: Palette,
+    config: &GameConfig,
+    asset_server: &AssetServer,
+) -> (Sprite, Handle<Image>) {
+    match kind {
+        EntityKind::Ship(player) => ship_sprite(player, palette, asset_server),
+        EntityKind::Asteroid(_, sprite) => asteroid_sprite(sprite, asset_server),
+        EntityKind::Laser(_) => laser_sprite(asset_server),
+        EntityKind::Saucer(size) => saucer_sprite(size, asset_server),
+        EntityKind::SaucerShot => saucer_shot_sprite(asset_server),
+        EntityKind::PowerUp(kind) => power_up_sprite(kind, palette, config),
+    }
+}
+
+pub fn update_online_status(
+    client: Res<NetClient>,
+    buffer: Res<SnapshotBuffer>,
+    mut text_query: Query<&mut Text, With<OnlineStatusText>>,
+) {
+    let status = match (&client.status, buffer.newest()) {
+        (ConnectionStatus::Connecting, _) => "Connecting...".to_string(),
+        (ConnectionStatus::Rejected(reason), _) => format!("Can't join: {}", reason),
+        (ConnectionStatus::Lost, _) => "Lost the connection to the server".to_string(),
+        (ConnectionStatus::Joined { player, players }, None) => {
+            format!(
+                "Joined as {}, waiting for {} players",
+                player.label(),
+                players
+            )
+        }
+        (ConnectionStatus::Joined { player, .. }, Some(snapshot)) => {
+            let mut lines: Vec<String> = snapshot
+                .players
+                .iter()
+                .enumerate()
+                .map(|(index, state)| {
+                    let you = if index == player.0 { " (you)" } else { "" };
+                    format!(
+                        "{}{}: {} lives, {} points",
+                        PlayerId(index).label(),
+                        you,
+                        state.lives,
+                        state.score
+                    )
+                })
+                .collect();
+            if snapshot.finished {
+                lines.push("Game over".to_string());
+            }
+            lines.join("\n")
+        }
+    };
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value.clone_from(&status);
+    }
+}
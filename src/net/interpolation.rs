@@ -0,0 +1,137 @@
+use std::collections::VecDeque;
+use std::f32::consts::{PI, TAU};
+
+use bevy::prelude::*;
+
+use super::protocol::{EntityState, Snapshot};
+
+/// How far behind the newest snapshot remote entities are drawn, in ticks. A late or lost
+/// snapshot then still leaves two to move between.
+pub const INTERPOLATION_DELAY: f64 = 6.0;
+/// Falling further behind than this, e.g. after a stall, jumps straight back to the delay.
+const MAX_LAG: f64 = 30.0;
+/// Movement over this distance between two snapshots is a wrap around the playfield edge,
+/// which is jumped rather than drawn sweeping across the screen.
+const MAX_STEP: f32 = 200.0;
+
+/// The snapshots received from the server, and the point in server time being drawn.
+#[derive(Resource, Debug, Default)]
+pub struct SnapshotBuffer {
+    snapshots: VecDeque<Snapshot>,
+    /// Server tick being drawn, between two snapshots.
+    render_tick: f64,
+}
+
+impl SnapshotBuffer {
+    pub fn newest(&self) -> Option<&Snapshot> {
+        self.snapshots.back()
+    }
+
+    /// Keeps `snapshot` if it's newer than any so far. Datagrams can arrive out of order.
+    pub fn push(&mut self, snapshot: Snapshot) {
+        match self.newest() {
+            Some(newest) if newest.tick >= snapshot.tick => return,
+            None => self.render_tick = snapshot.tick as f64 - INTERPOLATION_DELAY,
+            _ => {}
+        }
+        self.snapshots.push_back(snapshot);
+    }
+
+    /// Moves the drawn point on by `ticks`, staying within the snapshots received.
+    pub fn advance(&mut self, ticks: f64) {
+        let Some(newest) = self.newest().map(|newest| newest.tick as f64) else {
+            return;
+        };
+        self.render_tick = (self.render_tick + ticks).min(newest);
+        if self.render_tick < newest - MAX_LAG {
+            self.render_tick = newest - INTERPOLATION_DELAY;
+        }
+
+        // Only the last snapshot at or before the drawn point is still needed
+        while self.snapshots.len() > 1 && self.snapshots[1].tick as f64 <= self.render_tick {
+            self.snapshots.pop_front();
+        }
+    }
+
+    /// Every entity as it should be drawn now, between the snapshots either side of the drawn
+    /// point. Entities are shown from the snapshot they first appear in until the one they're
+    /// gone from.
+    pub fn sample(&self) -> Vec<EntityState> {
+        let (Some(from), Some(to)) = (self.snapshots.front(), self.snapshots.get(1)) else {
+            return self
+                .newest()
+                .map_or(Vec::new(), |newest| newest.entities.clone());
+        };
+        let progress = ((self.render_tick - from.tick as f64) / (to.tick - from.tick) as f64)
+            .clamp(0.0, 1.0) as f32;
+
+        to.entities
+            .iter()
+            .map(
+                |target| match from.entities.iter().find(|entity| entity.id == target.id) {
+                    Some(start) => interpolate(start, target, progress),
+                    None => *target,
+                },
+            )
+            .collect()
+    }
+}
+
+fn interpolate(from: &EntityState, to: &EntityState, progress: f32) -> EntityState {
+    if from.translation.distance(to.translation) > MAX_STEP {
+        return *to;
+    }
+
+    // Turn the short way round
+    let turn = (to.rotation - from.rotation + PI).rem_euclid(TAU) - PI;
+    EntityState {
+        translation: from.translation.lerp(to.translation, progress),
+        rotation: from.rotation + turn * progress,
+        ..*to
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::components::{AsteroidSize, MeteorSprite};
+    use crate::net::protocol::EntityKind;
+
+    fn snapshot(tick: u32, x: f32) -> Snapshot {
+        Snapshot {
+            tick,
+            entities: vec![EntityState {
+                id: 1,
+                kind: EntityKind::Asteroid(AsteroidSize::Large, MeteorSprite(1)),
+                translation: Vec2::new(x, 0.0),
+                rotation: 0.0,
+                scale: 1.0,
+            }],
+            ..default()
+        }
+    }
+
+    #[test]
+    fn draws_between_snapshots_and_settles_on_the_newest() {
+        let mut buffer = SnapshotBuffer::default();
+        buffer.push(snapshot(10, 0.0));
+        buffer.push(snapshot(16, 60.0));
+        // Out of date by the time it arrives
+        buffer.push(snapshot(12, 1000.0));
+
+        // Drawing starts a delay behind the first snapshot, so this is halfway to the second
+        buffer.advance(INTERPOLATION_DELAY + 3.0);
+        assert_eq!(buffer.sample()[0].translation.x, 30.0);
+
+        buffer.advance(100.0);
+        assert_eq!(buffer.sample()[0].translation.x, 60.0);
+    }
+
+    #[test]
+    fn wrapping_round_the_edge_is_a_jump() {
+        let from = snapshot(0, -390.0).entities[0];
+        let to = snapshot(1, 390.0).entities[0];
+
+        assert_eq!(interpolate(&from, &to, 0.5).translation.x, 390.0);
+    }
+}
@@ -0,0 +1,23 @@
+use std::io;
+
+pub mod client;
+pub mod interpolation;
+pub mod protocol;
+pub mod server;
+
+#[cfg(test)]
+mod tests;
+
+/// The largest datagram UDP can carry over IPv4.
+const MAX_DATAGRAM: usize = 65_507;
+/// Failed receives to skip past in a frame before leaving the rest for the next one.
+const MAX_RESETS: usize = 8;
+
+/// Whether a receive failed only because something sent earlier wasn't delivered, which some
+/// platforms report on the next receive.
+fn is_reset(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::ConnectionReset | io::ErrorKind::ConnectionRefused
+    )
+}
@@ -0,0 +1,412 @@
+use std::fmt;
+
+use bevy::prelude::Vec2;
+
+use crate::game::components::{AsteroidSize, MeteorSprite, PlayerId, PowerUpKind, SaucerSize};
+use crate::replay::file::{Reader, ReplayError, TickRecord};
+
+const MAGIC: &[u8; 4] = b"ASTN";
+/// Bumped whenever a message changes shape. Peers only talk to the same version.
+pub const PROTOCOL_VERSION: u16 = 2;
+
+/// Inputs a client repeats in every message, so a lost datagram doesn't lose a press.
+pub const INPUT_REDUNDANCY: usize = 4;
+
+/// A datagram from a client to the server.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClientMessage {
+    /// Asks for a place in the game. Repeated until the server answers.
+    Join,
+    /// The client's most recent ticks of input, oldest first. `sequence` numbers the last one.
+    Input {
+        sequence: u32,
+        records: Vec<TickRecord>,
+    },
+    Leave,
+}
+
+/// A datagram from the server to a client.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerMessage {
+    Welcome {
+        player: PlayerId,
+        players: u8,
+        tick_rate: f32,
+    },
+    Rejected(RejectReason),
+    Snapshot(Snapshot),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectReason {
+    /// Every player's place is taken.
+    Full,
+    /// The client speaks another version of the protocol.
+    Version,
+}
+
+impl fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RejectReason::Full => write!(f, "the game is full"),
+            RejectReason::Version => write!(f, "the server runs another version of the game"),
+        }
+    }
+}
+
+/// Everything a client draws, as of the end of one server tick.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Snapshot {
+    pub tick: u32,
+    /// Set once the game is over. The server repeats this last snapshot for a moment, and then
+    /// goes quiet.
+    pub finished: bool,
+    pub players: Vec<PlayerState>,
+    pub entities: Vec<EntityState>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlayerState {
+    pub lives: u8,
+    pub score: u32,
+}
+
+/// One entity in a [`Snapshot`], known to clients by the server's id for it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntityState {
+    pub id: u64,
+    pub kind: EntityKind,
+    pub translation: Vec2,
+    /// Radians counter-clockwise.
+    pub rotation: f32,
+    /// The same on both axes.
+    pub scale: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityKind {
+    Ship(PlayerId),
+    Asteroid(AsteroidSize, MeteorSprite),
+    Laser(PlayerId),
+    Saucer(SaucerSize),
+    SaucerShot,
+    PowerUp(PowerUpKind),
+}
+
+#[derive(Debug)]
+pub enum ProtocolError {
+    /// Not a message of this game at all.
+    NotAMessage,
+    UnsupportedVersion(u16),
+    Truncated,
+    Malformed,
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProtocolError::NotAMessage => write!(f, "not a game message"),
+            ProtocolError::UnsupportedVersion(version) => {
+                write!(f, "unsupported protocol version {}", version)
+            }
+            ProtocolError::Truncated => write!(f, "message is truncated"),
+            ProtocolError::Malformed => write!(f, "message is malformed"),
+        }
+    }
+}
+
+impl From<ReplayError> for ProtocolError {
+    fn from(_: ReplayError) -> ProtocolError {
+        // Reading past the end is the only way the shared reader fails
+        ProtocolError::Truncated
+    }
+}
+
+impl ClientMessage {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = header();
+        match self {
+            ClientMessage::Join => bytes.push(0),
+            ClientMessage::Input { sequence, records } => {
+                bytes.push(1);
+                bytes.extend_from_slice(&sequence.to_le_bytes());
+                bytes.push(records.len() as u8);
+                for record in records {
+                    record.write(&mut bytes);
+                }
+            }
+            ClientMessage::Leave => bytes.push(2),
+        }
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<ClientMessage, ProtocolError> {
+        let mut reader = read_header(bytes)?;
+        let [tag] = reader.take()?;
+        match tag {
+            0 => Ok(ClientMessage::Join),
+            1 => {
+                let sequence = u32::from_le_bytes(reader.take()?);
+                let [count] = reader.take()?;
+                let records = (0..count)
+                    .map(|_| reader.take_record())
+                    .collect::<Result<_, _>>()?;
+                Ok(ClientMessage::Input { sequence, records })
+            }
+            2 => Ok(ClientMessage::Leave),
+            _ => Err(ProtocolError::Malformed),
+        }
+    }
+}
+
+impl ServerMessage {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = header();
+        match self {
+            ServerMessage::Welcome {
+                player,
+                players,
+                tick_rate,
+            } => {
+                bytes.push(0);
+                bytes.push(player.0 as u8);
+                bytes.push(*players);
+                bytes.extend_from_slice(&tick_rate.to_le_bytes());
+            }
+            ServerMessage::Rejected(reason) => {
+                bytes.push(1);
+                bytes.push(match reason {
+                    RejectReason::Full => 0,
+                    RejectReason::Version => 1,
+                });
+            }
+            ServerMessage::Snapshot(snapshot) => {
+                bytes.push(2);
+                snapshot.write(&mut bytes);
+            }
+        }
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<ServerMessage, ProtocolError> {
+        let mut reader = read_header(bytes)?;
+        let [tag] = reader.take()?;
+        match tag {
+            0 => {
+                let [player, players] = reader.take()?;
+                Ok(ServerMessage::Welcome {
+                    player: PlayerId(player as usize),
+                    players,
+                    tick_rate: f32::from_le_bytes(reader.take()?),
+                })
+            }
+            1 => match reader.take()? {
+                [0] => Ok(ServerMessage::Rejected(RejectReason::Full)),
+                [1] => Ok(ServerMessage::Rejected(RejectReason::Version)),
+                _ => Err(ProtocolError::Malformed),
+            },
+            2 => Ok(ServerMessage::Snapshot(Snapshot::read(&mut reader)?)),
+            _ => Err(ProtocolError::Malformed),
+        }
+    }
+}
+
+impl Snapshot {
+    fn write(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.tick.to_le_bytes());
+        bytes.push(self.finished as u8);
+        bytes.push(self.players.len() as u8);
+        for player in &self.players {
+            bytes.push(player.lives);
+            bytes.extend_from_slice(&player.score.to_le_bytes());
+        }
+        bytes.extend_from_slice(&(self.entities.len() as u16).to_le_bytes());
+        for entity in &self.entities {
+            bytes.extend_from_slice(&entity.id.to_le_bytes());
+            bytes.extend_from_slice(&entity.kind.to_bytes());
+            bytes.extend_from_slice(&entity.translation.x.to_le_bytes());
+            bytes.extend_from_slice(&entity.translation.y.to_le_bytes());
+            bytes.extend_from_slice(&entity.rotation.to_le_bytes());
+            bytes.extend_from_slice(&entity.scale.to_le_bytes());
+        }
+    }
+
+    fn read(reader: &mut Reader) -> Result<Snapshot, ProtocolError> {
+        let tick = u32::from_le_bytes(reader.take()?);
+        let [finished, player_count] = reader.take()?;
+
+        let mut players = Vec::with_capacity(player_count as usize);
+        for _ in 0..player_count {
+            let [lives] = reader.take()?;
+            let score = u32::from_le_bytes(reader.take()?);
+            players.push(PlayerState { lives, score });
+        }
+
+        let entity_count = u16::from_le_bytes(reader.take()?);
+        let mut entities = Vec::with_capacity(entity_count as usize);
+        for _ in 0..entity_count {
+            let id = u64::from_le_bytes(reader.take()?);
+            let kind = EntityKind::from_bytes(reader.take()?)?;
+            let x = f32::from_le_bytes(reader.take()?);
+            let y = f32::from_le_bytes(reader.take()?);
+            let rotation = f32::from_le_bytes(reader.take()?);
+            let scale = f32::from_le_bytes(reader.take()?);
+            entities.push(EntityState {
+                id,
+                kind,
+                translation: Vec2::new(x, y),
+                rotation,
+                scale,
+            });
+        }
+
+        Ok(Snapshot {
+            tick,
+            finished: finished != 0,
+            players,
+            entities,
+        })
+    }
+}
+
+impl EntityKind {
+    fn to_bytes(self) -> [u8; 2] {
+        match self {
+            EntityKind::Ship(player) => [0, player.0 as u8],
+            // The size takes the low two bits, the meteor sprite the rest
+            EntityKind::Asteroid(size, MeteorSprite(sprite)) => [
+                1,
+                match size {
+                    AsteroidSize::Large => 0,
+                    AsteroidSize::Medium => 1,
+                    AsteroidSize::Small => 2,
+                } | sprite << 2,
+            ],
+            EntityKind::Laser(owner) => [2, owner.0 as u8],
+            EntityKind::Saucer(size) => [
+                3,
+                match size {
+                    SaucerSize::Large => 0,
+                    SaucerSize::Small => 1,
+                },
+            ],
+            EntityKind::SaucerShot => [4, 0],
+            EntityKind::PowerUp(kind) => [
+                5,
+                PowerUpKind::ALL
+                    .iter()
+                    .position(|other| *other == kind)
+                    .unwrap() as u8,
+            ],
+        }
+    }
+
+    fn from_bytes([tag, value]: [u8; 2]) -> Result<EntityKind, ProtocolError> {
+        let kind = match (tag, value) {
+            (0, player) => EntityKind::Ship(PlayerId(player as usize)),
+            (1, value) => {
+                let size = match value & 0b11 {
+                    0 => AsteroidSize::Large,
+                    1 => AsteroidSize::Medium,
+                    2 => AsteroidSize::Small,
+                    _ => return Err(ProtocolError::Malformed),
+                };
+                EntityKind::Asteroid(size, MeteorSprite(value >> 2))
+            }
+            (2, owner) => EntityKind::Laser(PlayerId(owner as usize)),
+            (3, 0) => EntityKind::Saucer(SaucerSize::Large),
+            (3, 1) => EntityKind::Saucer(SaucerSize::Small),
+            (4, _) => EntityKind::SaucerShot,
+            (5, kind) => match PowerUpKind::ALL.get(kind as usize) {
+                Some(kind) => EntityKind::PowerUp(*kind),
+                None => return Err(ProtocolError::Malformed),
+            },
+            _ => return Err(ProtocolError::Malformed),
+        };
+        Ok(kind)
+    }
+}
+
+fn header() -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(64);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&PROTOCOL_VERSION.to_le_bytes());
+    bytes
+}
+
+fn read_header(bytes: &[u8]) -> Result<Reader<'_>, ProtocolError> {
+    let mut reader = Reader::new(bytes);
+    if reader.take::<4>().map_err(|_| ProtocolError::NotAMessage)? != *MAGIC {
+        return Err(ProtocolError::NotAMessage);
+    }
+    let version = u16::from_le_bytes(reader.take()?);
+    if version != PROTOCOL_VERSION {
+        return Err(ProtocolError::UnsupportedVersion(version));
+    }
+    Ok(reader)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_round_trip() {
+        let input = ClientMessage::Input {
+            sequence: 77,
+            records: vec![TickRecord::default(); INPUT_REDUNDANCY],
+        };
+        assert_eq!(ClientMessage::decode(&input.encode()).unwrap(), input);
+
+        let snapshot = ServerMessage::Snapshot(Snapshot {
+            tick: 1234,
+            finished: true,
+            players: vec![
+                PlayerState {
+                    lives: 2,
+                    score: 900,
+                },
+                PlayerState::default(),
+            ],
+            entities: vec![
+                EntityState {
+                    id: 42,
+                    kind: EntityKind::PowerUp(PowerUpKind::Shield),
+                    translation: Vec2::new(-10.5, 300.0),
+                    rotation: 1.5,
+                    scale: 1.0,
+                },
+                EntityState {
+                    id: 43,
+                    kind: EntityKind::Laser(PlayerId(1)),
+                    translation: Vec2::ZERO,
+                    rotation: 0.0,
+                    scale: 0.3,
+                },
+                EntityState {
+                    id: 44,
+                    kind: EntityKind::Asteroid(AsteroidSize::Small, MeteorSprite(4)),
+                    translation: Vec2::new(5.0, 5.0),
+                    rotation: -2.0,
+                    scale: 0.5,
+                },
+            ],
+        });
+        assert_eq!(ServerMessage::decode(&snapshot.encode()).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn other_versions_are_refused() {
+        let mut bytes = ClientMessage::Join.encode();
+        bytes[4..6].copy_from_slice(&(PROTOCOL_VERSION + 1).to_le_bytes());
+
+        assert!(matches!(
+            ClientMessage::decode(&bytes),
+            Err(ProtocolError::UnsupportedVersion(version)) if version == PROTOCOL_VERSION + 1
+        ));
+        assert!(matches!(
+            ClientMessage::decode(b"GET / HTTP/1.1"),
+            Err(ProtocolError::NotAMessage)
+        ));
+    }
+}
@@ -0,0 +1,532 @@
+use std::fmt;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::utils::Instant;
+
+use crate::action::plugin::SampleActions;
+use crate::action::state::{TickActions, TickInput};
+use crate::game::components::*;
+use crate::game::event::PlayerHitEvent;
+use crate::game::plugin::GameSet;
+use crate::global::component::Scoreboard;
+use crate::global::state::AppState;
+use crate::replay::file::TickRecord;
+
+use super::protocol::*;
+use super::{is_reset, MAX_DATAGRAM, MAX_RESETS};
+
+/// How long a client can go quiet before its ship stops doing what it was last told.
+const INPUT_TIMEOUT: Duration = Duration::from_millis(500);
+/// How long a client can go quiet before its place is freed, as it's most likely gone.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long the final snapshot is repeated for after game over, in case it went missing.
+const FINISH_GRACE: f32 = 2.0;
+
+/// Runs the authoritative game for remote players: their input goes in, snapshots of
+/// everything on the playfield come out.
+///
+/// Active once a [`NetServer`] is inserted. The game starts as soon as every place is taken,
+/// and shortly after it's over the places are free for the next one.
+pub struct ServerPlugin;
+
+impl Plugin for ServerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<MatchEvent>()
+            .add_systems(
+                (
+                    receive_client_messages,
+                    drop_silent_clients,
+                    start_when_full,
+                    abandon_when_empty,
+                )
+                    .chain()
+                    .distributive_run_if(resource_exists::<NetServer>())
+                    .in_base_set(CoreSet::PreUpdate),
+            )
+            .add_system(
+                feed_remote_input
+                    .run_if(resource_exists::<NetServer>())
+                    .run_if(in_state(AppState::InGame))
+                    .after(SampleActions)
+                    .before(GameSet::Input)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                broadcast_snapshot
+                    .run_if(resource_exists::<NetServer>())
+                    .run_if(in_state(AppState::InGame))
+                    .after(GameSet::Updates)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                report_lives_lost
+                    .run_if(resource_exists::<NetServer>())
+                    .run_if(in_state(AppState::InGame))
+                    .after(GameSet::Updates)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                finish_session
+                    .run_if(resource_exists::<NetServer>())
+                    .in_schedule(OnEnter(AppState::GameOver)),
+            )
+            .add_system(
+                end_session
+                    .run_if(resource_exists::<NetServer>())
+                    .run_if(in_state(AppState::GameOver))
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            );
+    }
+}
+
+/// The server's socket and the players connected through it.
+#[derive(Resource)]
+pub struct NetServer {
+    socket: UdpSocket,
+    mode: GameMode,
+    /// One place per player, in player order.
+    clients: Vec<Option<RemoteClient>>,
+    tick: u32,
+    /// The most recent snapshot sent, encoded.
+    last_snapshot: Vec<u8>,
+    /// Counts down from game over to freeing the places.
+    finish_timer: Timer,
+}
+
+/// Something that happened in a game the server runs, for its log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchEvent {
+    Joined {
+        player: PlayerId,
+        address: SocketAddr,
+    },
+    Left {
+        player: PlayerId,
+    },
+    Started {
+        players: usize,
+        mode: GameMode,
+    },
+    LifeLost {
+        player: PlayerId,
+        lives: u8,
+    },
+    Finished {
+        scores: Vec<usize>,
+    },
+}
+
+/// One line of `key=value` pairs, starting with the kind of event.
+impl fmt::Display for MatchEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MatchEvent::Joined { player, address } => {
+                write!(
+                    f,
+                    "event=joined player={} address={}",
+                    player.0 + 1,
+                    address
+                )
+            }
+            MatchEvent::Left { player } => write!(f, "event=left player={}", player.0 + 1),
+            MatchEvent::Started { players, mode } => {
+                write!(f, "event=started players={} mode={:?}", players, mode)
+            }
+            MatchEvent::LifeLost { player, lives } => {
+                write!(f, "event=life_lost player={} lives={}", player.0 + 1, lives)
+            }
+            MatchEvent::Finished { scores } => {
+                let scores: Vec<String> = scores.iter().map(ToString::to_string).collect();
+                write!(f, "event=finished scores={}", scores.join(","))
+            }
+        }
+    }
+}
+
+struct RemoteClient {
+    address: SocketAddr,
+    /// The last input sequence number taken in.
+    sequence: Option<u32>,
+    /// Input received since the last tick, oldest first.
+    pending: Vec<TickRecord>,
+    /// What the player was last seen holding, for ticks no input arrives in time for.
+    held: TickActions,
+    /// When anything last arrived from the client.
+    last_heard: Instant,
+}
+
+impl NetServer {
+    /// Listens on `address` for the players of a `mode` game.
+    pub fn bind(address: impl ToSocketAddrs, mode: GameMode) -> io::Result<NetServer> {
+        let socket = UdpSocket::bind(address)?;
+        socket.set_nonblocking(true)?;
+        Ok(NetServer {
+            socket,
+            mode,
+            clients: (0..mode.players()).map(|_| None).collect(),
+            tick: 0,
+            last_snapshot: Vec::new(),
+            finish_timer: Timer::from_seconds(FINISH_GRACE, TimerMode::Once),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    fn is_full(&self) -> bool {
+        self.clients.iter().all(Option::is_some)
+    }
+
+    fn player_at(&self, address: SocketAddr) -> Option<PlayerId> {
+        self.clients
+            .iter()
+            .position(|client| {
+                client
+                    .as_ref()
+                    .is_some_and(|client| client.address == address)
+            })
+            .map(PlayerId)
+    }
+
+    fn send(&self, message: &ServerMessage, address: SocketAddr) {
+        self.send_bytes(&message.encode(), address);
+    }
+
+    fn send_bytes(&self, bytes: &[u8], address: SocketAddr) {
+        if let Err(error) = self.socket.send_to(bytes, address) {
+            warn!("Failed to send to {}: {}", address, error);
+        }
+    }
+
+    fn send_last_snapshot(&self) {
+        for client in self.clients.iter().flatten() {
+            self.send_bytes(&self.last_snapshot, client.address);
+        }
+    }
+
+    /// Gives the client at `address` a place, answering with which one. Returns the place if
+    /// it's a new one.
+    fn join(&mut self, address: SocketAddr, tick_rate: f32, now: Instant) -> Option<PlayerId> {
+        // A client keeps asking until it hears back, so it may already have a place
+        let joined = match self.player_at(address) {
+            Some(_) => None,
+            None => self.clients.iter().position(Option::is_none).map(|free| {
+                self.clients[free] = Some(RemoteClient {
+                    address,
+                    sequence: None,
+                    pending: Vec::new(),
+                    held: TickActions::default(),
+                    last_heard: now,
+                });
+                PlayerId(free)
+            }),
+        };
+
+        let reply = match self.player_at(address) {
+            Some(player) => ServerMessage::Welcome {
+                player,
+                players: self.clients.len() as u8,
+                tick_rate,
+            },
+            None => ServerMessage::Rejected(RejectReason::Full),
+        };
+        self.send(&reply, address);
+        joined
+    }
+
+    fn heard_from(&mut self, address: SocketAddr, now: Instant) {
+        if let Some(PlayerId(index)) = self.player_at(address) {
+            if let Some(client) = self.clients[index].as_mut() {
+                client.last_heard = now;
+            }
+        }
+    }
+
+    fn receive_input(&mut self, address: SocketAddr, sequence: u32, records: Vec<TickRecord>) {
+        let Some(PlayerId(index)) = self.player_at(address) else {
+            return;
+        };
+        let Some(client) = self.clients[index].as_mut() else {
+            return;
+        };
+
+        // Every message repeats the last few inputs, so only take the ones not seen yet
+        let first = sequence.wrapping_sub(records.len() as u32 - 1);
+        for (offset, record) in records.into_iter().enumerate() {
+            let record_sequence = first.wrapping_add(offset as u32);
+            if client.sequence.is_none_or(|seen| record_sequence > seen) {
+                client.pending.push(record);
+                client.sequence = Some(record_sequence);
+            }
+        }
+    }
+}
+
+impl RemoteClient {
+    /// Everything the player did since the last tick, as one tick's worth of actions: where
+    /// they ended up, with every press along the way.
+    fn take_actions(&mut self, now: Instant) -> TickActions {
+        // A client that has gone quiet may never say it let go, so let go for it
+        if now.duration_since(self.last_heard) > INPUT_TIMEOUT {
+            self.held = TickActions::default();
+        }
+        let mut actions = TickActions {
+            just_pressed: default(),
+            ..self.held.clone()
+        };
+        for record in self.pending.drain(..) {
+            let tick = record.to_actions();
+            actions.just_pressed.extend(tick.just_pressed);
+            actions.pressed = tick.pressed;
+            actions.rotation = tick.rotation;
+            actions.aim = tick.aim;
+        }
+        self.held = actions.clone();
+        actions
+    }
+}
+
+/// The time of this frame, which the clock only stands in for before the first update.
+fn now(time: &Time) -> Instant {
+    time.last_update().unwrap_or_else(Instant::now)
+}
+
+pub fn receive_client_messages(
+    mut server: ResMut<NetServer>,
+    time: Res<Time>,
+    fixed_time: Res<FixedTime>,
+    mut event_writer: EventWriter<MatchEvent>,
+) {
+    let tick_rate = 1.0 / fixed_time.period.as_secs_f32();
+    let now = now(&time);
+    let mut buffer = [0; MAX_DATAGRAM];
+    let mut resets = 0;
+
+    loop {
+        let (length, address) = match server.socket.recv_from(&mut buffer) {
+            Ok(received) => received,
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => break,
+            // A client that went away can make the next receive fail on some platforms
+            Err(error) if is_reset(&error) && resets < MAX_RESETS => {
+                resets += 1;
+                continue;
+            }
+            Err(error) => {
+                warn!("Failed to receive from clients: {}", error);
+                break;
+            }
+        };
+
+        let message = ClientMessage::decode(&buffer[..length]);
+        if message.is_ok() {
+            server.heard_from(address, now);
+        }
+
+        match message {
+            Ok(ClientMessage::Join) => {
+                if let Some(player) = server.join(address, tick_rate, now) {
+                    event_writer.send(MatchEvent::Joined { player, address });
+                }
+            }
+            Ok(ClientMessage::Input { sequence, records }) if !records.is_empty() => {
+                server.receive_input(address, sequence, records)
+            }
+            Ok(ClientMessage::Input { .. }) => {}
+            Ok(ClientMessage::Leave) => {
+                if let Some(player) = server.player_at(address) {
+                    server.clients[player.0] = None;
+                    event_writer.send(MatchEvent::Left { player });
+                }
+            }
+            Err(ProtocolError::UnsupportedVersion(_)) => {
+                server.send(&ServerMessage::Rejected(RejectReason::Version), address)
+            }
+            Err(error) => debug!("Ignoring datagram from {}: {}", address, error),
+        }
+    }
+}
+
+/// Frees the place of any client that has gone quiet for too long without saying it's leaving.
+pub fn drop_silent_clients(
+    mut server: ResMut<NetServer>,
+    time: Res<Time>,
+    mut event_writer: EventWriter<MatchEvent>,
+) {
+    let now = now(&time);
+    for (index, place) in server.clients.iter_mut().enumerate() {
+        if place
+            .as_ref()
+            .is_some_and(|client| now.duration_since(client.last_heard) > CLIENT_TIMEOUT)
+        {
+            *place = None;
+            event_writer.send(MatchEvent::Left {
+                player: PlayerId(index),
+            });
+        }
+    }
+}
+
+pub fn start_when_full(
+    mut server: ResMut<NetServer>,
+    state: Res<State<AppState>>,
+    mut mode: ResMut<GameMode>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut event_writer: EventWriter<MatchEvent>,
+) {
+    if state.0 == AppState::MainMenu && server.is_full() {
+        // Whatever was pressed while waiting for everyone isn't part of the game
+        for client in server.clients.iter_mut().flatten() {
+            client.pending.clear();
+            client.held = TickActions::default();
+        }
+        *mode = server.mode;
+        next_state.set(AppState::InGame);
+        event_writer.send(MatchEvent::Started {
+            players: server.clients.len(),
+            mode: server.mode,
+        });
+    }
+}
+
+/// Ends the game early once everyone playing it has left.
+pub fn abandon_when_empty(
+    server: Res<NetServer>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if state.0 == AppState::InGame && server.clients.iter().all(Option::is_none) {
+        next_state.set(AppState::GameOver);
+    }
+}
+
+pub fn report_lives_lost(
+    player_query: Query<&Player>,
+    mut player_hit_reader: EventReader<PlayerHitEvent>,
+    mut event_writer: EventWriter<MatchEvent>,
+) {
+    for hit in player_hit_reader.iter() {
+        if let Ok(player) = player_query.get(hit.player) {
+            event_writer.send(MatchEvent::LifeLost {
+                player: player.id,
+                lives: player.lives,
+            });
+        }
+    }
+}
+
+pub fn finish_session(
+    mut server: ResMut<NetServer>,
+    scoreboard: Res<Scoreboard>,
+    mut event_writer: EventWriter<MatchEvent>,
+) {
+    event_writer.send(MatchEvent::Finished {
+        scores: scoreboard.scores.clone(),
+    });
+    server.finish_timer.reset();
+}
+
+/// Repeats the final snapshot for a moment, so every client hears the game is over, then frees
+/// every place, ready for the next game.
+pub fn end_session(
+    mut server: ResMut<NetServer>,
+    fixed_time: Res<FixedTime>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    server.send_last_snapshot();
+    if server.finish_timer.tick(fixed_time.period).just_finished() {
+        for client in &mut server.clients {
+            *client = None;
+        }
+        next_state.set(AppState::MainMenu);
+    }
+}
+
+/// Hands each remote player's input to the simulation, in place of local devices.
+pub fn feed_remote_input(
+    mut server: ResMut<NetServer>,
+    time: Res<Time>,
+    mut input: ResMut<TickInput>,
+) {
+    let now = now(&time);
+    input.players = server
+        .clients
+        .iter_mut()
+        .map(|client| {
+            client
+                .as_mut()
+                .map(|client| client.take_actions(now))
+                .unwrap_or_default()
+        })
+        .collect();
+}
+
+type Replicated<'a> = (
+    Entity,
+    &'a Transform,
+    AnyOf<(
+        &'a Player,
+        (&'a Asteroid, &'a MeteorSprite),
+        &'a Laser,
+        &'a Saucer,
+        &'a SaucerShot,
+        &'a PowerUp,
+    )>,
+);
+
+pub fn broadcast_snapshot(
+    mut server: ResMut<NetServer>,
+    game_state: Res<GameState>,
+    scoreboard: Res<Scoreboard>,
+    entity_query: Query<Replicated, Without<Destroyed>>,
+    player_query: Query<&Player>,
+) {
+    server.tick += 1;
+
+    let entities = entity_query
+        .iter()
+        .map(|(entity, transform, kinds)| {
+            let kind = match kinds {
+                (Some(player), ..) => EntityKind::Ship(player.id),
+                (_, Some((asteroid, sprite)), ..) => EntityKind::Asteroid(asteroid.size, *sprite),
+                (_, _, Some(laser), ..) => EntityKind::Laser(laser.owner),
+                (_, _, _, Some(saucer), ..) => EntityKind::Saucer(saucer.size),
+                (_, _, _, _, Some(_), _) => EntityKind::SaucerShot,
+                (_, _, _, _, _, Some(power_up)) => EntityKind::PowerUp(power_up.kind),
+                _ => unreachable!("AnyOf matches at least one"),
+            };
+            EntityState {
+                id: entity.to_bits(),
+                kind,
+                translation: transform.translation.truncate(),
+                rotation: transform.rotation.to_euler(EulerRot::XYZ).2,
+                scale: transform.scale.x,
+            }
+        })
+        .collect();
+
+    let players = scoreboard
+        .scores
+        .iter()
+        .enumerate()
+        .map(|(index, score)| PlayerState {
+            lives: player_query
+                .iter()
+                .find(|player| player.id.0 == index)
+                .map_or(0, |player| player.lives),
+            score: *score as u32,
+        })
+        .collect();
+
+    server.last_snapshot = ServerMessage::Snapshot(Snapshot {
+        tick: server.tick,
+        finished: game_state.finished,
+        players,
+        entities,
+    })
+    .encode();
+    server.send_last_snapshot();
+}
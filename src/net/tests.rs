@@ -0,0 +1,361 @@
+use std::net::{SocketAddr, UdpSocket};
+use std::thread;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::action::state::{Action, TickActions, TickInput};
+use crate::game::components::*;
+use crate::global::state::AppState;
+use crate::replay::file::TickRecord;
+use crate::testing::Harness;
+
+use super::client::{ClientPlugin, ConnectionStatus, NetClient, RemoteEntity};
+use super::protocol::{ClientMessage, EntityKind, ServerMessage, Snapshot};
+use super::server::{NetServer, ServerPlugin};
+
+const SEED: u64 = 5;
+/// Frames to wait for something to come over the loopback before giving up.
+const PATIENCE: u32 = 500;
+
+fn server() -> Harness {
+    Harness::build_headless(SEED, |app| {
+        let server = NetServer::bind("127.0.0.1:0", GameMode::Coop).unwrap();
+        app.add_plugin(ServerPlugin).insert_resource(server);
+    })
+}
+
+fn client(server: SocketAddr) -> Harness {
+    Harness::build(SEED, |app| {
+        app.add_plugin(ClientPlugin)
+            .insert_resource(NetClient::connect(server).unwrap());
+    })
+}
+
+fn server_address(server: &Harness) -> SocketAddr {
+    server
+        .app
+        .world
+        .resource::<NetServer>()
+        .local_addr()
+        .unwrap()
+}
+
+/// A client that joins `server` and sends nothing else, for tests to listen in with.
+fn raw_client(server: SocketAddr) -> UdpSocket {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket.connect(server).unwrap();
+    socket.set_nonblocking(true).unwrap();
+    socket.send(&ClientMessage::Join.encode()).unwrap();
+    socket
+}
+
+/// Every snapshot that has arrived at `socket`.
+fn snapshots(socket: &UdpSocket) -> Vec<Snapshot> {
+    let mut bytes = [0; super::MAX_DATAGRAM];
+    let mut snapshots = Vec::new();
+    while let Ok(length) = socket.recv(&mut bytes) {
+        if let Ok(ServerMessage::Snapshot(snapshot)) = ServerMessage::decode(&bytes[..length]) {
+            snapshots.push(snapshot);
+        }
+    }
+    snapshots
+}
+
+/// Steps every app a frame, giving datagrams a moment to arrive.
+fn step_all(harnesses: &mut [&mut Harness]) {
+    for harness in harnesses.iter_mut() {
+        harness.step();
+    }
+    thread::sleep(Duration::from_millis(1));
+}
+
+fn status(client: &Harness) -> ConnectionStatus {
+    client.app.world.resource::<NetClient>().status.clone()
+}
+
+/// Where something is, and how big.
+fn placement(transform: &Transform) -> Vec3 {
+    transform.translation.truncate().extend(transform.scale.x)
+}
+
+/// Where the server has everything that's sent to clients.
+fn server_entities(server: &mut Harness) -> HashMap<u64, (EntityKind, Vec3)> {
+    let mut query = server.app.world.query_filtered::<(
+        Entity,
+        &Transform,
+        AnyOf<(
+            &Player,
+            (&Asteroid, &MeteorSprite),
+            &Laser,
+            &Saucer,
+            &SaucerShot,
+            &PowerUp,
+        )>,
+    ), Without<Destroyed>>();
+    query
+        .iter(&server.app.world)
+        .map(|(entity, transform, kinds)| {
+            let kind = match kinds {
+                (Some(player), ..) => EntityKind::Ship(player.id),
+                (_, Some((asteroid, sprite)), ..) => EntityKind::Asteroid(asteroid.size, *sprite),
+                (_, _, Some(laser), ..) => EntityKind::Laser(laser.owner),
+                (_, _, _, Some(saucer), ..) => EntityKind::Saucer(saucer.size),
+                (_, _, _, _, Some(_), _) => EntityKind::SaucerShot,
+                (_, _, _, _, _, Some(power_up)) => EntityKind::PowerUp(power_up.kind),
+                _ => unreachable!(),
+            };
+            (entity.to_bits(), (kind, placement(transform)))
+        })
+        .collect()
+}
+
+/// Where a client draws everything.
+fn client_entities(client: &mut Harness) -> HashMap<u64, (EntityKind, Vec3)> {
+    let mut query = client.app.world.query::<(&RemoteEntity, &Transform)>();
+    query
+        .iter(&client.app.world)
+        .map(|(remote, transform)| (remote.id, (remote.kind, placement(transform))))
+        .collect()
+}
+
+#[test]
+fn clients_on_the_loopback_play_one_game_and_see_the_same_playfield() {
+    let mut server = server();
+    let address = server_address(&server);
+    let mut first = client(address);
+    let mut second = client(address);
+    assert_eq!(first.state(), AppState::Online);
+
+    // The game starts once both have a place
+    for _ in 0..PATIENCE {
+        if server.state() == AppState::InGame {
+            break;
+        }
+        step_all(&mut [&mut server, &mut first, &mut second]);
+    }
+    assert_eq!(server.state(), AppState::InGame);
+    let mut places: Vec<_> = [status(&first), status(&second)]
+        .into_iter()
+        .map(|status| match status {
+            ConnectionStatus::Joined { player, players: 2 } => player,
+            status => panic!("not joined: {:?}", status),
+        })
+        .collect();
+    places.sort();
+    assert_eq!(places, vec![PlayerId(0), PlayerId(1)]);
+
+    // A press on one machine fires the right ship on the server
+    let shooter = match status(&second) {
+        ConnectionStatus::Joined { player, .. } => player,
+        _ => unreachable!(),
+    };
+    second.actions().press(Action::Fire);
+    step_all(&mut [&mut server, &mut first, &mut second]);
+    second.actions().release(Action::Fire);
+    let mut fired = false;
+    for _ in 0..PATIENCE {
+        step_all(&mut [&mut server, &mut first, &mut second]);
+        let mut laser_query = server.app.world.query::<&Laser>();
+        fired = laser_query
+            .iter(&server.app.world)
+            .any(|laser| laser.owner == shooter);
+        if fired {
+            break;
+        }
+    }
+    assert!(fired, "no laser from {:?}", shooter);
+
+    // With the server holding still, both clients catch up to exactly what it has
+    for _ in 0..PATIENCE {
+        step_all(&mut [&mut first, &mut second]);
+        let expected = server_entities(&mut server);
+        if client_entities(&mut first) == expected && client_entities(&mut second) == expected {
+            break;
+        }
+    }
+    let expected = server_entities(&mut server);
+    assert!(expected
+        .values()
+        .any(|(kind, _)| *kind == EntityKind::Laser(shooter)));
+    assert_eq!(client_entities(&mut first), expected);
+    assert_eq!(client_entities(&mut second), expected);
+}
+
+#[test]
+fn clients_that_go_quiet_stop_playing_and_lose_their_place() {
+    let mut server = server();
+    let address = server_address(&server);
+
+    // Two clients join, start thrusting, and then vanish without a word
+    let thrusting = TickActions {
+        pressed: [Action::Thrust].into_iter().collect(),
+        ..default()
+    };
+    let input = ClientMessage::Input {
+        sequence: 0,
+        records: vec![TickRecord::from_actions(&thrusting)],
+    };
+    let sockets: Vec<_> = (0..2)
+        .map(|_| UdpSocket::bind("127.0.0.1:0").unwrap())
+        .collect();
+    for socket in &sockets {
+        socket
+            .send_to(&ClientMessage::Join.encode(), address)
+            .unwrap();
+    }
+    thread::sleep(Duration::from_millis(10));
+    server.step();
+    for socket in &sockets {
+        socket.send_to(&input.encode(), address).unwrap();
+    }
+    drop(sockets);
+    thread::sleep(Duration::from_millis(10));
+    server.run_ticks(2);
+    assert_eq!(server.state(), AppState::InGame);
+    let input = server.app.world.resource::<TickInput>();
+    assert!(input.player(0).unwrap().pressed(Action::Thrust));
+
+    // Their ships let go soon after
+    server.run_ticks(60);
+    let input = server.app.world.resource::<TickInput>();
+    assert!(!input.player(0).unwrap().pressed(Action::Thrust));
+    assert!(!input.player(1).unwrap().pressed(Action::Thrust));
+
+    // And once they've been gone a while, so are their places, which ends the game
+    server.run_ticks(300);
+    assert_eq!(server.state(), AppState::GameOver);
+}
+
+#[test]
+fn input_sent_while_waiting_for_players_is_dropped_when_the_game_starts() {
+    let mut server = server();
+    let address = server_address(&server);
+
+    // The first player to join holds fire while waiting for the second
+    let early = raw_client(address);
+    thread::sleep(Duration::from_millis(10));
+    server.step();
+    let firing = TickActions {
+        pressed: [Action::Fire].into_iter().collect(),
+        just_pressed: [Action::Fire].into_iter().collect(),
+        ..default()
+    };
+    let input = ClientMessage::Input {
+        sequence: 0,
+        records: vec![TickRecord::from_actions(&firing)],
+    };
+    early.send(&input.encode()).unwrap();
+    thread::sleep(Duration::from_millis(10));
+    server.step();
+    assert_eq!(server.state(), AppState::MainMenu);
+
+    let _late = raw_client(address);
+    thread::sleep(Duration::from_millis(10));
+    server.run_ticks(2);
+    assert_eq!(server.state(), AppState::InGame);
+    let input = server.app.world.resource::<TickInput>();
+    assert!(!input.player(0).unwrap().pressed(Action::Fire));
+    assert_eq!(server.count::<Laser>(), 0);
+}
+
+#[test]
+fn the_end_of_the_game_is_repeated_before_the_places_are_freed() {
+    let mut server = server();
+    let sockets = [
+        raw_client(server_address(&server)),
+        raw_client(server_address(&server)),
+    ];
+    thread::sleep(Duration::from_millis(10));
+    server.run_ticks(2);
+    assert_eq!(server.state(), AppState::InGame);
+
+    // Every ship goes down at once
+    let mut player_query = server.app.world.query::<(&Transform, &mut Player)>();
+    let translations: Vec<Vec2> = player_query
+        .iter_mut(&mut server.app.world)
+        .map(|(transform, mut player)| {
+            player.lives = 1;
+            transform.translation.truncate()
+        })
+        .collect();
+    for translation in translations {
+        server.spawn_asteroid(AsteroidSize::Small, translation);
+    }
+    server.run_ticks(10);
+    assert_eq!(server.state(), AppState::GameOver);
+
+    thread::sleep(Duration::from_millis(10));
+    for socket in &sockets {
+        let finished = snapshots(socket)
+            .iter()
+            .filter(|snapshot| snapshot.finished)
+            .count();
+        assert!(finished > 1, "game over was only sent {} times", finished);
+    }
+
+    server.run_ticks(150);
+    assert_eq!(server.state(), AppState::MainMenu);
+}
+
+#[test]
+fn clients_notice_when_the_server_goes_quiet_mid_game() {
+    let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let mut client = client(server.local_addr().unwrap());
+    thread::sleep(Duration::from_millis(10));
+
+    let mut bytes = [0; super::MAX_DATAGRAM];
+    let (_, address) = server.recv_from(&mut bytes).unwrap();
+    for message in [
+        ServerMessage::Welcome {
+            player: PlayerId(0),
+            players: 1,
+            tick_rate: 60.0,
+        },
+        ServerMessage::Snapshot(Snapshot {
+            tick: 1,
+            players: vec![default()],
+            ..default()
+        }),
+    ] {
+        server.send_to(&message.encode(), address).unwrap();
+    }
+    thread::sleep(Duration::from_millis(10));
+    client.step();
+    assert!(matches!(status(&client), ConnectionStatus::Joined { .. }));
+
+    // And then nothing more
+    client.run_ticks(360);
+    assert_eq!(status(&client), ConnectionStatus::Lost);
+}
+
+#[test]
+fn leaving_goes_back_to_the_local_tick_rate() {
+    let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let mut client = client(server.local_addr().unwrap());
+    let local_period = client.app.world.resource::<FixedTime>().period;
+    thread::sleep(Duration::from_millis(10));
+
+    let mut bytes = [0; super::MAX_DATAGRAM];
+    let (_, address) = server.recv_from(&mut bytes).unwrap();
+    let welcome = ServerMessage::Welcome {
+        player: PlayerId(0),
+        players: 1,
+        tick_rate: 20.0,
+    };
+    server.send_to(&welcome.encode(), address).unwrap();
+    thread::sleep(Duration::from_millis(10));
+    client.step();
+    let online_period = client.app.world.resource::<FixedTime>().period;
+    assert_eq!(online_period.as_millis(), 50);
+
+    client.actions().press(Action::Pause);
+    client.step();
+    client.step();
+    assert_eq!(client.state(), AppState::MainMenu);
+    assert_eq!(
+        client.app.world.resource::<FixedTime>().period,
+        local_period
+    );
+}
@@ -0,0 +1,273 @@
+use std::f32::consts::TAU;
+use std::ops::Range;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+/// The most particles alive at once. Emitters stop adding particles while the cap is reached.
+pub const MAX_PARTICLES: usize = 600;
+
+/// A value that eases from `start` to `end` over a particle's life.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Curve {
+    pub start: f32,
+    pub end: f32,
+}
+impl Curve {
+    pub const fn new(start: f32, end: f32) -> Curve {
+        Curve { start, end }
+    }
+
+    /// The value at `progress`, from 0 at birth to 1 at death.
+    pub fn at(&self, progress: f32) -> f32 {
+        self.start + (self.end - self.start) * progress.clamp(0.0, 1.0)
+    }
+}
+
+/// How a kind of particle is born and how it changes as it ages.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParticleEffect {
+    /// Particles per burst, or per second for a continuous emitter.
+    pub count: f32,
+    /// Pixels per second.
+    pub speed: Range<f32>,
+    /// Seconds.
+    pub lifetime: Range<f32>,
+    /// Radians either side of the emitter's direction. Bursts with a spread of π go all ways.
+    pub spread: f32,
+    /// Pixels across, over the particle's life.
+    pub size: Curve,
+    /// Opacity over the particle's life.
+    pub alpha: Curve,
+    pub color: Color,
+}
+impl ParticleEffect {
+    /// Rock dust flying out of a destroyed asteroid or saucer `extent` pixels across.
+    pub fn explosion(extent: f32) -> ParticleEffect {
+        ParticleEffect {
+            count: (extent / 2.0).clamp(6.0, 40.0),
+            speed: extent..extent * 3.0,
+            lifetime: 0.4..0.9,
+            spread: TAU / 2.0,
+            size: Curve::new((extent / 8.0).max(2.0), 1.0),
+            alpha: Curve::new(1.0, 0.0),
+            color: Color::rgb(0.75, 0.7, 0.65),
+        }
+    }
+
+    /// Pieces of the ship scattered when it's hit.
+    pub fn ship_debris() -> ParticleEffect {
+        ParticleEffect {
+            count: 30.0,
+            speed: 40.0..160.0,
+            lifetime: 0.8..1.6,
+            spread: TAU / 2.0,
+            size: Curve::new(5.0, 2.0),
+            alpha: Curve::new(1.0, 0.0),
+            color: Color::rgb(0.9, 0.9, 1.0),
+        }
+    }
+
+    /// Sparks thrown back along a laser that hit something.
+    pub fn laser_sparks() -> ParticleEffect {
+        ParticleEffect {
+            count: 8.0,
+            speed: 60.0..180.0,
+            lifetime: 0.15..0.35,
+            spread: 0.8,
+            size: Curve::new(3.0, 1.0),
+            alpha: Curve::new(1.0, 0.0),
+            color: Color::rgb(1.0, 0.9, 0.4),
+        }
+    }
+
+    /// Exhaust streaming out of the back of the ship while it thrusts.
+    pub fn thrust() -> ParticleEffect {
+        ParticleEffect {
+            count: 60.0,
+            speed: 80.0..140.0,
+            lifetime: 0.2..0.4,
+            spread: 0.3,
+            size: Curve::new(4.0, 1.0),
+            alpha: Curve::new(0.9, 0.0),
+            color: Color::rgb(1.0, 0.6, 0.2),
+        }
+    }
+
+    /// A new particle heading off around `direction`, which may be zero for no preference.
+    pub fn particle(&self, random: &mut impl Rng, direction: Vec2) -> Particle {
+        let heading = if direction == Vec2::ZERO {
+            Vec2::Y
+        } else {
+            direction.normalize()
+        };
+        let angle = if self.spread > 0.0 {
+            random.gen_range(-self.spread..self.spread)
+        } else {
+            0.0
+        };
+
+        Particle {
+            velocity: Vec2::from_angle(angle).rotate(heading)
+                * random.gen_range(self.speed.clone()),
+            age: 0.0,
+            lifetime: random.gen_range(self.lifetime.clone()),
+            size: self.size,
+            alpha: self.alpha,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Emission {
+    /// Emits `count` particles once, then the emitter's entity is despawned.
+    Burst,
+    /// Emits `count` particles per second while active.
+    Continuous { active: bool },
+}
+
+/// Emits particles at its entity's position.
+#[derive(Component, Debug, Clone)]
+pub struct ParticleEmitter {
+    pub effect: ParticleEffect,
+    pub emission: Emission,
+    /// Which way particles head, relative to the entity's rotation.
+    pub direction: Vec2,
+    /// Part of a particle carried over from the last update, so low rates still emit.
+    carry: f32,
+}
+impl ParticleEmitter {
+    pub fn burst(effect: ParticleEffect, direction: Vec2) -> ParticleEmitter {
+        ParticleEmitter {
+            effect,
+            emission: Emission::Burst,
+            direction,
+            carry: 0.0,
+        }
+    }
+
+    pub fn continuous(effect: ParticleEffect, direction: Vec2) -> ParticleEmitter {
+        ParticleEmitter {
+            effect,
+            emission: Emission::Continuous { active: false },
+            direction,
+            carry: 0.0,
+        }
+    }
+
+    pub fn set_active(&mut self, active: bool) {
+        if let Emission::Continuous { active: current } = &mut self.emission {
+            *current = active;
+        }
+    }
+
+    /// How many particles to emit after `delta` seconds, given room for `available` more.
+    pub fn due(&mut self, delta: f32, available: usize) -> usize {
+        let wanted = match self.emission {
+            Emission::Burst => self.effect.count,
+            Emission::Continuous { active: false } => {
+                self.carry = 0.0;
+                return 0;
+            }
+            Emission::Continuous { active: true } => {
+                let wanted = self.carry + self.effect.count * delta;
+                self.carry = wanted.fract();
+                wanted
+            }
+        };
+        (wanted as usize).min(available)
+    }
+}
+
+/// One particle, moving in a straight line until it dies.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Particle {
+    /// Pixels per second.
+    pub velocity: Vec2,
+    /// Seconds since the particle was born.
+    pub age: f32,
+    pub lifetime: f32,
+    pub size: Curve,
+    pub alpha: Curve,
+}
+impl Particle {
+    /// Ages the particle by `delta` seconds, returning how far it moved. `None` once it has died.
+    pub fn advance(&mut self, delta: f32) -> Option<Vec2> {
+        self.age += delta;
+        (self.age < self.lifetime).then_some(self.velocity * delta)
+    }
+
+    /// How far through its life the particle is, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        (self.age / self.lifetime).min(1.0)
+    }
+
+    pub fn current_size(&self) -> f32 {
+        self.size.at(self.progress())
+    }
+
+    pub fn current_alpha(&self) -> f32 {
+        self.alpha.at(self.progress())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    #[test]
+    fn particles_fade_and_shrink_until_they_die() {
+        let mut particle =
+            ParticleEffect::laser_sparks().particle(&mut ChaCha8Rng::seed_from_u64(7), Vec2::X);
+        particle.lifetime = 1.0;
+
+        assert_eq!(particle.current_alpha(), 1.0);
+        assert_eq!(particle.current_size(), 3.0);
+
+        let moved = particle.advance(0.5).unwrap();
+        assert_eq!(moved, particle.velocity * 0.5);
+        assert_eq!(particle.current_alpha(), 0.5);
+        assert_eq!(particle.current_size(), 2.0);
+
+        assert!(particle.advance(0.5).is_none());
+        assert_eq!(particle.current_alpha(), 0.0);
+    }
+
+    #[test]
+    fn particles_head_off_within_the_spread() {
+        let mut random = ChaCha8Rng::seed_from_u64(7);
+        let effect = ParticleEffect::thrust();
+
+        for _ in 0..100 {
+            let particle = effect.particle(&mut random, Vec2::NEG_Y);
+            let speed = particle.velocity.length();
+            assert!(speed >= effect.speed.start - 1e-3 && speed <= effect.speed.end + 1e-3);
+            assert!(particle.velocity.angle_between(Vec2::NEG_Y).abs() <= effect.spread + 1e-4);
+        }
+    }
+
+    #[test]
+    fn continuous_emitters_carry_over_part_particles() {
+        let mut emitter = ParticleEmitter::continuous(ParticleEffect::thrust(), Vec2::NEG_Y);
+        assert_eq!(emitter.due(1.0, MAX_PARTICLES), 0);
+
+        emitter.set_active(true);
+        // 60 a second, so a 1/120 second frame only owes half a particle
+        let emitted: usize = (0..120)
+            .map(|_| emitter.due(1.0 / 120.0, MAX_PARTICLES))
+            .sum();
+        assert!((59..=60).contains(&emitted));
+    }
+
+    #[test]
+    fn emitters_respect_the_particle_cap() {
+        let mut burst = ParticleEmitter::burst(ParticleEffect::explosion(48.0), Vec2::ZERO);
+
+        assert_eq!(burst.due(0.0, MAX_PARTICLES), 24);
+        assert_eq!(burst.due(0.0, 5), 5);
+        assert_eq!(burst.due(0.0, 0), 0);
+    }
+}
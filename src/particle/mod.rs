@@ -0,0 +1,6 @@
+pub mod effect;
+pub mod plugin;
+
+mod system;
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::game::plugin::GameSet;
+use crate::global::state::AppState;
+
+use super::system::*;
+
+/// Draws explosions, exhaust and sparks as short-lived sprites.
+///
+/// Particles are only for show: they are simulated every frame rather than every tick, and
+/// draw from their own [`ParticleRng`] so they never change how a game plays out.
+pub struct ParticlePlugin;
+
+/// Randomness for particles, kept apart from the game's seeded RNG so replays stay exact.
+#[derive(Resource)]
+pub struct ParticleRng(pub ChaCha8Rng);
+
+impl Default for ParticleRng {
+    fn default() -> ParticleRng {
+        ParticleRng(ChaCha8Rng::from_entropy())
+    }
+}
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ParticleRng>()
+            .add_system(
+                spawn_impact_bursts
+                    .in_set(GameSet::Updates)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_systems(
+                (
+                    add_thrust_emitter,
+                    update_thrust_emitter,
+                    emit_particles,
+                    update_particles,
+                )
+                    .chain()
+                    .in_set(OnUpdate(AppState::InGame)),
+            );
+    }
+}
@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+
+use crate::config::game_config::GameConfig;
+use crate::game::components::{Asteroid, Destroyed, Laser, Player, Saucer};
+use crate::game::event::PlayerHitEvent;
+use crate::global::component::GameObject;
+
+use super::effect::{Emission, Particle, ParticleEffect, ParticleEmitter, MAX_PARTICLES};
+use super::plugin::ParticleRng;
+
+/// Gives every new ship an exhaust, which only runs while it thrusts.
+pub fn add_thrust_emitter(mut commands: Commands, player_query: Query<Entity, Added<Player>>) {
+    for entity in player_query.iter() {
+        commands.entity(entity).insert(ParticleEmitter::continuous(
+            ParticleEffect::thrust(),
+            Vec2::NEG_Y,
+        ));
+    }
+}
+
+pub fn update_thrust_emitter(mut player_query: Query<(&Player, &mut ParticleEmitter)>) {
+    for (player, mut emitter) in player_query.iter_mut() {
+        emitter.set_active(player.thrusting);
+    }
+}
+
+/// Leaves a burst of particles wherever something was destroyed or hit this tick.
+pub fn spawn_impact_bursts(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    asteroid_query: Query<(&Transform, &Asteroid), Added<Destroyed>>,
+    saucer_query: Query<(&Transform, &Saucer), Added<Destroyed>>,
+    laser_query: Query<(&Transform, &Laser), Added<Destroyed>>,
+    player_query: Query<&Transform, With<Player>>,
+    mut player_hit_reader: EventReader<PlayerHitEvent>,
+) {
+    let mut burst = |translation: Vec3, effect: ParticleEffect, direction: Vec2| {
+        commands.spawn((
+            SpatialBundle::from_transform(Transform::from_translation(translation)),
+            ParticleEmitter::burst(effect, direction),
+            GameObject,
+        ));
+    };
+
+    for (transform, asteroid) in asteroid_query.iter() {
+        let extent = config.asteroid.extent(asteroid.size);
+        burst(
+            transform.translation,
+            ParticleEffect::explosion(extent),
+            Vec2::ZERO,
+        );
+    }
+    for (transform, saucer) in saucer_query.iter() {
+        let extent = saucer.size.extent().max_element();
+        burst(
+            transform.translation,
+            ParticleEffect::explosion(extent),
+            Vec2::ZERO,
+        );
+    }
+    for (transform, laser) in laser_query.iter() {
+        burst(
+            transform.translation,
+            ParticleEffect::laser_sparks(),
+            -laser.velocity,
+        );
+    }
+    for hit in player_hit_reader.iter() {
+        if let Ok(transform) = player_query.get(hit.player) {
+            burst(
+                transform.translation,
+                ParticleEffect::ship_debris(),
+                Vec2::ZERO,
+            );
+        }
+    }
+}
+
+pub fn emit_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut particle_rng: ResMut<ParticleRng>,
+    mut emitter_query: Query<(Entity, &Transform, &mut ParticleEmitter)>,
+    particle_query: Query<(), With<Particle>>,
+) {
+    let delta = time.delta_seconds();
+    let random = &mut particle_rng.0;
+    let mut alive = particle_query.iter().count();
+
+    for (entity, transform, mut emitter) in emitter_query.iter_mut() {
+        let due = emitter.due(delta, MAX_PARTICLES.saturating_sub(alive));
+        let direction = (transform.rotation * emitter.direction.extend(0.0)).truncate();
+
+        for _ in 0..due {
+            let particle = emitter.effect.particle(random, direction);
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: emitter.effect.color.with_a(particle.current_alpha()),
+                        custom_size: Some(Vec2::splat(particle.current_size())),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(transform.translation),
+                    ..default()
+                },
+                particle,
+                GameObject,
+            ));
+        }
+        alive += due;
+
+        if emitter.emission == Emission::Burst {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+pub fn update_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut particle_query: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+) {
+    let delta = time.delta_seconds();
+
+    for (entity, mut particle, mut transform, mut sprite) in particle_query.iter_mut() {
+        let Some(moved) = particle.advance(delta) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+
+        transform.translation += moved.extend(0.0);
+        sprite.custom_size = Some(Vec2::splat(particle.current_size()));
+        sprite.color.set_a(particle.current_alpha());
+    }
+}
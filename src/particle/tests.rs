@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+
+use crate::action::state::Action;
+use crate::game::components::AsteroidSize;
+use crate::testing::Harness;
+
+use super::effect::{Particle, ParticleEffect, ParticleEmitter, MAX_PARTICLES};
+use super::plugin::ParticlePlugin;
+
+const SEED: u64 = 7;
+
+fn harness() -> Harness {
+    let mut harness = Harness::build(SEED, |app| {
+        app.add_plugin(ParticlePlugin);
+    });
+    harness.start_game();
+    harness
+}
+
+#[test]
+fn shot_asteroids_burst_into_particles_that_fade_away() {
+    let mut harness = harness();
+
+    let asteroid = harness.spawn_asteroid(AsteroidSize::Large, Vec2::new(0.0, 150.0));
+    harness.actions().press(Action::Fire);
+    harness.step();
+    harness.actions().release(Action::Fire);
+    while harness.exists(asteroid) {
+        assert!(harness.frames() < 60, "the asteroid was never hit");
+        harness.step();
+    }
+    harness.step();
+    assert!(harness.count::<Particle>() > 0);
+
+    harness.run_ticks(120);
+    assert_eq!(harness.count::<Particle>(), 0);
+    assert_eq!(harness.count::<ParticleEmitter>(), 1);
+}
+
+#[test]
+fn the_ship_only_leaves_exhaust_while_thrusting() {
+    let mut harness = harness();
+
+    harness.run_ticks(10);
+    assert_eq!(harness.count::<Particle>(), 0);
+
+    harness.actions().press(Action::Thrust);
+    harness.run_ticks(10);
+    assert!(harness.count::<Particle>() > 0);
+}
+
+#[test]
+fn particles_stop_at_the_cap() {
+    let mut harness = harness();
+
+    for _ in 0..100 {
+        harness.app.world.spawn((
+            SpatialBundle::default(),
+            ParticleEmitter::burst(ParticleEffect::ship_debris(), Vec2::ZERO),
+        ));
+    }
+    harness.step();
+
+    assert_eq!(harness.count::<Particle>(), MAX_PARTICLES);
+}
@@ -0,0 +1,18 @@
+use bevy::prelude::*;
+
+/// The root of the pause menu overlay.
+#[derive(Component)]
+pub struct PauseMenu;
+
+/// Present while leaving the pause menu for a new game or the main menu, so the game being left
+/// is cleared away on the way out.
+#[derive(Resource)]
+pub struct AbandonGame;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum PauseMenuButton {
+    Resume,
+    Restart,
+    Settings,
+    Quit,
+}
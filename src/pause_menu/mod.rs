@@ -0,0 +1,6 @@
+pub mod plugin;
+
+mod components;
+mod system;
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+
+use crate::global::state::AppState;
+use crate::global::system::clear_game_objects;
+use crate::global::ui::AddMenuButtons;
+
+use super::components::{AbandonGame, PauseMenuButton};
+use super::system::input::*;
+use super::system::render::*;
+
+/// Shows the pause menu over the frozen game while in [`AppState::Paused`].
+pub struct PauseMenuPlugin;
+
+impl Plugin for PauseMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_menu_buttons::<PauseMenuButton>()
+            .add_system(setup.in_schedule(OnEnter(AppState::Paused)))
+            .add_systems((button_listener, resume_listener).in_set(OnUpdate(AppState::Paused)))
+            .add_systems(
+                (
+                    clear_game_objects.run_if(resource_exists::<AbandonGame>()),
+                    close,
+                )
+                    .in_schedule(OnExit(AppState::Paused)),
+            );
+    }
+}
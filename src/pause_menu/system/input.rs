@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+
+use crate::{
+    action::state::{Action, ActionState},
+    global::state::AppState,
+    global::ui::ButtonClicked,
+    pause_menu::components::{AbandonGame, PauseMenuButton},
+    settings::plugin::SettingsReturn,
+};
+
+pub fn button_listener(
+    mut commands: Commands,
+    mut clicked_reader: EventReader<ButtonClicked<PauseMenuButton>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for ButtonClicked(button) in clicked_reader.iter() {
+        match button {
+            PauseMenuButton::Resume => next_state.set(AppState::InGame),
+            PauseMenuButton::Restart => {
+                // With the old game cleared away, entering InGame sets up a new one
+                commands.insert_resource(AbandonGame);
+                next_state.set(AppState::InGame);
+            }
+            PauseMenuButton::Settings => {
+                commands.insert_resource(SettingsReturn(AppState::Paused));
+                next_state.set(AppState::Settings);
+            }
+            PauseMenuButton::Quit => {
+                commands.insert_resource(AbandonGame);
+                next_state.set(AppState::MainMenu);
+            }
+        }
+    }
+}
+
+pub fn resume_listener(actions: Res<ActionState>, mut next_state: ResMut<NextState<AppState>>) {
+    if actions.just_pressed(Action::Pause) {
+        next_state.set(AppState::InGame);
+    }
+}
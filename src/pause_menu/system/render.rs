@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+
+use crate::global::component::GameObject;
+use crate::global::ui::spawn_button;
+use crate::pause_menu::components::{AbandonGame, PauseMenu, PauseMenuButton};
+
+pub fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+            // Draw over the game's own UI, such as the scoreboard
+            z_index: ZIndex::Global(1),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn(
+                    TextBundle::from_section(
+                        "Paused",
+                        TextStyle {
+                            font: asset_server.load("fonts/ExcludedItalic.ttf"),
+                            font_size: 60.0,
+                            color: Color::WHITE,
+                        },
+                    )
+                    .with_style(Style {
+                        margin: UiRect::bottom(Val::Px(30.0)),
+                        ..default()
+                    }),
+                )
+                .insert(GameObject);
+
+            spawn_button(parent, &asset_server, "Resume", PauseMenuButton::Resume);
+            spawn_button(parent, &asset_server, "Restart", PauseMenuButton::Restart);
+            spawn_button(parent, &asset_server, "Settings", PauseMenuButton::Settings);
+            spawn_button(parent, &asset_server, "Quit to Menu", PauseMenuButton::Quit);
+        })
+        .insert(PauseMenu)
+        // Tagged like the rest of the game so that quitting or restarting clears it away too
+        .insert(GameObject);
+}
+
+pub fn close(mut commands: Commands, menu_query: Query<Entity, With<PauseMenu>>) {
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<AbandonGame>();
+}
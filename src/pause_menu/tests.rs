@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+
+use crate::game::components::{Asteroid, AsteroidSize, Player};
+use crate::global::state::AppState;
+use crate::global::ui::ButtonClicked;
+use crate::testing::Harness;
+
+use super::components::{PauseMenu, PauseMenuButton};
+use super::plugin::PauseMenuPlugin;
+
+const SEED: u64 = 17;
+
+fn harness() -> Harness {
+    Harness::build(SEED, |app| {
+        app.add_plugin(PauseMenuPlugin);
+    })
+}
+
+/// Pauses the game with an asteroid in it and a ship that has lost a life.
+fn pause_a_game_in_progress(harness: &mut Harness) -> Entity {
+    let asteroid = harness.spawn_asteroid(AsteroidSize::Large, Vec2::new(300.0, 300.0));
+    harness
+        .app
+        .world
+        .query::<&mut Player>()
+        .single_mut(&mut harness.app.world)
+        .lives -= 1;
+    harness.enter(AppState::Paused);
+    assert_eq!(harness.count::<PauseMenu>(), 1);
+    asteroid
+}
+
+fn click(harness: &mut Harness, button: PauseMenuButton) {
+    harness.app.world.send_event(ButtonClicked(button));
+    harness.step();
+    harness.step();
+}
+
+#[test]
+fn resuming_carries_on_with_the_same_game() {
+    let mut harness = harness();
+    harness.start_game();
+    let asteroid = pause_a_game_in_progress(&mut harness);
+    let lives = harness.player().lives;
+
+    click(&mut harness, PauseMenuButton::Resume);
+
+    assert_eq!(harness.state(), AppState::InGame);
+    assert!(harness.exists(asteroid));
+    assert_eq!(harness.player().lives, lives);
+    assert_eq!(harness.count::<PauseMenu>(), 0);
+}
+
+#[test]
+fn restarting_clears_the_old_game_away_for_a_new_one() {
+    let mut harness = harness();
+    harness.start_game();
+    let asteroid = pause_a_game_in_progress(&mut harness);
+
+    click(&mut harness, PauseMenuButton::Restart);
+
+    assert_eq!(harness.state(), AppState::InGame);
+    assert!(!harness.exists(asteroid));
+    let lives = harness.config().player.lives;
+    assert_eq!(harness.player().lives, lives);
+
+    // Later pauses are no longer abandoning anything
+    let asteroid = pause_a_game_in_progress(&mut harness);
+    click(&mut harness, PauseMenuButton::Resume);
+    assert!(harness.exists(asteroid));
+}
+
+#[test]
+fn quitting_leaves_nothing_of_the_game_behind() {
+    let mut harness = harness();
+    harness.start_game();
+    pause_a_game_in_progress(&mut harness);
+
+    click(&mut harness, PauseMenuButton::Quit);
+
+    assert_eq!(harness.state(), AppState::MainMenu);
+    assert_eq!(harness.count::<Player>(), 0);
+    assert_eq!(harness.count::<Asteroid>(), 0);
+}
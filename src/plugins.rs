@@ -0,0 +1,214 @@
+use bevy::app::PluginGroupBuilder;
+use bevy::prelude::*;
+
+use crate::action::plugin::ActionPlugin;
+use crate::audio::plugin::SoundPlugin;
+use crate::camera::plugin::CameraPlugin;
+use crate::config::plugin::ConfigPlugin;
+use crate::game::plugin::{GamePlugin, GameVisualsPlugin};
+use crate::game_over::plugin::GameOverPlugin;
+use crate::global::state::AppStatePlugin;
+use crate::global::ui::ButtonPlugin;
+use crate::high_score::plugin::HighScorePlugin;
+use crate::main_menu::plugin::MainMenuPlugin;
+use crate::net::client::ClientPlugin;
+use crate::particle::plugin::ParticlePlugin;
+use crate::pause_menu::plugin::PauseMenuPlugin;
+use crate::replay::plugin::ReplayPlugin;
+use crate::settings::plugin::SettingsPlugin;
+use crate::versus::plugin::{RoundResultsPlugin, VersusPlugin};
+
+/// The whole game as one plugin group, with a builder for leaving parts out.
+///
+/// The default is everything, and goes on top of `DefaultPlugins`. Its settings screen starts
+/// from the default preferences and never saves them, unless
+/// [`AsteroidsPlugins::with_settings`] hands it the saved ones.
+/// [`AsteroidsPlugins::headless`] is only the simulation, which runs under `MinimalPlugins`
+/// without a window or assets. Without the menus, something else has to start a game by
+/// leaving [`AppState::MainMenu`](crate::global::state::AppState::MainMenu), and each versus
+/// round by leaving [`AppState::RoundResults`](crate::global::state::AppState::RoundResults).
+pub struct AsteroidsPlugins {
+    tick_rate: f32,
+    config_file: bool,
+    visuals: bool,
+    menus: bool,
+    sound: bool,
+    replays: bool,
+    online: bool,
+    settings: SettingsPlugin,
+}
+
+impl Default for AsteroidsPlugins {
+    fn default() -> AsteroidsPlugins {
+        AsteroidsPlugins {
+            tick_rate: GamePlugin::default().tick_rate,
+            config_file: true,
+            visuals: true,
+            menus: true,
+            sound: true,
+            replays: true,
+            online: true,
+            settings: SettingsPlugin::default(),
+        }
+    }
+}
+
+impl AsteroidsPlugins {
+    /// Only the simulation, versus matches included, and the actions that drive it.
+    pub fn headless() -> AsteroidsPlugins {
+        AsteroidsPlugins {
+            config_file: false,
+            visuals: false,
+            menus: false,
+            sound: false,
+            replays: false,
+            online: false,
+            ..default()
+        }
+    }
+
+    /// Simulation ticks per second.
+    pub fn with_tick_rate(mut self, tick_rate: f32) -> AsteroidsPlugins {
+        self.tick_rate = tick_rate;
+        self
+    }
+
+    /// Reading the game config from the assets folder, rather than using the built-in values.
+    pub fn with_config_file(mut self, enabled: bool) -> AsteroidsPlugins {
+        self.config_file = enabled;
+        self
+    }
+
+    /// The camera, sprites, HUD and particle effects.
+    pub fn with_visuals(mut self, enabled: bool) -> AsteroidsPlugins {
+        self.visuals = enabled;
+        self
+    }
+
+    /// The main menu, pause menu, settings, game over, versus results and high score screens.
+    pub fn with_menus(mut self, enabled: bool) -> AsteroidsPlugins {
+        self.menus = enabled;
+        self
+    }
+
+    /// Sound effects and music.
+    pub fn with_sound(mut self, enabled: bool) -> AsteroidsPlugins {
+        self.sound = enabled;
+        self
+    }
+
+    /// Recording every game, and playing recordings back.
+    pub fn with_replays(mut self, enabled: bool) -> AsteroidsPlugins {
+        self.replays = enabled;
+        self
+    }
+
+    /// Joining games run by a server, once a [`NetClient`](crate::net::client::NetClient) is
+    /// inserted.
+    pub fn with_online(mut self, enabled: bool) -> AsteroidsPlugins {
+        self.online = enabled;
+        self
+    }
+
+    /// Has the settings screen, which comes with the menus, edit the preferences `settings` was
+    /// loaded with.
+    pub fn with_settings(mut self, settings: SettingsPlugin) -> AsteroidsPlugins {
+        self.settings = settings;
+        self
+    }
+}
+
+impl PluginGroup for AsteroidsPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        let mut group = PluginGroupBuilder::start::<AsteroidsPlugins>().add(AppStatePlugin);
+
+        if self.visuals {
+            group = group.add(CameraPlugin);
+        }
+        if self.config_file {
+            group = group.add(ConfigPlugin);
+        }
+        group = group.add(ActionPlugin);
+        if self.menus {
+            group = group.add(ButtonPlugin).add(MainMenuPlugin);
+        }
+        group = group
+            .add(GamePlugin {
+                tick_rate: self.tick_rate,
+            })
+            .add(VersusPlugin);
+        if self.visuals {
+            group = group.add(GameVisualsPlugin).add(ParticlePlugin);
+        }
+        if self.menus {
+            group = group
+                .add(self.settings)
+                .add(PauseMenuPlugin)
+                .add(GameOverPlugin)
+                .add(RoundResultsPlugin)
+                .add(HighScorePlugin);
+        }
+        if self.replays {
+            group = group.add(ReplayPlugin);
+        }
+        if self.online {
+            group = group.add(ClientPlugin);
+        }
+        if self.sound {
+            group = group.add(SoundPlugin::default());
+        }
+
+        group
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::audio::AudioPlugin;
+    use bevy::input::InputPlugin;
+
+    use super::*;
+    use crate::game::components::{Playfield, SeedConfig};
+    use crate::global::state::AppState;
+    use crate::global::ui::ButtonClicked;
+    use crate::main_menu::components::MainMenuButton;
+    use crate::settings::components::{SettingsButton, SettingsMenu};
+
+    #[test]
+    fn the_default_group_has_a_settings_screen_to_go_with_its_menus() {
+        let mut app = App::new();
+        // What the group expects of `DefaultPlugins`, less the window and renderer
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin::default())
+            .add_plugin(AudioPlugin)
+            .add_plugin(InputPlugin)
+            .init_resource::<UiScale>()
+            .add_event::<CursorMoved>()
+            .add_event::<ReceivedCharacter>()
+            .insert_resource(Playfield::default())
+            .insert_resource(SeedConfig {
+                fixed_seed: Some(3),
+            })
+            .add_plugins(AsteroidsPlugins::default());
+        app.update();
+
+        app.world
+            .send_event(ButtonClicked(MainMenuButton::Settings));
+        app.update();
+        app.update();
+        assert_eq!(
+            app.world.resource::<State<AppState>>().0,
+            AppState::Settings
+        );
+        let mut menu_query = app.world.query::<&SettingsMenu>();
+        assert_eq!(menu_query.iter(&app.world).count(), 1);
+
+        app.world.send_event(ButtonClicked(SettingsButton::Back));
+        app.update();
+        app.update();
+        assert_eq!(
+            app.world.resource::<State<AppState>>().0,
+            AppState::MainMenu
+        );
+    }
+}
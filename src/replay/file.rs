@@ -0,0 +1,343 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::Vec2;
+use bevy::utils::HashSet;
+
+use crate::action::state::{dequantize_rotation, quantize_rotation, Action, TickActions};
+
+const MAGIC: &[u8; 4] = b"ASTR";
+const VERSION: u16 = 2;
+/// Single player replays from before co-op, with no player count in the header.
+const SOLO_VERSION: u16 = 1;
+
+const THRUST_PRESSED: u8 = 1 << 0;
+const THRUST_JUST_PRESSED: u8 = 1 << 1;
+const FIRE_PRESSED: u8 = 1 << 2;
+const FIRE_JUST_PRESSED: u8 = 1 << 3;
+const HAS_AIM: u8 = 1 << 4;
+
+/// The simulation input for one tick, as stored in a replay.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TickRecord {
+    flags: u8,
+    rotation: i8,
+    aim: (i16, i16),
+}
+
+impl TickRecord {
+    pub fn from_actions(actions: &TickActions) -> TickRecord {
+        let mut flags = 0;
+        for (set, action, flag) in [
+            (&actions.pressed, Action::Thrust, THRUST_PRESSED),
+            (&actions.just_pressed, Action::Thrust, THRUST_JUST_PRESSED),
+            (&actions.pressed, Action::Fire, FIRE_PRESSED),
+            (&actions.just_pressed, Action::Fire, FIRE_JUST_PRESSED),
+        ] {
+            if set.contains(&action) {
+                flags |= flag;
+            }
+        }
+
+        let aim = match actions.aim {
+            Some(aim) => {
+                flags |= HAS_AIM;
+                (aim.x.round() as i16, aim.y.round() as i16)
+            }
+            None => (0, 0),
+        };
+
+        TickRecord {
+            flags,
+            rotation: quantize_rotation(actions.rotation),
+            aim,
+        }
+    }
+
+    /// Appends the record's encoding to `bytes`. The aim is only written when there is one.
+    pub fn write(&self, bytes: &mut Vec<u8>) {
+        bytes.push(self.flags);
+        bytes.extend_from_slice(&self.rotation.to_le_bytes());
+        if self.flags & HAS_AIM != 0 {
+            bytes.extend_from_slice(&self.aim.0.to_le_bytes());
+            bytes.extend_from_slice(&self.aim.1.to_le_bytes());
+        }
+    }
+
+    pub fn to_actions(self) -> TickActions {
+        let actions_with = |thrust: u8, fire: u8| -> HashSet<Action> {
+            [(Action::Thrust, thrust), (Action::Fire, fire)]
+                .into_iter()
+                .filter(|(_, flag)| self.flags & flag != 0)
+                .map(|(action, _)| action)
+                .collect()
+        };
+        let pressed = actions_with(THRUST_PRESSED, FIRE_PRESSED);
+        let just_pressed = actions_with(THRUST_JUST_PRESSED, FIRE_JUST_PRESSED);
+
+        TickActions {
+            pressed,
+            just_pressed,
+            rotation: dequantize_rotation(self.rotation),
+            aim: (self.flags & HAS_AIM != 0)
+                .then(|| Vec2::new(self.aim.0 as f32, self.aim.1 as f32)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    NotAReplay,
+    UnsupportedVersion(u16),
+    Truncated,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Io(error) => write!(f, "{}", error),
+            ReplayError::NotAReplay => write!(f, "not a replay file"),
+            ReplayError::UnsupportedVersion(version) => {
+                write!(f, "unsupported replay version {}", version)
+            }
+            ReplayError::Truncated => write!(f, "replay file is truncated"),
+        }
+    }
+}
+
+impl From<io::Error> for ReplayError {
+    fn from(error: io::Error) -> ReplayError {
+        ReplayError::Io(error)
+    }
+}
+
+/// A recorded game: everything needed to play it back through the normal game systems.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub tick_rate: f32,
+    /// How many players took part.
+    pub players: usize,
+    /// Everyone's score added together.
+    pub final_score: usize,
+    /// One record per player for every tick, in player order.
+    pub ticks: Vec<Vec<TickRecord>>,
+}
+
+impl Replay {
+    /// Encodes the replay, run-length compressing repeated ticks.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&self.tick_rate.to_le_bytes());
+        bytes.push(self.players as u8);
+        bytes.extend_from_slice(&(self.final_score as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.ticks.len() as u32).to_le_bytes());
+
+        let mut index = 0;
+        while index < self.ticks.len() {
+            let tick = &self.ticks[index];
+            let run = self.ticks[index..]
+                .iter()
+                .take(u16::MAX as usize)
+                .take_while(|other| *other == tick)
+                .count();
+
+            bytes.extend_from_slice(&(run as u16).to_le_bytes());
+            for record in tick {
+                record.write(&mut bytes);
+            }
+
+            index += run;
+        }
+
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Replay, ReplayError> {
+        let mut reader = Reader::new(bytes);
+
+        if reader.take::<4>().map_err(|_| ReplayError::NotAReplay)? != *MAGIC {
+            return Err(ReplayError::NotAReplay);
+        }
+        let version = u16::from_le_bytes(reader.take()?);
+        if version != VERSION && version != SOLO_VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+
+        let seed = u64::from_le_bytes(reader.take()?);
+        let tick_rate = f32::from_le_bytes(reader.take()?);
+        let players = match version {
+            SOLO_VERSION => 1,
+            _ => u8::from_le_bytes(reader.take()?) as usize,
+        };
+        let final_score = u64::from_le_bytes(reader.take()?) as usize;
+        let tick_count = u32::from_le_bytes(reader.take()?) as usize;
+
+        let mut ticks = Vec::with_capacity(tick_count);
+        while ticks.len() < tick_count {
+            let run = u16::from_le_bytes(reader.take()?) as usize;
+            let tick = (0..players)
+                .map(|_| reader.take_record())
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if run == 0 || ticks.len() + run > tick_count {
+                return Err(ReplayError::Truncated);
+            }
+            ticks.extend(std::iter::repeat_n(tick, run));
+        }
+
+        Ok(Replay {
+            seed,
+            tick_rate,
+            players,
+            final_score,
+            ticks,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Replay, ReplayError> {
+        Replay::decode(&fs::read(path)?)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.encode())
+    }
+}
+
+/// Reads little-endian values off the front of a byte slice.
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Reader<'a> {
+        Reader { bytes }
+    }
+
+    pub(crate) fn take<const N: usize>(&mut self) -> Result<[u8; N], ReplayError> {
+        if self.bytes.len() < N {
+            return Err(ReplayError::Truncated);
+        }
+        let (head, tail) = self.bytes.split_at(N);
+        self.bytes = tail;
+        Ok(head.try_into().unwrap())
+    }
+
+    pub(crate) fn take_record(&mut self) -> Result<TickRecord, ReplayError> {
+        let [flags] = self.take()?;
+        let rotation = i8::from_le_bytes(self.take()?);
+        let aim = if flags & HAS_AIM != 0 {
+            (
+                i16::from_le_bytes(self.take()?),
+                i16::from_le_bytes(self.take()?),
+            )
+        } else {
+            (0, 0)
+        };
+
+        Ok(TickRecord {
+            flags,
+            rotation,
+            aim,
+        })
+    }
+}
+
+/// Where the most recent game is recorded, if the platform has a data directory.
+pub fn last_replay_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("asteroids").join("last.replay"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_the_file_format() {
+        let mut aiming = TickActions {
+            aim: Some(Vec2::new(-120.0, 37.0)),
+            rotation: 0.5,
+            ..Default::default()
+        };
+        aiming.pressed.insert(Action::Thrust);
+        aiming.just_pressed.insert(Action::Fire);
+
+        let idle = vec![TickRecord::default(); 2];
+        let mut ticks = vec![idle.clone(); 70_000];
+        ticks.push(vec![
+            TickRecord::default(),
+            TickRecord::from_actions(&aiming),
+        ]);
+        ticks.push(idle);
+
+        let replay = Replay {
+            seed: 1234,
+            tick_rate: 60.0,
+            players: 2,
+            final_score: 870,
+            ticks,
+        };
+
+        let bytes = replay.encode();
+        assert!(bytes.len() < 64);
+        assert_eq!(Replay::decode(&bytes).unwrap(), replay);
+        assert_eq!(replay.ticks[70_000][1].to_actions(), aiming.quantized());
+    }
+
+    #[test]
+    fn reads_single_player_replays_from_before_co_op() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&SOLO_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&7u64.to_le_bytes());
+        bytes.extend_from_slice(&60.0f32.to_le_bytes());
+        bytes.extend_from_slice(&20u64.to_le_bytes());
+        bytes.extend_from_slice(&3u32.to_le_bytes());
+        bytes.extend_from_slice(&3u16.to_le_bytes());
+        bytes.extend_from_slice(&[FIRE_PRESSED, 0]);
+
+        let replay = Replay::decode(&bytes).unwrap();
+        assert_eq!(replay.players, 1);
+        assert_eq!(replay.final_score, 20);
+        assert_eq!(
+            replay.ticks,
+            vec![
+                vec![TickRecord {
+                    flags: FIRE_PRESSED,
+                    ..Default::default()
+                }];
+                3
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_corrupt_files() {
+        assert!(matches!(
+            Replay::decode(b"nope"),
+            Err(ReplayError::NotAReplay)
+        ));
+
+        let replay = Replay {
+            seed: 1,
+            tick_rate: 60.0,
+            players: 1,
+            final_score: 0,
+            ticks: vec![vec![TickRecord::default()]; 10],
+        };
+        let bytes = replay.encode();
+        assert!(matches!(
+            Replay::decode(&bytes[..bytes.len() - 1]),
+            Err(ReplayError::Truncated)
+        ));
+    }
+}
@@ -0,0 +1,116 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::action::plugin::SampleActions;
+use crate::game::components::GameMode;
+use crate::game::plugin::{is_new_game, is_running, GameSet};
+use crate::global::state::AppState;
+
+use super::file::{Replay, TickRecord};
+use super::system::*;
+
+/// Records every game's input and plays recordings back through the normal game systems.
+pub struct ReplayPlugin;
+
+/// The input of the game in progress, saved when the game ends.
+#[derive(Resource)]
+pub struct Recorder {
+    pub players: usize,
+    pub ticks: Vec<Vec<TickRecord>>,
+}
+
+/// Present while a replay is being played back instead of live input.
+#[derive(Resource)]
+pub struct Playback {
+    pub replay: Replay,
+    /// Index of the next tick to feed to the simulation.
+    pub cursor: usize,
+    pub speed: f32,
+    /// Settings replaced for the duration of the playback, restored when it ends.
+    pub(super) previous_seed: Option<u64>,
+    pub(super) previous_period: Duration,
+    pub(super) previous_mode: GameMode,
+}
+
+impl Playback {
+    pub fn is_finished(&self) -> bool {
+        self.cursor >= self.replay.ticks.len()
+    }
+}
+
+/// Insert to load a replay file and start playing it back.
+#[derive(Resource)]
+pub struct PlaybackRequest(pub PathBuf);
+
+/// Playback speeds that can be cycled through while watching a replay.
+pub const PLAYBACK_SPEEDS: [f32; 5] = [0.5, 1.0, 2.0, 4.0, 8.0];
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(begin_requested_playback.run_if(resource_exists::<PlaybackRequest>()))
+            // Recording
+            .add_system(
+                start_recording
+                    .run_if(not_playing_back)
+                    .run_if(is_recorded)
+                    .run_if(is_new_game)
+                    .in_schedule(OnEnter(AppState::InGame)),
+            )
+            .add_system(
+                record_tick
+                    .run_if(resource_exists::<Recorder>())
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(is_running)
+                    .after(SampleActions)
+                    .before(GameSet::Input)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                save_recording
+                    .run_if(resource_exists::<Recorder>())
+                    .in_schedule(OnEnter(AppState::GameOver)),
+            )
+            // Playback
+            .add_system(
+                feed_playback
+                    .run_if(resource_exists::<Playback>())
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(is_running)
+                    .after(SampleActions)
+                    .before(GameSet::Input)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_systems(
+                (rewind_playback, spawn_replay_overlay)
+                    .distributive_run_if(resource_exists::<Playback>())
+                    .distributive_run_if(is_new_game)
+                    .in_schedule(OnEnter(AppState::InGame)),
+            )
+            .add_system(
+                playback_speed_control
+                    .run_if(resource_exists::<Playback>())
+                    .in_set(OnUpdate(AppState::InGame)),
+            )
+            .add_system(
+                end_playback
+                    .run_if(resource_exists::<Playback>())
+                    .in_schedule(OnExit(AppState::GameOver)),
+            )
+            .add_system(
+                end_playback
+                    .run_if(resource_exists::<Playback>())
+                    .in_schedule(OnEnter(AppState::MainMenu)),
+            );
+    }
+}
+
+/// Versus rounds aren't recorded, as a replay is of a single game.
+pub fn is_recorded(mode: Res<GameMode>) -> bool {
+    *mode != GameMode::Versus
+}
+
+pub fn not_playing_back(playback: Option<Res<Playback>>) -> bool {
+    playback.is_none()
+}
@@ -0,0 +1,201 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::action::state::TickInput;
+use crate::game::components::{GameMode, GameRng, GameState, SeedConfig};
+use crate::global::{component::GameObject, component::Scoreboard, state::AppState};
+
+use super::file::{last_replay_path, Replay, TickRecord};
+use super::plugin::*;
+
+#[derive(Component)]
+pub struct ReplayOverlayText;
+
+pub fn begin_requested_playback(
+    mut commands: Commands,
+    request: Res<PlaybackRequest>,
+    mut seed_config: ResMut<SeedConfig>,
+    mut fixed_time: ResMut<FixedTime>,
+    mut mode: ResMut<GameMode>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    commands.remove_resource::<PlaybackRequest>();
+
+    let replay = match Replay::load(&request.0) {
+        Ok(replay) => replay,
+        Err(error) => {
+            warn!("Can't play {}: {}", request.0.display(), error);
+            return;
+        }
+    };
+    info!(
+        "Playing back {} ({} ticks, seed {})",
+        request.0.display(),
+        replay.ticks.len(),
+        replay.seed
+    );
+
+    let previous_seed = seed_config.fixed_seed.replace(replay.seed);
+    let previous_period = fixed_time.period;
+    fixed_time.period = Duration::from_secs_f32(1.0 / replay.tick_rate);
+    let previous_mode = std::mem::replace(
+        &mut *mode,
+        match replay.players {
+            1 => GameMode::Solo,
+            _ => GameMode::Coop,
+        },
+    );
+
+    commands.insert_resource(Playback {
+        replay,
+        cursor: 0,
+        speed: 1.0,
+        previous_seed,
+        previous_period,
+        previous_mode,
+    });
+    next_state.set(AppState::InGame);
+}
+
+pub fn start_recording(mut commands: Commands, mode: Res<GameMode>) {
+    commands.insert_resource(Recorder {
+        players: mode.players(),
+        ticks: Vec::new(),
+    });
+}
+
+pub fn record_tick(input: Res<TickInput>, mut recorder: ResMut<Recorder>) {
+    let tick = (0..recorder.players)
+        .map(|player| {
+            input
+                .player(player)
+                .map(TickRecord::from_actions)
+                .unwrap_or_default()
+        })
+        .collect();
+    recorder.ticks.push(tick);
+}
+
+pub fn save_recording(
+    mut commands: Commands,
+    mut recorder: ResMut<Recorder>,
+    scoreboard: Res<Scoreboard>,
+    game_rng: Res<GameRng>,
+    fixed_time: Res<FixedTime>,
+) {
+    let replay = Replay {
+        seed: game_rng.seed,
+        tick_rate: 1.0 / fixed_time.period.as_secs_f32(),
+        players: recorder.players,
+        final_score: scoreboard.total(),
+        ticks: std::mem::take(&mut recorder.ticks),
+    };
+    commands.remove_resource::<Recorder>();
+
+    if let Some(path) = last_replay_path() {
+        if let Err(error) = replay.save(&path) {
+            warn!("Failed to save replay to {}: {}", path.display(), error);
+        }
+    }
+}
+
+pub fn feed_playback(
+    mut playback: ResMut<Playback>,
+    mut input: ResMut<TickInput>,
+    mut game_state: ResMut<GameState>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if playback.is_finished() {
+        // The recording stopped without the game being lost, so stop here too
+        *input = TickInput::default();
+        game_state.finished = true;
+        next_state.set(AppState::GameOver);
+        return;
+    }
+
+    input.players = playback.replay.ticks[playback.cursor]
+        .iter()
+        .map(|record| record.to_actions())
+        .collect();
+    playback.cursor += 1;
+}
+
+/// Starts a restarted playback from the first tick again.
+pub fn rewind_playback(mut playback: ResMut<Playback>) {
+    playback.cursor = 0;
+}
+
+pub fn spawn_replay_overlay(
+    mut commands: Commands,
+    playback: Res<Playback>,
+    asset_server: Res<AssetServer>,
+) {
+    commands
+        .spawn(
+            TextBundle::from_section(
+                overlay_text(playback.speed),
+                TextStyle {
+                    font: asset_server.load("fonts/ExcludedItalic.ttf"),
+                    font_size: 32.0,
+                    color: Color::RED,
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    bottom: Val::Px(5.0),
+                    right: Val::Px(10.0),
+                    ..default()
+                },
+                ..default()
+            }),
+        )
+        .insert(ReplayOverlayText)
+        .insert(GameObject);
+}
+
+pub fn playback_speed_control(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut playback: ResMut<Playback>,
+    mut time: ResMut<Time>,
+    mut overlay_query: Query<&mut Text, With<ReplayOverlayText>>,
+) {
+    let current = PLAYBACK_SPEEDS
+        .iter()
+        .position(|speed| *speed == playback.speed)
+        .unwrap_or(1);
+
+    let next = if keyboard_input.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]) {
+        current.saturating_sub(1)
+    } else if keyboard_input.any_just_pressed([KeyCode::Equals, KeyCode::NumpadAdd]) {
+        (current + 1).min(PLAYBACK_SPEEDS.len() - 1)
+    } else {
+        return;
+    };
+
+    playback.speed = PLAYBACK_SPEEDS[next];
+    time.set_relative_speed(playback.speed);
+    for mut text in overlay_query.iter_mut() {
+        text.sections[0].value = overlay_text(playback.speed);
+    }
+}
+
+pub fn end_playback(
+    mut commands: Commands,
+    playback: Res<Playback>,
+    mut seed_config: ResMut<SeedConfig>,
+    mut fixed_time: ResMut<FixedTime>,
+    mut time: ResMut<Time>,
+    mut mode: ResMut<GameMode>,
+) {
+    seed_config.fixed_seed = playback.previous_seed;
+    *mode = playback.previous_mode;
+    fixed_time.period = playback.previous_period;
+    time.set_relative_speed(1.0);
+    commands.remove_resource::<Playback>();
+}
+
+fn overlay_text(speed: f32) -> String {
+    format!("REPLAY {}x", speed)
+}
@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+
+use crate::action::state::Action;
+use crate::game::components::GameRng;
+use crate::global::state::AppState;
+use crate::testing::Harness;
+
+use super::file::Replay;
+use super::plugin::{Playback, PlaybackRequest, Recorder, ReplayPlugin};
+
+#[test]
+fn playback_reproduces_the_recorded_game() {
+    let mut game = Harness::build(42, |app| {
+        app.add_plugin(ReplayPlugin);
+    });
+    game.start_game();
+
+    for tick in 0..1200 {
+        let mut actions = game.actions();
+        let actions = actions.player_mut(0);
+        if tick % 10 == 0 {
+            actions.press(Action::Fire);
+        } else {
+            actions.release(Action::Fire);
+        }
+        actions.set_rotation(1.0);
+        game.step();
+        assert_eq!(game.state(), AppState::InGame);
+    }
+
+    let replay = Replay {
+        seed: game.app.world.resource::<GameRng>().seed,
+        tick_rate: 1.0 / game.period().as_secs_f32(),
+        players: 1,
+        final_score: game.score(),
+        ticks: game.app.world.resource::<Recorder>().ticks.clone(),
+    };
+    assert!(replay.final_score > 0);
+
+    let path = std::env::temp_dir().join(format!("asteroids-replay-{}", std::process::id()));
+    replay.save(&path).unwrap();
+
+    // Live keyboard input must not leak into the playback
+    let mut playback = Harness::build(0, |app| {
+        app.add_plugin(ReplayPlugin)
+            .init_resource::<Input<KeyCode>>()
+            .init_resource::<Gamepads>()
+            .init_resource::<Input<GamepadButton>>()
+            .init_resource::<Axis<GamepadAxis>>()
+            .add_event::<CursorMoved>()
+            .insert_resource(PlaybackRequest(path.clone()));
+    });
+    playback
+        .app
+        .world
+        .resource_mut::<Input<KeyCode>>()
+        .press(KeyCode::Space);
+    while playback.state() != AppState::GameOver {
+        assert!(playback.frames() < 2000, "playback never finished");
+        playback.step();
+    }
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(playback.app.world.resource::<Playback>().is_finished());
+    assert_eq!(playback.score(), replay.final_score);
+}
@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+
+use super::preferences::{Control, Preferences};
+
+/// The root of the settings screen.
+#[derive(Component)]
+pub struct SettingsMenu;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SettingsButton {
+    MasterVolume,
+    SfxVolume,
+    MusicVolume,
+    Display,
+    ScreenFit,
+    VSync,
+    ScreenShake,
+    Palette,
+    Bind(Control),
+    Back,
+}
+
+impl SettingsButton {
+    /// Every button, top to bottom.
+    pub fn all() -> impl Iterator<Item = SettingsButton> {
+        [
+            SettingsButton::MasterVolume,
+            SettingsButton::SfxVolume,
+            SettingsButton::MusicVolume,
+            SettingsButton::Display,
+            SettingsButton::ScreenFit,
+            SettingsButton::VSync,
+            SettingsButton::ScreenShake,
+            SettingsButton::Palette,
+        ]
+        .into_iter()
+        .chain(Control::ALL.map(SettingsButton::Bind))
+        .chain([SettingsButton::Back])
+    }
+
+    /// What the button reads, showing the current value of its setting.
+    pub fn label(&self, preferences: &Preferences, rebinding: Option<Control>) -> String {
+        let on_off = |on: bool| if on { "On" } else { "Off" };
+        let percent = |volume: f32| format!("{:.0}%", volume * 100.0);
+
+        match self {
+            SettingsButton::MasterVolume => {
+                format!("Master Volume: {}", percent(preferences.audio.master))
+            }
+            SettingsButton::SfxVolume => {
+                format!("Effects Volume: {}", percent(preferences.audio.sfx))
+            }
+            SettingsButton::MusicVolume => {
+                format!("Music Volume: {}", percent(preferences.audio.music))
+            }
+            SettingsButton::Display => format!("Display: {}", preferences.display.label()),
+            SettingsButton::ScreenFit => {
+                format!("Screen Fit: {}", preferences.screen_fit.label())
+            }
+            SettingsButton::VSync => format!("VSync: {}", on_off(preferences.vsync)),
+            SettingsButton::ScreenShake => {
+                format!("Screen Shake: {}", on_off(preferences.screen_shake))
+            }
+            SettingsButton::Palette => format!("Palette: {}", preferences.palette.label()),
+            SettingsButton::Bind(control) if rebinding == Some(*control) => {
+                format!("{}: press a key...", control.label())
+            }
+            SettingsButton::Bind(control) => {
+                let keys: Vec<String> = preferences
+                    .keys
+                    .keys(*control)
+                    .iter()
+                    .map(|key| format!("{:?}", key))
+                    .collect();
+                let keys = if keys.is_empty() {
+                    "-".to_string()
+                } else {
+                    keys.join(", ")
+                };
+                format!("{}: {}", control.label(), keys)
+            }
+            SettingsButton::Back => "Back".to_string(),
+        }
+    }
+}
+
+/// The control waiting for its new key, after its button was clicked.
+#[derive(Resource, Default)]
+pub struct Rebinding(pub Option<Control>);
@@ -0,0 +1,7 @@
+pub mod plugin;
+pub mod preferences;
+
+pub(crate) mod components;
+mod system;
+#[cfg(test)]
+mod tests;
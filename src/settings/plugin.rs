@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+
+use crate::audio::settings::AudioSettings;
+use crate::camera::fit::ScreenFit;
+use crate::camera::shake::ScreenShake;
+use crate::global::state::AppState;
+use crate::global::ui::AddMenuButtons;
+
+use super::components::{Rebinding, SettingsButton};
+use super::preferences::Preferences;
+use super::system::apply::*;
+use super::system::input::*;
+use super::system::render::*;
+
+/// The settings screen, and the saved [`Preferences`] it edits.
+///
+/// The preferences are loaded before the app is built, so the window can open the way it was
+/// left. Any change to them is passed on to the volume, controls, palette and window. The
+/// default is the default preferences, never saved.
+#[derive(Default)]
+pub struct SettingsPlugin {
+    pub preferences: Preferences,
+    pub path: Option<PathBuf>,
+}
+
+/// Where the preferences are saved when leaving the settings screen.
+#[derive(Resource)]
+pub struct PreferencesPath(pub Option<PathBuf>);
+
+/// The screen to go back to when leaving the settings.
+#[derive(Resource)]
+pub struct SettingsReturn(pub AppState);
+
+impl Default for SettingsReturn {
+    fn default() -> SettingsReturn {
+        SettingsReturn(AppState::MainMenu)
+    }
+}
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        // The sound and camera plugins own these, but the settings still work without them
+        app.init_resource::<AudioSettings>()
+            .init_resource::<ScreenShake>()
+            .init_resource::<ScreenFit>()
+            .insert_resource(self.preferences.clone())
+            .insert_resource(PreferencesPath(self.path.clone()))
+            .init_resource::<SettingsReturn>()
+            .init_resource::<Rebinding>()
+            .add_menu_buttons::<SettingsButton>()
+            .add_system(apply_preferences.run_if(resource_changed::<Preferences>()))
+            .add_system(setup.in_schedule(OnEnter(AppState::Settings)))
+            .add_systems(
+                (button_listener, back_listener, capture_key, update_labels)
+                    .chain()
+                    .in_set(OnUpdate(AppState::Settings)),
+            )
+            .add_system(close.in_schedule(OnExit(AppState::Settings)));
+    }
+}
@@ -0,0 +1,402 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use bevy::window::{PresentMode, WindowMode};
+use serde::{Deserialize, Serialize};
+
+use crate::action::bindings::{InputBindings, InputSource, LocalBindings};
+use crate::action::state::Action;
+use crate::audio::settings::AudioSettings;
+use crate::camera::fit::ScreenFit;
+use crate::global::palette::Palette;
+
+/// Everything chosen on the settings screen, saved between runs.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    pub audio: AudioSettings,
+    pub keys: KeyBindings,
+    pub display: DisplayMode,
+    pub screen_fit: ScreenFit,
+    pub vsync: bool,
+    pub screen_shake: bool,
+    pub palette: Palette,
+}
+
+impl Default for Preferences {
+    fn default() -> Preferences {
+        Preferences {
+            audio: AudioSettings::default(),
+            keys: KeyBindings::default(),
+            display: DisplayMode::Windowed,
+            screen_fit: ScreenFit::default(),
+            vsync: true,
+            screen_shake: true,
+            palette: Palette::default(),
+        }
+    }
+}
+
+/// Whether the game runs in a window or takes over the screen.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisplayMode {
+    #[default]
+    Windowed,
+    Fullscreen,
+}
+
+impl DisplayMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DisplayMode::Windowed => "Windowed",
+            DisplayMode::Fullscreen => "Fullscreen",
+        }
+    }
+
+    pub fn window_mode(&self) -> WindowMode {
+        match self {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            // Borderless keeps the desktop resolution, so switching is quick and alt-tab friendly
+            DisplayMode::Fullscreen => WindowMode::BorderlessFullscreen,
+        }
+    }
+}
+
+/// A control that can be rebound on the settings screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Control {
+    Thrust,
+    Fire,
+    RotateLeft,
+    RotateRight,
+    Pause,
+}
+
+impl Control {
+    pub const ALL: [Control; 5] = [
+        Control::Thrust,
+        Control::Fire,
+        Control::RotateLeft,
+        Control::RotateRight,
+        Control::Pause,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Control::Thrust => "Thrust",
+            Control::Fire => "Fire",
+            Control::RotateLeft => "Turn Left",
+            Control::RotateRight => "Turn Right",
+            Control::Pause => "Pause",
+        }
+    }
+}
+
+/// The keyboard keys for each control. Mouse and gamepad controls aren't rebindable.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub thrust: Vec<KeyCode>,
+    pub fire: Vec<KeyCode>,
+    pub rotate_left: Vec<KeyCode>,
+    pub rotate_right: Vec<KeyCode>,
+    pub pause: Vec<KeyCode>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> KeyBindings {
+        let defaults = InputBindings::default();
+        let keys = |sources: &[InputSource]| {
+            sources
+                .iter()
+                .filter_map(|source| match source {
+                    InputSource::Key(key) => Some(*key),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        KeyBindings {
+            thrust: keys(&defaults.buttons[&Action::Thrust]),
+            fire: keys(&defaults.buttons[&Action::Fire]),
+            rotate_left: keys(&defaults.rotate_left),
+            rotate_right: keys(&defaults.rotate_right),
+            pause: keys(&defaults.buttons[&Action::Pause]),
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn keys(&self, control: Control) -> &[KeyCode] {
+        match control {
+            Control::Thrust => &self.thrust,
+            Control::Fire => &self.fire,
+            Control::RotateLeft => &self.rotate_left,
+            Control::RotateRight => &self.rotate_right,
+            Control::Pause => &self.pause,
+        }
+    }
+
+    fn keys_mut(&mut self, control: Control) -> &mut Vec<KeyCode> {
+        match control {
+            Control::Thrust => &mut self.thrust,
+            Control::Fire => &mut self.fire,
+            Control::RotateLeft => &mut self.rotate_left,
+            Control::RotateRight => &mut self.rotate_right,
+            Control::Pause => &mut self.pause,
+        }
+    }
+
+    /// Makes `key` the only key for `control`, taking it away from any other control so one
+    /// key never does two things.
+    pub fn rebind(&mut self, control: Control, key: KeyCode) {
+        for other in Control::ALL {
+            self.keys_mut(other).retain(|bound| *bound != key);
+        }
+        *self.keys_mut(control) = vec![key];
+    }
+
+    /// Swaps these keys into `bindings`, leaving its mouse and gamepad controls alone.
+    pub fn apply(&self, bindings: &mut InputBindings) {
+        let replace_keys = |sources: &mut Vec<InputSource>, keys: &[KeyCode]| {
+            sources.retain(|source| !matches!(source, InputSource::Key(_)));
+            sources.splice(0..0, keys.iter().copied().map(InputSource::Key));
+        };
+
+        for (action, keys) in [
+            (Action::Thrust, &self.thrust),
+            (Action::Fire, &self.fire),
+            (Action::Pause, &self.pause),
+        ] {
+            replace_keys(bindings.buttons.entry(action).or_default(), keys);
+        }
+        replace_keys(&mut bindings.rotate_left, &self.rotate_left);
+        replace_keys(&mut bindings.rotate_right, &self.rotate_right);
+    }
+
+    /// Swaps these keys into the first player's bindings for games on one screen, leaving out
+    /// any key the other players have, as they share the keyboard.
+    pub fn apply_local(&self, bindings: &mut LocalBindings) {
+        let Some((first, others)) = bindings.players.split_first_mut() else {
+            return;
+        };
+        let taken: Vec<KeyCode> = others
+            .iter()
+            .flat_map(|other| {
+                other
+                    .buttons
+                    .values()
+                    .flatten()
+                    .chain(&other.rotate_left)
+                    .chain(&other.rotate_right)
+            })
+            .filter_map(|source| match source {
+                InputSource::Key(key) => Some(*key),
+                _ => None,
+            })
+            .collect();
+
+        let mut keys = self.clone();
+        for control in Control::ALL {
+            keys.keys_mut(control).retain(|key| !taken.contains(key));
+        }
+        keys.apply(first);
+    }
+}
+
+#[derive(Debug)]
+pub enum PreferencesError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+}
+
+impl fmt::Display for PreferencesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PreferencesError::Io(error) => write!(f, "{}", error),
+            PreferencesError::Parse(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<io::Error> for PreferencesError {
+    fn from(error: io::Error) -> PreferencesError {
+        PreferencesError::Io(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for PreferencesError {
+    fn from(error: ron::error::SpannedError) -> PreferencesError {
+        PreferencesError::Parse(error)
+    }
+}
+
+impl Preferences {
+    pub fn window_mode(&self) -> WindowMode {
+        self.display.window_mode()
+    }
+
+    pub fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Preferences, PreferencesError> {
+        Ok(ron::de::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Loads the preferences at `path`, falling back to the defaults if the file is missing or
+    /// corrupt.
+    pub fn load(path: &Path) -> Preferences {
+        match Preferences::read(path) {
+            Ok(preferences) => preferences,
+            Err(PreferencesError::Io(error)) if error.kind() == io::ErrorKind::NotFound => {
+                Preferences::default()
+            }
+            Err(error) => {
+                bevy::log::warn!("Ignoring settings file {}: {}", path.display(), error);
+                Preferences::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let contents = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)
+    }
+}
+
+/// Where the preferences live, if the platform has a config directory.
+pub fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("asteroids").join("settings.ron"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_the_file_format() {
+        let mut preferences = Preferences {
+            display: DisplayMode::Fullscreen,
+            screen_fit: ScreenFit::Expand,
+            vsync: false,
+            screen_shake: false,
+            palette: Palette::Colorblind,
+            ..default()
+        };
+        preferences.audio.music = 0.3;
+        preferences.keys.rebind(Control::Fire, KeyCode::J);
+
+        let contents =
+            ron::ser::to_string_pretty(&preferences, ron::ser::PrettyConfig::default()).unwrap();
+        assert_eq!(
+            ron::de::from_str::<Preferences>(&contents).unwrap(),
+            preferences
+        );
+    }
+
+    #[test]
+    fn missing_fields_keep_their_defaults() {
+        let preferences: Preferences =
+            ron::de::from_str("(vsync: false, audio: (sfx: 0.2))").unwrap();
+
+        assert!(!preferences.vsync);
+        assert_eq!(preferences.audio.sfx, 0.2);
+        assert_eq!(preferences.audio.master, AudioSettings::default().master);
+        assert_eq!(preferences.keys, KeyBindings::default());
+    }
+
+    #[test]
+    fn rebinding_a_key_takes_it_from_other_controls() {
+        let mut keys = KeyBindings::default();
+        keys.rebind(Control::Thrust, KeyCode::Space);
+
+        assert_eq!(keys.thrust, vec![KeyCode::Space]);
+        assert!(keys.fire.is_empty());
+    }
+
+    #[test]
+    fn applying_keys_keeps_mouse_and_gamepad_controls() {
+        let mut keys = KeyBindings::default();
+        keys.rebind(Control::Fire, KeyCode::J);
+        let mut bindings = InputBindings::default();
+        keys.apply(&mut bindings);
+
+        assert_eq!(
+            bindings.buttons[&Action::Fire],
+            vec![
+                InputSource::Key(KeyCode::J),
+                InputSource::Mouse(MouseButton::Left),
+                InputSource::GamepadButton(GamepadButtonType::South),
+            ]
+        );
+        assert_eq!(
+            bindings.buttons[&Action::Confirm],
+            InputBindings::default().buttons[&Action::Confirm]
+        );
+    }
+
+    #[test]
+    fn the_first_local_player_gets_the_keys_the_others_leave_free() {
+        let mut bindings = LocalBindings::default();
+        KeyBindings::default().apply_local(&mut bindings);
+        assert_eq!(
+            bindings.players[0].buttons,
+            LocalBindings::default().players[0].buttons
+        );
+
+        let mut keys = KeyBindings::default();
+        keys.rebind(Control::Fire, KeyCode::J);
+        keys.rebind(Control::Thrust, KeyCode::Up);
+        keys.apply_local(&mut bindings);
+
+        let [first, second] = &bindings.players[..] else {
+            panic!("two players");
+        };
+        assert_eq!(
+            first.buttons[&Action::Fire],
+            vec![
+                InputSource::Key(KeyCode::J),
+                InputSource::Mouse(MouseButton::Left),
+                InputSource::GamepadButton(GamepadButtonType::South),
+            ]
+        );
+        // Up is the second player's thrust, so the first is left with the gamepad and mouse
+        assert!(!first.buttons[&Action::Thrust]
+            .iter()
+            .any(|source| matches!(source, InputSource::Key(_))));
+        assert!(second.buttons[&Action::Thrust].contains(&InputSource::Key(KeyCode::Up)));
+    }
+
+    #[test]
+    fn load_falls_back_to_the_defaults() {
+        let dir = std::env::temp_dir().join(format!("asteroids-settings-{}", std::process::id()));
+        let path = dir.join("settings.ron");
+
+        assert_eq!(Preferences::load(&path), Preferences::default());
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, "garbage").unwrap();
+        assert_eq!(Preferences::load(&path), Preferences::default());
+
+        let preferences = Preferences {
+            screen_shake: false,
+            ..default()
+        };
+        preferences.save(&path).unwrap();
+        assert_eq!(Preferences::load(&path), preferences);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::action::bindings::{InputBindings, LocalBindings};
+use crate::audio::settings::AudioSettings;
+use crate::camera::fit::ScreenFit;
+use crate::camera::shake::ScreenShake;
+use crate::global::palette::Palette;
+use crate::settings::preferences::Preferences;
+
+/// Passes the preferences on to the parts of the game they control.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_preferences(
+    preferences: Res<Preferences>,
+    mut audio: ResMut<AudioSettings>,
+    mut palette: ResMut<Palette>,
+    mut bindings: ResMut<InputBindings>,
+    mut local_bindings: ResMut<LocalBindings>,
+    mut shake: ResMut<ScreenShake>,
+    mut screen_fit: ResMut<ScreenFit>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    *audio = preferences.audio;
+    *palette = preferences.palette;
+    preferences.keys.apply(&mut bindings);
+    preferences.keys.apply_local(&mut local_bindings);
+
+    shake.enabled = preferences.screen_shake;
+    *screen_fit = preferences.screen_fit;
+
+    for mut window in window_query.iter_mut() {
+        // Only touch the window when something changed, as setting these makes it reconfigure
+        if window.mode != preferences.window_mode() {
+            window.mode = preferences.window_mode();
+        }
+        if window.present_mode != preferences.present_mode() {
+            window.present_mode = preferences.present_mode();
+        }
+    }
+}
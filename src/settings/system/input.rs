@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+use crate::action::state::{Action, ActionState};
+use crate::camera::fit::ScreenFit;
+use crate::global::palette::Palette;
+use crate::global::state::AppState;
+use crate::global::ui::ButtonClicked;
+use crate::settings::components::{Rebinding, SettingsButton};
+use crate::settings::plugin::{PreferencesPath, SettingsReturn};
+use crate::settings::preferences::{DisplayMode, Preferences};
+
+/// How much each click on a volume button turns it up, before it wraps back round to silent.
+const VOLUME_STEP: f32 = 0.1;
+
+pub fn button_listener(
+    mut clicked_reader: EventReader<ButtonClicked<SettingsButton>>,
+    mut preferences: ResMut<Preferences>,
+    mut rebinding: ResMut<Rebinding>,
+    path: Res<PreferencesPath>,
+    settings_return: Res<SettingsReturn>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for ButtonClicked(button) in clicked_reader.iter() {
+        // Clicking anything else gives up on waiting for a key
+        rebinding.0 = None;
+
+        match button {
+            SettingsButton::MasterVolume => step_volume(&mut preferences.audio.master),
+            SettingsButton::SfxVolume => step_volume(&mut preferences.audio.sfx),
+            SettingsButton::MusicVolume => step_volume(&mut preferences.audio.music),
+            SettingsButton::Display => {
+                preferences.display = match preferences.display {
+                    DisplayMode::Windowed => DisplayMode::Fullscreen,
+                    DisplayMode::Fullscreen => DisplayMode::Windowed,
+                }
+            }
+            SettingsButton::ScreenFit => {
+                preferences.screen_fit = match preferences.screen_fit {
+                    ScreenFit::Letterbox => ScreenFit::Expand,
+                    ScreenFit::Expand => ScreenFit::Letterbox,
+                }
+            }
+            SettingsButton::VSync => preferences.vsync = !preferences.vsync,
+            SettingsButton::ScreenShake => preferences.screen_shake = !preferences.screen_shake,
+            SettingsButton::Palette => {
+                preferences.palette = match preferences.palette {
+                    Palette::Standard => Palette::Colorblind,
+                    Palette::Colorblind => Palette::Standard,
+                }
+            }
+            SettingsButton::Bind(control) => rebinding.0 = Some(*control),
+            SettingsButton::Back => leave(&preferences, &path, &settings_return, &mut next_state),
+        }
+    }
+}
+
+pub fn back_listener(
+    actions: Res<ActionState>,
+    preferences: Res<Preferences>,
+    rebinding: Res<Rebinding>,
+    path: Res<PreferencesPath>,
+    settings_return: Res<SettingsReturn>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    // While waiting for a key, the pause key is being bound rather than pressed
+    if rebinding.0.is_none() && actions.just_pressed(Action::Pause) {
+        leave(&preferences, &path, &settings_return, &mut next_state);
+    }
+}
+
+/// Binds the next key pressed to the control waiting for one.
+pub fn capture_key(
+    keyboard_input: Option<Res<Input<KeyCode>>>,
+    mut preferences: ResMut<Preferences>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    let Some(control) = rebinding.0 else {
+        return;
+    };
+    let Some(key) = keyboard_input.and_then(|input| input.get_just_pressed().next().copied())
+    else {
+        return;
+    };
+
+    preferences.keys.rebind(control, key);
+    rebinding.0 = None;
+}
+
+fn step_volume(volume: &mut f32) {
+    let steps = (*volume / VOLUME_STEP).round() + 1.0;
+    *volume = if steps * VOLUME_STEP > 1.0 + f32::EPSILON {
+        0.0
+    } else {
+        steps * VOLUME_STEP
+    };
+}
+
+fn leave(
+    preferences: &Preferences,
+    path: &PreferencesPath,
+    settings_return: &SettingsReturn,
+    next_state: &mut NextState<AppState>,
+) {
+    if let Some(path) = &path.0 {
+        if let Err(error) = preferences.save(path) {
+            warn!("Failed to save settings to {}: {}", path.display(), error);
+        }
+    }
+    next_state.set(settings_return.0);
+}
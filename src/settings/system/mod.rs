@@ -0,0 +1,3 @@
+pub mod apply;
+pub mod input;
+pub mod render;
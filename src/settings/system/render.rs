@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+
+use crate::global::component::GameObject;
+use crate::global::ui::{spawn_button_with_look, ButtonLook};
+use crate::settings::components::{Rebinding, SettingsButton, SettingsMenu};
+use crate::settings::preferences::Preferences;
+
+pub fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    preferences: Res<Preferences>,
+    rebinding: Res<Rebinding>,
+) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            // Opaque, as the settings can be opened over a paused game
+            background_color: Color::rgb(0.1, 0.1, 0.1).into(),
+            z_index: ZIndex::Global(2),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn(
+                    TextBundle::from_section(
+                        "Settings",
+                        TextStyle {
+                            font: asset_server.load("fonts/ExcludedItalic.ttf"),
+                            font_size: 60.0,
+                            color: Color::WHITE,
+                        },
+                    )
+                    .with_style(Style {
+                        margin: UiRect::bottom(Val::Px(20.0)),
+                        ..default()
+                    }),
+                )
+                .insert(GameObject);
+
+            for button in SettingsButton::all() {
+                spawn_button_with_look(
+                    parent,
+                    &asset_server,
+                    &button.label(&preferences, rebinding.0),
+                    button,
+                    ButtonLook::COMPACT,
+                );
+            }
+        })
+        .insert(SettingsMenu);
+}
+
+/// Keeps every button's label showing the current value of its setting.
+pub fn update_labels(
+    preferences: Res<Preferences>,
+    rebinding: Res<Rebinding>,
+    button_query: Query<(&SettingsButton, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !preferences.is_changed() && !rebinding.is_changed() {
+        return;
+    }
+
+    for (button, children) in button_query.iter() {
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.sections[0].value = button.label(&preferences, rebinding.0);
+        }
+    }
+}
+
+pub fn close(mut commands: Commands, menu_query: Query<Entity, With<SettingsMenu>>) {
+    for entity in menu_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
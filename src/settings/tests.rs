@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+
+use crate::action::bindings::{InputBindings, InputSource, LocalBindings};
+use crate::action::state::Action;
+use crate::audio::settings::AudioSettings;
+use crate::camera::shake::ScreenShake;
+use crate::game::components::Player;
+use crate::global::palette::Palette;
+use crate::global::state::AppState;
+use crate::global::ui::ButtonClicked;
+use crate::testing::Harness;
+
+use super::components::{SettingsButton, SettingsMenu};
+use super::plugin::{SettingsPlugin, SettingsReturn};
+use super::preferences::{Control, Preferences};
+
+const SEED: u64 = 3;
+
+fn harness() -> Harness {
+    Harness::build(SEED, |app| {
+        app.init_resource::<Input<KeyCode>>()
+            .init_resource::<Gamepads>()
+            .init_resource::<Input<GamepadButton>>()
+            .init_resource::<Axis<GamepadAxis>>()
+            .add_event::<CursorMoved>()
+            .add_plugin(SettingsPlugin {
+                preferences: Preferences::default(),
+                // Nothing is saved, so the tests leave no files behind
+                path: None,
+            });
+    })
+}
+
+fn click(harness: &mut Harness, button: SettingsButton) {
+    harness.app.world.send_event(ButtonClicked(button));
+    harness.step();
+}
+
+#[test]
+fn changes_are_passed_on_to_the_game() {
+    let mut harness = harness();
+    harness.enter(AppState::Settings);
+
+    click(&mut harness, SettingsButton::SfxVolume);
+    click(&mut harness, SettingsButton::Palette);
+    click(&mut harness, SettingsButton::ScreenShake);
+    harness.step();
+
+    let world = &harness.app.world;
+    assert!((world.resource::<AudioSettings>().sfx - 0.9).abs() < 1e-5);
+    assert_eq!(*world.resource::<Palette>(), Palette::Colorblind);
+    assert!(!world.resource::<ScreenShake>().enabled);
+}
+
+#[test]
+fn the_next_key_pressed_is_bound_to_the_control() {
+    let mut harness = harness();
+    harness.enter(AppState::Settings);
+
+    click(&mut harness, SettingsButton::Bind(Control::Fire));
+    harness
+        .app
+        .world
+        .resource_mut::<Input<KeyCode>>()
+        .press(KeyCode::J);
+    harness.step();
+    harness.step();
+
+    let fire = &harness.app.world.resource::<InputBindings>().buttons[&Action::Fire];
+    assert!(fire.contains(&InputSource::Key(KeyCode::J)));
+    assert!(!fire.contains(&InputSource::Key(KeyCode::Space)));
+    assert!(fire.contains(&InputSource::Mouse(MouseButton::Left)));
+
+    // The first player of a game on one screen gets it too
+    let local_fire =
+        &harness.app.world.resource::<LocalBindings>().players[0].buttons[&Action::Fire];
+    assert!(local_fire.contains(&InputSource::Key(KeyCode::J)));
+    assert!(!local_fire.contains(&InputSource::Key(KeyCode::Space)));
+}
+
+#[test]
+fn back_returns_to_the_paused_game() {
+    let mut harness = harness();
+    harness.start_game();
+    harness.enter(AppState::Paused);
+    harness
+        .app
+        .insert_resource(SettingsReturn(AppState::Paused));
+    harness.enter(AppState::Settings);
+    assert_eq!(harness.count::<SettingsMenu>(), 1);
+
+    click(&mut harness, SettingsButton::Back);
+    harness.step();
+
+    assert_eq!(harness.state(), AppState::Paused);
+    assert_eq!(harness.count::<SettingsMenu>(), 0);
+    assert_eq!(harness.count::<Player>(), 1);
+}
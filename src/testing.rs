@@ -0,0 +1,199 @@
+//! A windowless build of the game for tests, stepped one fixed tick at a time.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy::utils::Instant;
+
+use crate::action::state::ActionState;
+use crate::config::game_config::GameConfig;
+use crate::game::collider::Collider;
+use crate::game::components::{
+    Asteroid, AsteroidSize, GameMode, Player, PlayerId, PowerUp, PowerUpKind, Saucer, SaucerSize,
+    SeedConfig,
+};
+use crate::game::plugin::GameVisualsPlugin;
+use crate::global::component::{GameObject, Scoreboard};
+use crate::global::state::AppState;
+use crate::plugins::AsteroidsPlugins;
+
+/// Runs the gameplay plugins under `MinimalPlugins`, with input injected through
+/// [`ActionState`] instead of real devices.
+pub struct Harness {
+    pub app: App,
+    start: Instant,
+    period: Duration,
+    frames: u32,
+}
+
+impl Harness {
+    pub fn new(seed: u64) -> Harness {
+        Harness::build(seed, |_| {})
+    }
+
+    /// Builds the harness, letting `configure` add plugins and resources before the first update.
+    pub fn build(seed: u64, configure: impl FnOnce(&mut App)) -> Harness {
+        Harness::build_headless(seed, |app| {
+            app.add_plugin(AssetPlugin::default())
+                .add_plugin(GameVisualsPlugin);
+            configure(app);
+        })
+    }
+
+    /// Like [`Harness::build`], but with only the simulation: no assets, sprites or HUD.
+    pub fn build_headless(seed: u64, configure: impl FnOnce(&mut App)) -> Harness {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<Input<MouseButton>>()
+            .add_event::<ReceivedCharacter>()
+            .insert_resource(SeedConfig {
+                fixed_seed: Some(seed),
+            })
+            .add_plugins(AsteroidsPlugins::headless());
+        configure(&mut app);
+
+        let period = app.world.resource::<FixedTime>().period;
+        let mut harness = Harness {
+            app,
+            start: Instant::now(),
+            period,
+            frames: 0,
+        };
+        // The first update only starts the clock, so no tick runs in it
+        harness.step();
+        harness
+    }
+
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// How many frames have been stepped, including the initial one.
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// Runs one frame, advancing the clock by exactly one tick.
+    pub fn step(&mut self) {
+        self.app.insert_resource(TimeUpdateStrategy::ManualInstant(
+            self.start + self.period * self.frames,
+        ));
+        self.app.update();
+        self.frames += 1;
+    }
+
+    pub fn run_ticks(&mut self, ticks: u32) {
+        for _ in 0..ticks {
+            self.step();
+        }
+    }
+
+    /// Leaves the main menu and runs the first tick of a new game.
+    pub fn start_game(&mut self) {
+        self.enter(AppState::InGame);
+    }
+
+    /// Like [`Harness::start_game`], but with the players of `mode`.
+    pub fn start_game_in(&mut self, mode: GameMode) {
+        self.app.insert_resource(mode);
+        self.start_game();
+    }
+
+    /// Switches to `state` and steps a frame, so the transition has happened on return.
+    pub fn enter(&mut self, state: AppState) {
+        self.app
+            .world
+            .resource_mut::<NextState<AppState>>()
+            .set(state);
+        self.step();
+    }
+
+    pub fn actions(&mut self) -> Mut<'_, ActionState> {
+        self.app.world.resource_mut::<ActionState>()
+    }
+
+    pub fn config(&self) -> &GameConfig {
+        self.app.world.resource::<GameConfig>()
+    }
+
+    pub fn state(&self) -> AppState {
+        self.app.world.resource::<State<AppState>>().0
+    }
+
+    /// Everyone's score added together.
+    pub fn score(&self) -> usize {
+        self.app.world.resource::<Scoreboard>().total()
+    }
+
+    /// The first player's ship.
+    pub fn player(&mut self) -> Mut<'_, Player> {
+        self.player_with(PlayerId(0))
+    }
+
+    /// The ship of player `id`, which must still be in the game.
+    pub fn player_with(&mut self, id: PlayerId) -> Mut<'_, Player> {
+        let mut player_query = self.app.world.query::<&mut Player>();
+        player_query
+            .iter_mut(&mut self.app.world)
+            .find(|player| player.id == id)
+            .expect("no such player")
+    }
+
+    /// Places a motionless asteroid, without a sprite.
+    pub fn spawn_asteroid(&mut self, size: AsteroidSize, translation: Vec2) -> Entity {
+        let radius = self.config().asteroid.extent(size) / 2.0;
+        self.app
+            .world
+            .spawn((
+                SpatialBundle::from_transform(Transform::from_translation(translation.extend(0.0))),
+                Asteroid {
+                    size,
+                    trajectory: Vec2::ZERO,
+                    speed: 0.0,
+                    rotation: 0.0,
+                },
+                Collider::circle(radius),
+                GameObject,
+            ))
+            .id()
+    }
+
+    /// Places a motionless power-up, without a sprite.
+    pub fn spawn_power_up(&mut self, kind: PowerUpKind, translation: Vec2) -> Entity {
+        let power_up_config = &self.config().power_up;
+        let (lifetime, size) = (power_up_config.lifetime, power_up_config.size);
+        self.app
+            .world
+            .spawn((
+                SpatialBundle::from_transform(Transform::from_translation(translation.extend(0.0))),
+                Sprite::default(),
+                PowerUp::new(kind, Vec2::ZERO, lifetime),
+                Collider::rect(Vec2::splat(size)),
+                GameObject,
+            ))
+            .id()
+    }
+
+    /// Places a saucer flying in `direction` (0 to hold its position), without a sprite.
+    pub fn spawn_saucer(&mut self, size: SaucerSize, translation: Vec2, direction: f32) -> Entity {
+        self.app
+            .world
+            .spawn((
+                SpatialBundle::from_transform(Transform::from_translation(translation.extend(0.0))),
+                Saucer::new(size, direction, translation.y),
+                Collider::rect(size.extent()),
+                GameObject,
+            ))
+            .id()
+    }
+
+    /// Counts the entities with component `T`.
+    pub fn count<T: Component>(&mut self) -> usize {
+        self.app.world.query::<&T>().iter(&self.app.world).count()
+    }
+
+    pub fn exists(&self, entity: Entity) -> bool {
+        self.app.world.get_entity(entity).is_some()
+    }
+}
@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+
+use crate::config::game_config::VersusConfig;
+use crate::game::components::PlayerId;
+
+/// A versus match in progress, kept from one round to the next.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct VersusMatch {
+    pub best_of: u32,
+    pub rounds_to_win: u32,
+    /// Rounds won by each player, in player order.
+    pub wins: Vec<u32>,
+    /// Who won each round played so far. A round where both ships went down together is a
+    /// draw, which is played again rather than counted, so the match stays best of `best_of`.
+    pub rounds: Vec<PlayerId>,
+    /// Whether the last round played was a draw.
+    pub drawn: bool,
+}
+
+impl VersusMatch {
+    pub fn new(config: &VersusConfig, players: usize) -> VersusMatch {
+        VersusMatch {
+            best_of: config.best_of,
+            rounds_to_win: config.rounds_to_win(),
+            wins: vec![0; players],
+            rounds: Vec::new(),
+            drawn: false,
+        }
+    }
+
+    pub fn record(&mut self, winner: Option<PlayerId>) {
+        self.drawn = winner.is_none();
+        if let Some(winner) = winner {
+            if let Some(wins) = self.wins.get_mut(winner.0) {
+                *wins += 1;
+            }
+            self.rounds.push(winner);
+        }
+    }
+
+    /// The number of the round just played, counting from 1. A draw shares its number with
+    /// the round that replays it.
+    pub fn round(&self) -> usize {
+        if self.drawn {
+            self.rounds.len() + 1
+        } else {
+            self.rounds.len()
+        }
+    }
+
+    /// The player who has won the match, once someone has.
+    pub fn winner(&self) -> Option<PlayerId> {
+        self.wins
+            .iter()
+            .position(|wins| *wins >= self.rounds_to_win)
+            .map(PlayerId)
+    }
+}
+
+/// The column of text on the round results screen.
+#[derive(Component)]
+pub struct RoundResultsScreen;
+
+/// Counts down until the round results screen can be left, so a player still firing as the
+/// round ends doesn't skip straight past it.
+#[derive(Resource)]
+pub struct ResultsDelay(pub Timer);
@@ -0,0 +1,6 @@
+pub mod components;
+pub mod plugin;
+
+mod system;
+#[cfg(test)]
+mod tests;
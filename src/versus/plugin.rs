@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+
+use crate::game::plugin::is_new_game;
+use crate::global::state::AppState;
+use crate::global::system::clear_game_objects;
+
+use super::components::VersusMatch;
+use super::system::*;
+
+/// Plays versus games as matches of several rounds, keeping the score from one to the next.
+///
+/// Each round ends in [`AppState::RoundResults`], which something has to leave for the next
+/// round: the [`RoundResultsPlugin`] screen, or the app itself.
+pub struct VersusPlugin;
+
+/// The results screen shown after each versus round, which starts the next one when clicked.
+pub struct RoundResultsPlugin;
+
+impl Plugin for VersusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            start_match
+                .run_if(is_versus)
+                .run_if(not(resource_exists::<VersusMatch>()))
+                .run_if(is_new_game)
+                .in_schedule(OnEnter(AppState::InGame)),
+        )
+        // The ships are needed to tell who won, so they are only cleared once that's done
+        .add_systems(
+            (record_round, clear_game_objects)
+                .chain()
+                .in_schedule(OnEnter(AppState::RoundResults)),
+        )
+        .add_systems((clear_game_objects, finish_match).in_schedule(OnExit(AppState::RoundResults)))
+        // Leaving for the main menu abandons the match
+        .add_system(end_match.in_schedule(OnEnter(AppState::MainMenu)));
+    }
+}
+
+impl Plugin for RoundResultsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            show_round_results
+                .after(record_round)
+                .in_schedule(OnEnter(AppState::RoundResults)),
+        )
+        .add_system(next_round_listener.in_set(OnUpdate(AppState::RoundResults)));
+    }
+}
@@ -0,0 +1,155 @@
+use bevy::prelude::*;
+
+use crate::action::state::{Action, ActionState};
+use crate::config::game_config::GameConfig;
+use crate::game::components::{GameMode, Player, PlayerId};
+use crate::global::{
+    component::GameObject, constants::SCOREBOARD_FONT_SIZE, palette::Palette, state::AppState,
+    ui::spawn_line,
+};
+
+use super::components::*;
+
+/// Seconds the round results stay up before they can be left.
+const RESULTS_DELAY: f32 = 0.75;
+
+pub fn is_versus(mode: Res<GameMode>) -> bool {
+    *mode == GameMode::Versus
+}
+
+pub fn start_match(mut commands: Commands, config: Res<GameConfig>, mode: Res<GameMode>) {
+    commands.insert_resource(VersusMatch::new(&config.versus, mode.players()));
+}
+
+pub fn end_match(mut commands: Commands) {
+    commands.remove_resource::<VersusMatch>();
+}
+
+/// Once a match has been won, the next round starts a new one.
+pub fn finish_match(mut commands: Commands, versus_match: Res<VersusMatch>) {
+    if versus_match.winner().is_some() {
+        commands.remove_resource::<VersusMatch>();
+    }
+}
+
+/// Gives the round to whoever still has lives left. If nobody does, it's a draw.
+pub fn record_round(mut versus_match: ResMut<VersusMatch>, player_query: Query<&Player>) {
+    let mut survivors = player_query.iter().filter(|player| player.lives > 0);
+    let winner = match (survivors.next(), survivors.next()) {
+        (Some(player), None) => Some(player.id),
+        _ => None,
+    };
+    versus_match.record(winner);
+}
+
+pub fn show_round_results(
+    mut commands: Commands,
+    versus_match: Res<VersusMatch>,
+    palette: Res<Palette>,
+    asset_server: Res<AssetServer>,
+) {
+    let font: Handle<Font> = asset_server.load("fonts/Excluded.ttf");
+    let italic_font: Handle<Font> = asset_server.load("fonts/ExcludedItalic.ttf");
+    let round = versus_match.round();
+    let round_winner = versus_match
+        .rounds
+        .last()
+        .copied()
+        .filter(|_| !versus_match.drawn);
+
+    let (title, color) = match (versus_match.winner(), round_winner) {
+        (Some(winner), _) => (
+            format!("{} wins the match!", winner.label()),
+            winner.tint(*palette),
+        ),
+        (None, Some(winner)) => (
+            format!("{} takes round {}", winner.label(), round),
+            winner.tint(*palette),
+        ),
+        (None, None) => (format!("Round {} is a draw", round), Color::WHITE),
+    };
+    commands.insert_resource(ResultsDelay(Timer::from_seconds(
+        RESULTS_DELAY,
+        TimerMode::Once,
+    )));
+
+    let prompt = match versus_match.winner() {
+        Some(_) => "Click anywhere to play again.",
+        None => "Click anywhere to start the next round.",
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            spawn_line(
+                parent,
+                title,
+                TextStyle {
+                    font: italic_font,
+                    font_size: 60.0,
+                    color,
+                },
+            );
+            for (player, wins) in versus_match.wins.iter().enumerate() {
+                let player = PlayerId(player);
+                spawn_line(
+                    parent,
+                    format!("{}: {}", player.label(), wins),
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: SCOREBOARD_FONT_SIZE,
+                        color: player.tint(*palette),
+                    },
+                );
+            }
+            spawn_line(
+                parent,
+                format!(
+                    "Best of {}: first to {} rounds",
+                    versus_match.best_of, versus_match.rounds_to_win
+                ),
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 24.0,
+                    color: Color::GRAY,
+                },
+            );
+            spawn_line(
+                parent,
+                prompt,
+                TextStyle {
+                    font,
+                    font_size: 24.0,
+                    color: Color::WHITE,
+                },
+            );
+        })
+        .insert(RoundResultsScreen)
+        .insert(GameObject);
+}
+
+/// Starts the next round, or a new match once this one has been won.
+pub fn next_round_listener(
+    time: Res<Time>,
+    mut delay: ResMut<ResultsDelay>,
+    buttons: Res<Input<MouseButton>>,
+    actions: Res<ActionState>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    // Fire and confirm share a gamepad button, and the mouse fires as well as clicks
+    if !delay.0.tick(time.delta()).finished() {
+        return;
+    }
+    if buttons.just_pressed(MouseButton::Left) || actions.just_pressed(Action::Confirm) {
+        next_state.set(AppState::InGame);
+    }
+}
@@ -0,0 +1,153 @@
+use bevy::prelude::*;
+
+use crate::action::state::Action;
+use crate::game::components::{AsteroidSize, GameMode, Player, PlayerId};
+use crate::global::state::AppState;
+use crate::testing::Harness;
+
+use super::components::{RoundResultsScreen, VersusMatch};
+use super::plugin::RoundResultsPlugin;
+
+const SEED: u64 = 11;
+
+fn harness() -> Harness {
+    Harness::build(SEED, |app| {
+        app.add_plugin(RoundResultsPlugin);
+    })
+}
+
+/// Takes the last life of player `id` by dropping an asteroid on their ship.
+fn knock_out(harness: &mut Harness, id: PlayerId) {
+    let mut player_query = harness.app.world.query::<(&Transform, &mut Player)>();
+    let (transform, mut player) = player_query
+        .iter_mut(&mut harness.app.world)
+        .find(|(_, player)| player.id == id)
+        .unwrap();
+    player.lives = 1;
+    let translation = transform.translation.truncate();
+    harness.spawn_asteroid(AsteroidSize::Small, translation);
+    harness.step();
+    harness.step();
+}
+
+/// Waits until the results can be left, then leaves them.
+fn confirm(harness: &mut Harness) {
+    harness.run_ticks(60);
+    harness.actions().press(Action::Confirm);
+    harness.step();
+    harness.actions().release(Action::Confirm);
+    harness.step();
+}
+
+fn wins(harness: &Harness) -> Vec<u32> {
+    harness.app.world.resource::<VersusMatch>().wins.clone()
+}
+
+#[test]
+fn a_round_ends_as_soon_as_one_player_is_out() {
+    let mut harness = harness();
+    harness.start_game_in(GameMode::Versus);
+
+    knock_out(&mut harness, PlayerId(0));
+
+    assert_eq!(harness.state(), AppState::RoundResults);
+    assert_eq!(wins(&harness), vec![0, 1]);
+    assert_eq!(harness.count::<RoundResultsScreen>(), 1);
+    assert_eq!(harness.count::<Player>(), 0);
+
+    // The next round starts afresh, with the match score kept
+    confirm(&mut harness);
+    assert_eq!(harness.state(), AppState::InGame);
+    assert_eq!(harness.count::<Player>(), 2);
+    let lives = harness.config().player.lives;
+    assert_eq!(harness.player().lives, lives);
+    assert_eq!(wins(&harness), vec![0, 1]);
+}
+
+#[test]
+fn the_results_stay_up_through_a_confirm_pressed_as_the_round_ends() {
+    let mut harness = harness();
+    harness.start_game_in(GameMode::Versus);
+
+    knock_out(&mut harness, PlayerId(0));
+    harness.actions().press(Action::Confirm);
+    harness.step();
+    harness.actions().release(Action::Confirm);
+    harness.step();
+    assert_eq!(harness.state(), AppState::RoundResults);
+
+    confirm(&mut harness);
+    assert_eq!(harness.state(), AppState::InGame);
+}
+
+#[test]
+fn a_drawn_round_is_played_again_without_counting() {
+    let mut harness = harness();
+    harness.start_game_in(GameMode::Versus);
+
+    // Both ships go down on the same tick
+    let mut player_query = harness.app.world.query::<(&Transform, &mut Player)>();
+    let translations: Vec<Vec2> = player_query
+        .iter_mut(&mut harness.app.world)
+        .map(|(transform, mut player)| {
+            player.lives = 1;
+            transform.translation.truncate()
+        })
+        .collect();
+    for translation in translations {
+        harness.spawn_asteroid(AsteroidSize::Small, translation);
+    }
+    harness.step();
+    harness.step();
+
+    assert_eq!(harness.state(), AppState::RoundResults);
+    let versus_match = harness.app.world.resource::<VersusMatch>();
+    assert!(versus_match.drawn);
+    assert_eq!(versus_match.round(), 1);
+    assert!(versus_match.rounds.is_empty());
+    assert_eq!(wins(&harness), vec![0, 0]);
+
+    // The replay is still round one, so the match can't run past its best of
+    confirm(&mut harness);
+    knock_out(&mut harness, PlayerId(1));
+    let versus_match = harness.app.world.resource::<VersusMatch>();
+    assert!(!versus_match.drawn);
+    assert_eq!(versus_match.round(), 1);
+    assert_eq!(versus_match.rounds, vec![PlayerId(0)]);
+}
+
+#[test]
+fn the_first_to_win_most_rounds_takes_the_match() {
+    let mut harness = harness();
+    harness.start_game_in(GameMode::Versus);
+    let best_of = harness.config().versus.best_of;
+    assert_eq!(best_of, 3);
+
+    knock_out(&mut harness, PlayerId(1));
+    confirm(&mut harness);
+    knock_out(&mut harness, PlayerId(1));
+
+    let versus_match = harness.app.world.resource::<VersusMatch>();
+    assert_eq!(versus_match.winner(), Some(PlayerId(0)));
+    assert_eq!(versus_match.rounds, vec![PlayerId(0); 2]);
+
+    // Playing again starts a new match
+    confirm(&mut harness);
+    assert_eq!(harness.state(), AppState::InGame);
+    assert_eq!(wins(&harness), vec![0, 0]);
+}
+
+#[test]
+fn matches_are_kept_without_the_results_screen() {
+    let mut harness = Harness::build_headless(SEED, |_| {});
+    harness.start_game_in(GameMode::Versus);
+
+    knock_out(&mut harness, PlayerId(0));
+    assert_eq!(harness.state(), AppState::RoundResults);
+    assert_eq!(harness.count::<Player>(), 0);
+
+    // Without the screen, it's up to the app to start the next round
+    harness.enter(AppState::InGame);
+    assert_eq!(harness.count::<Player>(), 2);
+    assert_eq!(wins(&harness), vec![0, 1]);
+}
@@ -0,0 +1,75 @@
+//! Runs the game inside an app of its own, through the library's public API only, the way
+//! another crate would embed it.
+
+use std::time::Duration;
+
+use asteroids::prelude::*;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy::utils::Instant;
+
+/// A windowless app with only the simulation, stepped a tick at a time.
+struct Game {
+    app: App,
+    start: Instant,
+    period: Duration,
+    frames: u32,
+}
+
+impl Game {
+    fn new(seed: u64) -> Game {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(SeedConfig {
+                fixed_seed: Some(seed),
+            })
+            .add_plugins(AsteroidsPlugins::headless());
+        let period = app.world.resource::<FixedTime>().period;
+        let mut game = Game {
+            app,
+            start: Instant::now(),
+            period,
+            frames: 0,
+        };
+        game.step();
+        game
+    }
+
+    fn step(&mut self) {
+        self.app.insert_resource(TimeUpdateStrategy::ManualInstant(
+            self.start + self.period * self.frames,
+        ));
+        self.app.update();
+        self.frames += 1;
+    }
+
+    fn count<T: Component>(&mut self) -> usize {
+        self.app.world.query::<&T>().iter(&self.app.world).count()
+    }
+}
+
+#[test]
+fn a_headless_game_plays_without_menus_or_a_window() {
+    let mut game = Game::new(3);
+    // Nothing leaves the main menu without the menus, so the app does it itself
+    game.app
+        .world
+        .resource_mut::<NextState<AppState>>()
+        .set(AppState::InGame);
+    game.step();
+    assert_eq!(game.count::<Player>(), 1);
+
+    game.app
+        .world
+        .resource_mut::<ActionState>()
+        .player_mut(0)
+        .press(Action::Fire);
+    game.step();
+    assert_eq!(game.count::<Laser>(), 1);
+
+    for _ in 0..600 {
+        game.step();
+    }
+    assert!(game.count::<Asteroid>() > 0);
+    assert_eq!(game.app.world.resource::<Scoreboard>().scores.len(), 1);
+}